@@ -0,0 +1,73 @@
+// Throughput of the amortization engine on 30-year loans, the shape batch
+// files and the comparison table spend their time in. Run with
+// `cargo bench`; Criterion keeps earlier runs in target/criterion to compare
+// against.
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rust_decimal_macros::dec;
+
+use loancalc::config::DEFAULT_CONFIG;
+use loancalc::conventions::{Rounding, RoundingMode};
+use loancalc::dates::{Calendar, PaymentDay};
+use loancalc::interest::InterestMethod;
+use loancalc::schedule::PaymentStrategy;
+use loancalc::{exact_amortization, monthly_payment, LoanCalculator};
+
+fn payment_formulas(c: &mut Criterion) {
+    c.bench_function("monthly_payment 360", |b| {
+        b.iter(|| monthly_payment(black_box(dec!(350000)), black_box(dec!(6.5)), black_box(360)))
+    });
+    c.bench_function("exact_amortization 360", |b| {
+        b.iter(|| exact_amortization(black_box(dec!(350000)), black_box(dec!(6.5)), 360, Rounding::cents(RoundingMode::HalfUp)))
+    });
+}
+
+fn schedules(c: &mut Criterion) {
+    let calculator = LoanCalculator::from_config(DEFAULT_CONFIG).unwrap();
+    c.bench_function("generate_schedule 360", |b| {
+        b.iter(|| calculator.generate_schedule(black_box(dec!(350000)), dec!(6.5), 360, &PaymentStrategy::Standard))
+    });
+    c.bench_function("schedule_rows 360 total interest", |b| {
+        b.iter(|| {
+            calculator
+                .schedule_rows(black_box(dec!(350000)), dec!(6.5), 360, &PaymentStrategy::Standard)
+                .map(|row| row.interest)
+                .sum::<rust_decimal::Decimal>()
+        })
+    });
+    c.bench_function("generate_schedule 360 extra monthly", |b| {
+        let strategy = PaymentStrategy::ExtraMonthly { amount: dec!(200) };
+        b.iter(|| calculator.generate_schedule(black_box(dec!(350000)), dec!(6.5), 360, &strategy))
+    });
+}
+
+// A batch of 100 distinct loans quoted three ways, each on a fresh calculator
+// so the quote cache is cold the way it is for a new batch file
+fn batch_quotes(c: &mut Criterion) {
+    let calendar = Calendar {
+        period_start: chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+        payment_day: PaymentDay::Day(1),
+        holidays: None,
+    };
+    let methods = [
+        InterestMethod::Amortizing,
+        InterestMethod::SimpleDaily { days_early: 0, calendar },
+        InterestMethod::RuleOf78s { payoff_after: None },
+    ];
+    c.bench_function("batch of 100 loans x 3 methods", |b| {
+        b.iter_batched(
+            || LoanCalculator::from_config(DEFAULT_CONFIG).unwrap(),
+            |calculator| {
+                for loan in 0..100u32 {
+                    let principal = dec!(200000) + rust_decimal::Decimal::from(loan * 1000);
+                    for method in &methods {
+                        black_box(calculator.calculate_cost(method, principal, dec!(6.5), 360));
+                    }
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, payment_formulas, schedules, batch_quotes);
+criterion_main!(benches);
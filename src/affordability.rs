@@ -0,0 +1,126 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::{json, Value};
+
+use crate::interest::InterestMethod;
+use crate::provenance::Provenance;
+use crate::LoanCalculator;
+
+// Payments grow with the principal under every interest method, so the
+// largest loan a payment supports can be bisected for between nothing and
+// the payments' sum
+const SOLVER_ITERATIONS: usize = 64;
+
+// The inverse of a comparison: what each bank would lend for a payment
+pub struct AffordabilityResults<'a> {
+    pub loan_type: &'a str,
+    pub payment: Decimal,
+    pub term_months: u32,
+    pub credit_score: u16,
+    pub interest_method: &'a InterestMethod,
+    pub offers: &'a [(String, Decimal)],
+    // (bank name, reason)
+    pub declined: &'a [(String, String)],
+    pub provenance: &'a Provenance,
+}
+
+fn amount(value: Decimal) -> Value {
+    Value::String(format!("{:.2}", value.round_dp(2)))
+}
+
+impl LoanCalculator {
+    // Largest principal, to the cent, whose payment does not exceed the one
+    // given; None when even a cent cannot be quoted
+    pub fn max_principal(
+        &self,
+        method: &InterestMethod,
+        payment: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> Option<Decimal> {
+        let affordable = |principal: Decimal| {
+            self.compute_cost(method, principal, annual_rate, num_payments)
+                .is_some_and(|cost| cost.monthly_payment <= payment)
+        };
+        if payment <= dec!(0) || !affordable(dec!(0.01)) {
+            return None;
+        }
+
+        let mut low = dec!(0.01);
+        let mut high = payment * Decimal::from(num_payments);
+        for _ in 0..SOLVER_ITERATIONS {
+            if high - low <= dec!(0.01) {
+                break;
+            }
+            let middle = ((low + high) / dec!(2)).round_dp(2);
+            if affordable(middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Some(low)
+    }
+
+    pub fn affordability_document(&self, results: &AffordabilityResults) -> Value {
+        let mut banks: Vec<Value> = results
+            .offers
+            .iter()
+            .map(|(name, rate)| {
+                let principal = self.max_principal(results.interest_method, results.payment, *rate, results.term_months);
+                let cost = principal.and_then(|principal| {
+                    self.calculate_cost(results.interest_method, principal, *rate, results.term_months)
+                });
+                json!({
+                    "bank": name,
+                    "qualified": principal.is_some(),
+                    "rate": rate.round_dp(4).to_string(),
+                    "max_amount": principal.map(amount),
+                    "total_interest": cost.as_ref().map(|cost| amount(cost.total_interest)),
+                    "total_payment": cost.as_ref().map(|cost| amount(cost.total_payment)),
+                })
+            })
+            .collect();
+        banks.extend(results.declined.iter().map(|(name, reason)| {
+            json!({
+                "bank": name,
+                "qualified": false,
+                "reason": reason,
+            })
+        }));
+
+        json!({
+            "loan": {
+                "type": results.loan_type,
+                "payment": amount(results.payment),
+                "term_months": results.term_months,
+                "credit_score": results.credit_score,
+                "interest_method": results.interest_method.get_description(),
+            },
+            "banks": banks,
+            "provenance": results.provenance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_principal_is_the_largest_cent_the_payment_covers() {
+        let calculator = LoanCalculator::from_config(crate::config::DEFAULT_CONFIG).unwrap();
+        // 30 years at 6% pays 0.00599551 of the principal a month, so a
+        // 1,199.10 payment covers up to 1,199.105 / 0.00599551 = 200,000.66
+        // before it rounds up a cent
+        let principal = calculator.max_principal(&InterestMethod::Amortizing, dec!(1199.10), dec!(6), 360).unwrap();
+        assert_eq!(principal, dec!(200000.65));
+        let payment = |principal| {
+            calculator.calculate_cost(&InterestMethod::Amortizing, principal, dec!(6), 360).unwrap().monthly_payment
+        };
+        assert_eq!(payment(principal), dec!(1199.10));
+        assert_eq!(payment(principal + dec!(0.01)), dec!(1199.11));
+
+        assert_eq!(calculator.max_principal(&InterestMethod::Amortizing, dec!(0), dec!(6), 360), None);
+    }
+}
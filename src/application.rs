@@ -0,0 +1,176 @@
+use std::fs;
+use std::path::Path;
+
+use rust_decimal::Decimal;
+use serde_json::{json, Map, Value};
+
+use crate::collateral::Collateral;
+use crate::dates::Closing;
+use crate::interest::InterestMethod;
+use crate::provenance::Provenance;
+use crate::{LoanCalculator, LoanType};
+
+// The borrower's entries and the quotes they produced, in a layout modelled
+// on MISMO's DEAL structure so origination systems can map it without
+// rekeying. Containers are upper case; field names follow MISMO terms where
+// one exists. Amounts are strings, to the cent as shown in the tables.
+pub struct Application<'a> {
+    pub loan_type: &'a LoanType,
+    pub amount: Decimal,
+    pub term_months: u32,
+    pub credit_score: u16,
+    pub closing: &'a Closing,
+    pub interest_method: &'a InterestMethod,
+    pub collateral: &'a [Collateral],
+    pub offers: &'a [(String, Decimal)],
+    pub provenance: &'a Provenance,
+}
+
+fn amount(value: Decimal) -> Value {
+    Value::String(format!("{:.2}", value.round_dp(2)))
+}
+
+impl LoanCalculator {
+    pub fn application_document(&self, application: &Application) -> Value {
+        let collateral: Vec<Value> = application
+            .collateral
+            .iter()
+            .map(|item| {
+                json!({
+                    "CollateralDescription": item.description,
+                    "PropertyEstimatedValueAmount": amount(item.value),
+                })
+            })
+            .collect();
+
+        let quotes: Vec<Value> = application
+            .offers
+            .iter()
+            .filter_map(|(name, rate)| {
+                let cost = self.calculate_cost(
+                    application.interest_method,
+                    application.amount,
+                    *rate,
+                    application.term_months,
+                )?;
+                Some(json!({
+                    "LenderName": name,
+                    "NoteRatePercent": rate.round_dp(4).to_string(),
+                    "InitialPrincipalAndInterestPaymentAmount": amount(cost.monthly_payment),
+                    "TotalInterestAmount": amount(cost.total_interest),
+                    "EstimatedClosingCostsAmount": amount(self.get_closing_costs(name, application.loan_type)),
+                }))
+            })
+            .collect();
+
+        json!({
+            "MESSAGE": {
+                "ABOUT_VERSIONS": {
+                    "ABOUT_VERSION": {
+                        "CreatedDatetime": application.provenance.generated_at,
+                        "DataVersionIdentifier": application.provenance.engine_version,
+                    }
+                },
+                // MISMO leaves data outside its dictionary to EXTENSION containers
+                "EXTENSION": {
+                    "OTHER": {
+                        "PROVENANCE": application
+                            .provenance
+                            .entries()
+                            .into_iter()
+                            .map(|(name, value)| (name, Value::String(value)))
+                            .collect::<Map<String, Value>>(),
+                    }
+                },
+                "DEAL": {
+                    "COLLATERALS": { "COLLATERAL": collateral },
+                    "LOANS": {
+                        "LOAN": {
+                            "TERMS_OF_LOAN": {
+                                "LoanPurposeType": application.loan_type.get_name(),
+                                "BaseLoanAmount": amount(application.amount),
+                            },
+                            "AMORTIZATION": {
+                                "LoanAmortizationPeriodCount": application.term_months,
+                                "LoanAmortizationPeriodType": "Month",
+                                "InterestCalculationDescription": application.interest_method.get_description(),
+                            },
+                            "CLOSING_INFORMATION": {
+                                "EstimatedClosingDate": application.closing.date.to_string(),
+                                "ScheduledFirstPaymentDate": application.closing.first_payment_date().to_string(),
+                                "PaymentDueDayDescription": application.closing.payment_day.get_description(),
+                            },
+                            "QUOTES": { "QUOTE": quotes },
+                        }
+                    },
+                    "PARTIES": {
+                        "PARTY": {
+                            "ROLES": {
+                                "ROLE": {
+                                    "PartyRoleType": "Borrower",
+                                    "BORROWER": {
+                                        "CREDIT_SCORES": {
+                                            "CREDIT_SCORE": {
+                                                "CreditScoreValue": application.credit_score,
+                                            }
+                                        }
+                                    },
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        })
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Objects become nested elements and arrays repeat their element name
+fn write_xml(xml: &mut String, name: &str, value: &Value, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Null => {}
+        Value::Array(items) => {
+            for item in items {
+                write_xml(xml, name, item, depth);
+            }
+        }
+        Value::Object(fields) => {
+            xml.push_str(&format!("{}<{}>\n", indent, name));
+            write_xml_fields(xml, fields, depth + 1);
+            xml.push_str(&format!("{}</{}>\n", indent, name));
+        }
+        Value::String(text) => xml.push_str(&format!("{}<{}>{}</{}>\n", indent, name, escape_xml(text), name)),
+        other => xml.push_str(&format!("{}<{}>{}</{}>\n", indent, name, other, name)),
+    }
+}
+
+fn write_xml_fields(xml: &mut String, fields: &Map<String, Value>, depth: usize) {
+    for (name, value) in fields {
+        write_xml(xml, name, value, depth);
+    }
+}
+
+// The file extension picks the layout: .json or .xml
+pub fn write_application(document: &Value, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::to_string_pretty(document)? + "\n",
+        Some("xml") => {
+            let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            if let Value::Object(fields) = document {
+                write_xml_fields(&mut xml, fields, 0);
+            }
+            xml
+        }
+        _ => return Err(format!("Application path '{}' must end in .json or .xml", path).into()),
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
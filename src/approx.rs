@@ -0,0 +1,96 @@
+use rust_decimal::prelude::*;
+
+// Approximate f64 versions of the amortization math, for search loops that
+// only need to get close. Results shown or exported always come from the
+// exact Decimal engine. For terms up to 50 years and rates up to 100%, the
+// approximate payment is within a relative 1e-9 of the exact one.
+const RELATIVE_ERROR_BOUND: f64 = 1e-9;
+
+// Past this many percent a year no rate search goes looking
+pub const MAX_RATE: u32 = 12_800;
+
+pub fn approx_monthly_payment(principal: f64, annual_rate: f64, num_payments: u32) -> f64 {
+    let monthly_rate = annual_rate / 1200.0;
+    if monthly_rate == 0.0 {
+        return principal / num_payments as f64;
+    }
+
+    let base_raised = (1.0 + monthly_rate).powi(num_payments as i32);
+    principal * monthly_rate * base_raised / (base_raised - 1.0)
+}
+
+// Range of annual rates, in percent, that holds the rate at which `principal`
+// amortizes with the given payment, found with the approximate math. The
+// margin is far wider than the error bound, so exact bisection can finish
+// inside it. The top of the range starts at 100% and doubles until it is
+// high enough, which short loans sold at a deep discount need; None when
+// even MAX_RATE is not.
+pub fn bracket_rate_for_payment(
+    principal: Decimal,
+    monthly_payment: Decimal,
+    num_payments: u32,
+) -> Option<(Decimal, Decimal)> {
+    let principal = principal.to_f64()?;
+    let monthly_payment = monthly_payment.to_f64()?;
+
+    let mut low = 0.0;
+    let mut high = 100.0;
+    while approx_monthly_payment(principal, high, num_payments) * (1.0 + RELATIVE_ERROR_BOUND) < monthly_payment {
+        if high >= f64::from(MAX_RATE) {
+            return None;
+        }
+        low = high;
+        high *= 2.0;
+    }
+    while high - low > 1e-6 {
+        let mid = (low + high) / 2.0;
+        let payment = approx_monthly_payment(principal, mid, num_payments);
+        if payment * (1.0 + RELATIVE_ERROR_BOUND) < monthly_payment {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let margin = 0.001;
+    Some((
+        Decimal::from_f64(low - margin).unwrap_or(Decimal::ZERO).max(Decimal::ZERO),
+        Decimal::from_f64(high + margin)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monthly_payment;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn approximate_payment_is_within_the_bound() {
+        for num_payments in [1, 12, 36, 60, 84, 120, 180, 240, 360, 480, 600] {
+            for rate in ["0", "0.01", "0.5", "2.99", "6.5", "12", "24.99", "36", "59.9", "99.99"] {
+                let exact_rate = Decimal::from_str(rate).unwrap();
+                let exact = monthly_payment(dec!(250000), exact_rate, num_payments).unwrap();
+                let approx = approx_monthly_payment(250000.0, exact_rate.to_f64().unwrap(), num_payments);
+                let exact = exact.to_f64().unwrap();
+                assert!(
+                    ((approx - exact) / exact).abs() <= RELATIVE_ERROR_BOUND,
+                    "{} payments at {}%: {} against {}",
+                    num_payments,
+                    rate,
+                    approx,
+                    exact
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bracket_holds_the_rate() {
+        for (num_payments, rate) in [(60, dec!(7.25)), (360, dec!(6.5)), (12, dec!(0)), (600, dec!(18))] {
+            let payment = monthly_payment(dec!(30000), rate, num_payments).unwrap();
+            let (low, high) = bracket_rate_for_payment(dec!(30000), payment, num_payments).unwrap();
+            assert!(low <= rate && rate <= high, "{} not in {}..{}", rate, low, high);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::conventions::CentRate;
+use crate::schedule::{Schedule, ScheduleRow};
+use crate::LoanCalculator;
+
+// A hybrid ARM such as a 5/1: fixed at `initial_rate` for `fixed_months`,
+// then reset every `adjustment_months` to the index plus `margin`. Each
+// reset moves the rate by at most the initial cap (first reset) or the
+// periodic cap (later ones), and never more than the lifetime cap above the
+// initial rate or below the margin.
+#[derive(Debug, Clone)]
+pub struct ArmTerms {
+    pub initial_rate: Decimal,
+    pub fixed_months: u32,
+    pub adjustment_months: u32,
+    pub margin: Decimal,
+    pub initial_cap: Decimal,
+    pub periodic_cap: Decimal,
+    pub lifetime_cap: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArmScenario {
+    // The index falls to zero
+    BestCase,
+    // The index stays where it is today
+    Expected { index: Decimal },
+    // The index rises without limit
+    WorstCase,
+}
+
+impl ArmScenario {
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            ArmScenario::BestCase => "Best case",
+            ArmScenario::Expected { .. } => "Expected",
+            ArmScenario::WorstCase => "Worst case",
+        }
+    }
+}
+
+impl ArmTerms {
+    pub fn max_rate(&self) -> Decimal {
+        self.initial_rate + self.lifetime_cap
+    }
+
+    // The rate the index would set with no caps, held within the lifetime
+    // limits. A margin above the lifetime cap leaves every path at the cap.
+    fn target_rate(&self, scenario: &ArmScenario) -> Decimal {
+        let rate = match scenario {
+            ArmScenario::BestCase => self.margin,
+            ArmScenario::Expected { index } => (*index + self.margin).max(self.margin),
+            ArmScenario::WorstCase => self.max_rate(),
+        };
+        rate.min(self.max_rate())
+    }
+
+    // Rate for each adjustment period in turn, starting with the fixed period
+    pub fn rate_path(&self, scenario: &ArmScenario, num_payments: u32) -> Vec<(u32, Decimal)> {
+        let target = self.target_rate(scenario);
+        let mut path = vec![(1, self.initial_rate)];
+        let mut rate = self.initial_rate;
+        let mut month = self.fixed_months + 1;
+        while month <= num_payments {
+            let cap = if path.len() == 1 { self.initial_cap } else { self.periodic_cap };
+            rate = target.clamp(rate - cap, rate + cap);
+            path.push((month, rate));
+            month += self.adjustment_months.max(1);
+        }
+        path
+    }
+}
+
+impl LoanCalculator {
+    // Re-amortizes whatever is owed over the remaining term at each reset.
+    // Payments and interest are billed to the cent, as `schedule_rows` bills
+    // them.
+    pub fn generate_arm_schedule(
+        &self,
+        principal: Decimal,
+        num_payments: u32,
+        terms: &ArmTerms,
+        scenario: &ArmScenario,
+    ) -> Schedule {
+        let rounding = self.rounding();
+        let path = terms.rate_path(scenario, num_payments);
+        let mut rows = Vec::with_capacity(num_payments as usize);
+        let mut balance = rounding.round_cents(principal);
+        let mut payment = dec!(0);
+        let mut monthly_rate = CentRate::new(rounding, dec!(0));
+        for month in 1..=num_payments {
+            if let Some((_, rate)) = path.iter().find(|(start, _)| *start == month) {
+                monthly_rate = CentRate::new(rounding, rate / dec!(100) / dec!(12));
+                payment = rounding.round_cents(self.calculate_monthly_payment(balance, *rate, num_payments - month + 1));
+            }
+            let interest = monthly_rate.interest(balance);
+            let due = balance + interest;
+            let paid = if month == num_payments { due } else { payment.min(due) };
+            balance = due - paid;
+            rows.push(ScheduleRow {
+                payment: paid,
+                interest,
+                balance,
+                offset_balance: dec!(0),
+            });
+        }
+        Schedule { rows }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(margin: Decimal) -> ArmTerms {
+        ArmTerms {
+            initial_rate: dec!(3),
+            fixed_months: 60,
+            adjustment_months: 12,
+            margin,
+            initial_cap: dec!(2),
+            periodic_cap: dec!(2),
+            lifetime_cap: dec!(5),
+        }
+    }
+
+    #[test]
+    fn expected_path_follows_the_index_within_the_caps() {
+        let path = terms(dec!(2.75)).rate_path(&ArmScenario::Expected { index: dec!(4) }, 360);
+        assert_eq!(path[0], (1, dec!(3)));
+        assert_eq!(path[1], (61, dec!(5)));
+        assert_eq!(path[2], (73, dec!(6.75)));
+        assert!(path.iter().skip(2).all(|(_, rate)| *rate == dec!(6.75)));
+    }
+
+    #[test]
+    fn margin_above_the_lifetime_cap_holds_at_the_cap() {
+        let terms = terms(dec!(9));
+        for scenario in [ArmScenario::BestCase, ArmScenario::Expected { index: dec!(4) }, ArmScenario::WorstCase] {
+            let path = terms.rate_path(&scenario, 360);
+            assert_eq!(path.last().map(|(_, rate)| *rate), Some(dec!(8)), "{:?}", scenario);
+        }
+    }
+}
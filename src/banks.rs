@@ -0,0 +1,292 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dialoguer::{Confirm, Input, Select};
+use prettytable::{row, Cell, Row, Table};
+use rust_decimal::prelude::*;
+use serde_yaml::{Mapping, Value};
+
+use loancalc::config::{self, ConfigFormat, ConfigSource};
+use loancalc::{format_money, format_months, BankConfig, LoanCalculator, LoanType};
+
+use crate::lint::{self, LOAN_TYPES};
+
+fn describe_range(bank: &BankConfig, loan_type: &LoanType) -> String {
+    let range = lint::rate_range(bank, loan_type);
+    if !lint::offers(range) {
+        return "Not offered".to_string();
+    }
+    let mut lines = vec![format!("{:.2}%-{:.2}%", range.min, range.max)];
+    if let Some(months) = range.max_term_months {
+        lines.push(format!("up to {}", format_months(months)));
+    }
+    let closing_costs = lint::closing_costs(bank, loan_type);
+    if closing_costs != 0.0 {
+        let closing_costs = Decimal::from_f64(closing_costs).unwrap_or_default();
+        lines.push(format!("{} closing", format_money(closing_costs)));
+    }
+    lines.join("\n")
+}
+
+// Closing costs vary by loan type, so they show under each range instead
+fn describe_fees(bank: &BankConfig) -> String {
+    let mut fees = Vec::new();
+    for (name, percent) in [
+        ("origination", bank.origination_fee),
+        ("points", bank.points),
+        ("prepayment penalty", bank.prepayment_penalty),
+    ] {
+        if percent != 0.0 {
+            fees.push(format!("{}% {}", percent, name));
+        }
+    }
+    if fees.is_empty() {
+        "None".to_string()
+    } else {
+        fees.join("\n")
+    }
+}
+
+// `banks` shows what the calculator will quote from, then checks the config
+// the way `config lint` does, exiting non-zero on any problem
+pub fn list(source: &ConfigSource) -> Result<(), Box<dyn std::error::Error>> {
+    let config = lint::load(source)?;
+    println!("Banks in {}:", source.get_description());
+
+    let mut table = Table::new();
+    table.set_titles(row!["Bank", "Home", "Car", "Personal", "Student", "Min Score", "Fees"]);
+    for bank in &config.banks {
+        let mut cells = vec![Cell::new(&bank.name)];
+        cells.extend(
+            LOAN_TYPES
+                .iter()
+                .map(|loan_type| Cell::new(&describe_range(bank, loan_type))),
+        );
+        cells.extend([
+            Cell::new(&bank.min_credit_score.to_string()),
+            Cell::new(&describe_fees(bank)),
+        ]);
+        table.add_row(Row::new(cells));
+    }
+    if config.banks.is_empty() {
+        table.add_row(row!["No banks are configured"]);
+    }
+    crate::print_table(&table);
+    println!();
+
+    lint::report(&config, &source.get_description());
+    Ok(())
+}
+
+// The YAML file edits are written to. The built-in banks are copied to the
+// user config directory the first time they are edited.
+fn editable_config(source: &ConfigSource) -> Result<(PathBuf, String), Box<dyn std::error::Error>> {
+    match source {
+        ConfigSource::File(path) => {
+            if ConfigFormat::from_path(path) != Some(ConfigFormat::Yaml) {
+                return Err(format!("{}: only YAML bank configs can be edited", path.display()).into());
+            }
+            Ok((path.clone(), fs::read_to_string(path)?))
+        }
+        ConfigSource::Builtin => {
+            let dir = config::user_config_dir().ok_or("no home directory to keep the bank config in")?;
+            Ok((dir.join(config::CONFIG_FILES[0]), config::DEFAULT_CONFIG.to_string()))
+        }
+    }
+}
+
+// Checked to load before anything is written. Comments do not survive the
+// rewrite, so the previous file is kept as banks.yaml.bak.
+fn save(path: &Path, document: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = serde_yaml::to_string(document)?;
+    LoanCalculator::from_config(&contents).map_err(|e| format!("the edited config does not load: {}", e))?;
+
+    if path.exists() {
+        let backup = format!("{}.bak", path.display());
+        fs::copy(path, &backup)?;
+        println!("Saved the previous config as {}", backup);
+    }
+    config::write_atomically(path, &contents)?;
+    Ok(())
+}
+
+fn banks_of(document: &mut Value) -> Result<&mut Vec<Value>, Box<dyn std::error::Error>> {
+    document
+        .get_mut("banks")
+        .and_then(Value::as_sequence_mut)
+        .ok_or_else(|| "the config has no banks list".into())
+}
+
+fn bank_name(bank: &Value) -> &str {
+    bank.get("name").and_then(Value::as_str).unwrap_or_default()
+}
+
+// The bank named on the command line, or one picked from the list
+fn choose_bank(banks: &[Value], name: Option<&str>, action: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    if banks.is_empty() {
+        return Err("no banks are configured".into());
+    }
+    if let Some(name) = name {
+        return banks
+            .iter()
+            .position(|bank| bank_name(bank).eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("no bank named '{}'", name).into());
+    }
+    let names: Vec<&str> = banks.iter().map(bank_name).collect();
+    Ok(Select::new()
+        .with_prompt(format!("Select the bank to {}", action))
+        .items(&names)
+        .default(0)
+        .interact()?)
+}
+
+fn parse_range(input: &str) -> Result<Option<(f64, f64)>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    let (min, max) = input
+        .split_once('-')
+        .ok_or_else(|| "Enter the lowest and highest rate, like 4.5-6.5".to_string())?;
+    let rate = |value: &str| {
+        value
+            .trim()
+            .trim_end_matches('%')
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("'{}' is not a rate", value.trim()))
+    };
+    let (min, max) = (rate(min)?, rate(max)?);
+    if min <= 0.0 {
+        return Err("Rates must be above 0%".to_string());
+    }
+    if min > max {
+        return Err(format!("The lowest rate comes first: {}-{}", max, min));
+    }
+    Ok(Some((min, max)))
+}
+
+fn describe_rates(range: Option<&Value>) -> String {
+    let rate = |field: &str| range.and_then(|range| range.get(field)).and_then(Value::as_f64).unwrap_or(0.0);
+    let (min, max) = (rate("min"), rate("max"));
+    if min == 0.0 && max == 0.0 {
+        String::new()
+    } else {
+        format!("{}-{}", min, max)
+    }
+}
+
+// Asks for everything `banks add` needs, starting from the bank's current
+// values when editing. Fields not asked about are left as they were.
+fn prompt_bank(bank: &mut Value, banks: &[Value]) -> Result<(), Box<dyn std::error::Error>> {
+    let current_name = bank_name(bank).to_string();
+    let name: String = Input::new()
+        .with_prompt("Bank name")
+        .with_initial_text(&current_name)
+        .validate_with(|input: &String| {
+            let input = input.trim();
+            if input.is_empty() {
+                Err("Enter a name".to_string())
+            } else if !input.eq_ignore_ascii_case(&current_name)
+                && banks.iter().any(|other| bank_name(other).eq_ignore_ascii_case(input))
+            {
+                Err(format!("There is already a bank named '{}'", input))
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()?;
+    bank["name"] = Value::from(name.trim());
+
+    for loan_type in &LOAN_TYPES {
+        let field = format!("{}_loan_range", loan_type.get_name());
+        let input: String = Input::new()
+            .with_prompt(format!(
+                "{} loan rates in %, like 4.5-6.5 (blank if not offered)",
+                capitalize(loan_type.get_name())
+            ))
+            .with_initial_text(describe_rates(bank.get(&field)))
+            .allow_empty(true)
+            .validate_with(|input: &String| parse_range(input).map(|_| ()))
+            .interact_text()?;
+        let (min, max) = parse_range(&input)?.unwrap_or((0.0, 0.0));
+        if !bank.get(&field).is_some_and(Value::is_mapping) {
+            bank[field.as_str()] = Value::Mapping(Mapping::new());
+        }
+        bank[field.as_str()]["min"] = Value::from(min);
+        bank[field.as_str()]["max"] = Value::from(max);
+    }
+
+    let score: u16 = Input::new()
+        .with_prompt("Minimum credit score")
+        .with_initial_text(
+            bank.get("min_credit_score")
+                .and_then(Value::as_u64)
+                .unwrap_or(620)
+                .to_string(),
+        )
+        .validate_with(|input: &u16| {
+            if (300..=850).contains(input) {
+                Ok(())
+            } else {
+                Err("Credit scores run from 300 to 850")
+            }
+        })
+        .interact_text()?;
+    bank["min_credit_score"] = Value::from(score);
+    Ok(())
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+pub fn add(source: &ConfigSource) -> Result<(), Box<dyn std::error::Error>> {
+    let (path, contents) = editable_config(source)?;
+    let mut document: Value = serde_yaml::from_str(&contents)?;
+    let banks = banks_of(&mut document)?;
+    let mut bank = Value::Mapping(Mapping::new());
+    prompt_bank(&mut bank, banks)?;
+    let name = bank_name(&bank).to_string();
+    banks.push(bank);
+    save(&path, &document)?;
+    println!("Added {} to {}", name, path.display());
+    Ok(())
+}
+
+pub fn edit(source: &ConfigSource, name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let (path, contents) = editable_config(source)?;
+    let mut document: Value = serde_yaml::from_str(&contents)?;
+    let banks = banks_of(&mut document)?;
+    let index = choose_bank(banks, name, "edit")?;
+    let mut bank = banks[index].clone();
+    prompt_bank(&mut bank, banks)?;
+    let name = bank_name(&bank).to_string();
+    banks[index] = bank;
+    save(&path, &document)?;
+    println!("Updated {} in {}", name, path.display());
+    Ok(())
+}
+
+pub fn remove(source: &ConfigSource, name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let (path, contents) = editable_config(source)?;
+    let mut document: Value = serde_yaml::from_str(&contents)?;
+    let banks = banks_of(&mut document)?;
+    let index = choose_bank(banks, name, "remove")?;
+    let name = bank_name(&banks[index]).to_string();
+    let confirmed = Confirm::new()
+        .with_prompt(format!("Remove {} from {}?", name, path.display()))
+        .default(false)
+        .interact()?;
+    if !confirmed {
+        return Ok(());
+    }
+    banks.remove(index);
+    save(&path, &document)?;
+    println!("Removed {} from {}", name, path.display());
+    Ok(())
+}
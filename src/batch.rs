@@ -0,0 +1,407 @@
+use std::path::Path;
+
+use colored::Colorize;
+use prettytable::{row, Table};
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+
+use loancalc::config::{self, ConfigFormat};
+use loancalc::dates::{Closing, PaymentDay, StubInterest};
+use loancalc::frequency::PaymentFrequency;
+use loancalc::interest::InterestMethod;
+use loancalc::provenance::Provenance;
+use loancalc::{format_money, results, spreadsheet, LoanCalculator, LoanType};
+
+use crate::cli::BatchFormat;
+use crate::i18n::{format_months, tr, trf};
+
+// A file of loans to compare, named like the flags of a single comparison:
+//
+// scenarios:
+//   - name: Our house
+//     type: home
+//     amount: 350000
+//     term: 30
+//     score: 740
+//   - name: Sam's car
+//     type: car
+//     amount: 28000
+//     term: 60m
+//     score: 690
+//     rate: 6.9
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchFile {
+    // Read one at a time, so a scenario with a bad field fails on its own
+    scenarios: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchScenario {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(rename = "type")]
+    loan_type: String,
+    amount: f64,
+    // Years, or months like 66m, as with --term
+    term: BatchTerm,
+    score: u16,
+    // Quoted alongside the banks, as the interactive custom rate is
+    #[serde(default)]
+    rate: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BatchTerm {
+    Years(u32),
+    Text(String),
+}
+
+struct BatchLoan {
+    name: String,
+    loan_type: LoanType,
+    amount: Decimal,
+    term_months: u32,
+    credit_score: u16,
+    interest_method: InterestMethod,
+    offers: Vec<(String, Decimal)>,
+    declined: Vec<(String, String)>,
+    warnings: Vec<String>,
+}
+
+impl BatchLoan {
+    fn get_description(&self) -> String {
+        format!(
+            "{} loan of {}, {}, credit score {}",
+            self.loan_type.get_name(),
+            format_money(self.amount),
+            format_months(self.term_months),
+            self.credit_score
+        )
+    }
+}
+
+fn check_scenario(scenario: &BatchScenario) -> Result<(LoanType, Decimal, u32, Option<Decimal>), String> {
+    let loan_type = LoanType::from_name(&scenario.loan_type)
+        .ok_or_else(|| format!("unknown type '{}' (expected home, car, personal or student)", scenario.loan_type))?;
+    crate::check_loan_amount(&loan_type, scenario.amount).map_err(|e| format!("amount: {}", e))?;
+    let amount = Decimal::from_f64(scenario.amount).ok_or("amount must be a number")?;
+    let term = match &scenario.term {
+        BatchTerm::Years(years) => years.to_string(),
+        BatchTerm::Text(text) => text.clone(),
+    };
+    let term_months = crate::check_loan_term(&loan_type, &term).map_err(|e| format!("term: {}", e))?;
+    if !(300..=850).contains(&scenario.score) {
+        return Err("score: Credit score must be between 300 and 850".to_string());
+    }
+    let rate = match scenario.rate {
+        Some(rate) if rate > 0.0 && rate < 100.0 => Decimal::from_f64(rate),
+        Some(_) => return Err("rate: Interest rate must be between 0 and 100".to_string()),
+        None => None,
+    };
+    Ok((loan_type, amount, term_months, rate))
+}
+
+// Every scenario is checked before any is quoted, so the errors come
+// before the results. Scenarios that fail are reported on stderr and left
+// out; how many failed comes back with the quotes of the rest.
+fn quote_all(
+    calculator: &mut LoanCalculator,
+    file: BatchFile,
+    path: &str,
+    closing: &Closing,
+) -> (Vec<BatchLoan>, usize) {
+    let mut checked = Vec::new();
+    let mut failed = 0;
+    for (index, value) in file.scenarios.into_iter().enumerate() {
+        let name = value
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Scenario {}", index + 1));
+        let loan = serde_json::from_value::<BatchScenario>(value)
+            .map_err(|e| e.to_string())
+            .and_then(|scenario| Ok((scenario.name.clone(), scenario.score, check_scenario(&scenario)?)));
+        match loan {
+            Ok((given, score, loan)) => checked.push((given.unwrap_or(name), score, loan)),
+            Err(e) => {
+                eprintln!("{} {}: {}: {}", "error:".red().bold(), path, name, e);
+                failed += 1;
+            }
+        }
+    }
+
+    let mut loans = Vec::new();
+    for (name, credit_score, (loan_type, amount, term_months, rate)) in checked {
+        let mut declined = Vec::new();
+        let mut warnings = Vec::new();
+        let mut offers = crate::quote_banks(
+            calculator,
+            &loan_type,
+            amount,
+            term_months,
+            credit_score,
+            &mut declined,
+            &mut warnings,
+        );
+        let interest_method = calculator.default_interest_method(&loan_type, &closing.calendar());
+        if let Some(rate) = rate {
+            let rate = calculator.monthly_rate("Custom Rate", rate);
+            let cost = calculator.calculate_cost(&interest_method, amount, rate, term_months);
+            crate::add_offer_warnings(&mut warnings, "Custom Rate", rate, cost);
+            offers.push(("Custom Rate".to_string(), rate));
+        }
+        loans.push(BatchLoan {
+            name,
+            loan_type,
+            amount,
+            term_months,
+            credit_score,
+            interest_method,
+            offers,
+            declined,
+            warnings,
+        });
+    }
+    (loans, failed)
+}
+
+fn recommended(calculator: &LoanCalculator, loan: &BatchLoan) -> Option<String> {
+    calculator
+        .rank_offers(
+            &loan.interest_method,
+            &loan.loan_type,
+            &loan.offers,
+            loan.amount,
+            loan.term_months,
+            calculator.ranking(),
+        )
+        .into_iter()
+        .next()
+        .map(|best| best.name)
+}
+
+fn print_batch_table(calculator: &LoanCalculator, loans: &[BatchLoan]) {
+    let mut table = Table::new();
+    table.set_titles(row![
+        tr("Scenario"),
+        tr("Bank"),
+        tr("Interest Rate"),
+        tr("Monthly Payment"),
+        tr("Total Interest"),
+        tr("Total Payment")
+    ]);
+    for loan in loans {
+        let scenario = format!("{}\n{}", loan.name, loan.get_description());
+        let mut first = true;
+        for (bank, rate) in &loan.offers {
+            let Some(cost) = calculator.calculate_cost(&loan.interest_method, loan.amount, *rate, loan.term_months)
+            else {
+                continue;
+            };
+            let apr = calculator.calculate_apr(
+                &loan.interest_method,
+                bank,
+                &loan.loan_type,
+                loan.amount,
+                *rate,
+                loan.term_months,
+            );
+            table.add_row(row![
+                if first { scenario.as_str() } else { "" },
+                bank,
+                crate::format_offer_rate(calculator, bank, *rate, apr),
+                format_money(cost.monthly_payment),
+                format_money(cost.total_interest),
+                format_money(cost.total_payment)
+            ]);
+            first = false;
+        }
+        if first {
+            table.add_row(row![scenario, tr("No bank offers this loan"), "", "", "", ""]);
+        }
+    }
+    crate::print_table(&table);
+
+    for loan in loans {
+        if let Some(best) = recommended(calculator, loan) {
+            println!("{}", trf("Recommended for {}: {}", &[&loan.name, &best]));
+        }
+    }
+    for loan in loans {
+        for (bank, reason) in &loan.declined {
+            println!("{} {}: {} {}", tr("Declined:").yellow().bold(), loan.name, bank, reason);
+        }
+        for warning in &loan.warnings {
+            println!("{} {}: {}", tr("Warning:").yellow().bold(), loan.name, warning);
+        }
+    }
+}
+
+// Each row carries its own scenario's inputs; the stamp above them covers the
+// run as a whole
+fn write_batch_csv(
+    calculator: &LoanCalculator,
+    loans: &[BatchLoan],
+    provenance: &Provenance,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stdout = std::io::stdout();
+    spreadsheet::write_provenance(&mut stdout, provenance)?;
+    let mut writer = csv::Writer::from_writer(stdout);
+    writer.write_record([
+        "scenario",
+        "loan_type",
+        "amount",
+        "term_months",
+        "credit_score",
+        "interest_method",
+        "bank",
+        "qualified",
+        "rate",
+        "apr",
+        "monthly_payment",
+        "total_interest",
+        "total_payment",
+        "reason",
+    ])?;
+    for loan in loans {
+        let loan_fields = [
+            loan.name.clone(),
+            loan.loan_type.get_name().to_string(),
+            format!("{:.2}", loan.amount.round_dp(2)),
+            loan.term_months.to_string(),
+            loan.credit_score.to_string(),
+            loan.interest_method.get_description(),
+        ];
+        for (bank, rate) in &loan.offers {
+            let cost = calculator.calculate_cost(&loan.interest_method, loan.amount, *rate, loan.term_months);
+            let apr = calculator.calculate_apr(
+                &loan.interest_method,
+                bank,
+                &loan.loan_type,
+                loan.amount,
+                *rate,
+                loan.term_months,
+            );
+            let mut record = loan_fields.to_vec();
+            record.extend([
+                bank.clone(),
+                cost.is_some().to_string(),
+                format!("{:.4}", rate.round_dp(4)),
+                apr.map(|apr| format!("{:.4}", apr.round_dp(4))).unwrap_or_default(),
+                cost.as_ref().map(|cost| format!("{:.2}", cost.monthly_payment.round_dp(2))).unwrap_or_default(),
+                cost.as_ref().map(|cost| format!("{:.2}", cost.total_interest.round_dp(2))).unwrap_or_default(),
+                cost.as_ref().map(|cost| format!("{:.2}", cost.total_payment.round_dp(2))).unwrap_or_default(),
+                String::new(),
+            ]);
+            writer.write_record(&record)?;
+        }
+        for (bank, reason) in &loan.declined {
+            let mut record = loan_fields.to_vec();
+            record.extend([bank.clone(), "false".to_string()]);
+            record.extend(std::iter::repeat_n(String::new(), 5));
+            record.push(reason.clone());
+            writer.write_record(&record)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// One `--output json` comparison document per scenario
+fn print_batch_json(
+    calculator: &LoanCalculator,
+    loans: &[BatchLoan],
+    closing: &Closing,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let scenarios: Vec<_> = loans
+        .iter()
+        .map(|loan| {
+            let provenance = calculator.provenance(vec![
+                ("loan_type", loan.loan_type.get_name().to_string()),
+                ("loan_amount", format!("{:.2}", loan.amount.round_dp(2))),
+                ("term_months", loan.term_months.to_string()),
+                ("credit_score", loan.credit_score.to_string()),
+                ("interest_method", loan.interest_method.get_description()),
+                ("closing_date", closing.date.to_string()),
+            ]);
+            json!({
+                "name": loan.name,
+                "comparison": calculator.comparison_document(&results::ComparisonResults {
+                    loan_type: &loan.loan_type,
+                    amount: loan.amount,
+                    purchase: None,
+                    housing: None,
+                    deferment: None,
+                    income: None,
+                    tax_rate: None,
+                    rate_quotes: &[],
+                    term_months: loan.term_months,
+                    credit_score: loan.credit_score,
+                    closing,
+                    interest_method: &loan.interest_method,
+                    frequency: PaymentFrequency::Monthly,
+                    offers: &loan.offers,
+                    declined: &loan.declined,
+                    filtered: &[],
+                    warnings: &loan.warnings,
+                    provenance: &provenance,
+                }),
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&json!({ "scenarios": scenarios }))?);
+    Ok(())
+}
+
+pub fn run(mut calculator: LoanCalculator, path: &Path, format: BatchFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let file = path.display().to_string();
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", file, e))?;
+    // YAML unless the extension says otherwise
+    let batch: BatchFile = config::parse_config(
+        &contents,
+        ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Yaml),
+        &file,
+    )?;
+    if batch.scenarios.is_empty() {
+        return Err(format!("{} lists no scenarios", file).into());
+    }
+
+    let mut warnings = Vec::new();
+    calculator.use_market_rates(false, &mut warnings);
+    for warning in &warnings {
+        eprintln!("{} {}", tr("Warning:").yellow().bold(), warning);
+    }
+    // Every scenario closes today with payments due on the 1st, as a
+    // scripted run does
+    let closing = Closing {
+        date: chrono::Local::now().date_naive(),
+        payment_day: PaymentDay::Day(1),
+        holidays: None,
+        stub_interest: StubInterest::Prepaid,
+    };
+    let total = batch.scenarios.len();
+    let (loans, failed) = quote_all(&mut calculator, batch, &file, &closing);
+    if loans.is_empty() {
+        return Err(trf("None of the {} scenarios in {} could be quoted", &[&total, &file]).into());
+    }
+    match format {
+        BatchFormat::Table => print_batch_table(&calculator, &loans),
+        BatchFormat::Csv => {
+            let provenance = calculator.provenance(vec![
+                ("batch_file", file.clone()),
+                ("closing_date", closing.date.to_string()),
+            ]);
+            write_batch_csv(&calculator, &loans, &provenance)?
+        }
+        BatchFormat::Json => print_batch_json(&calculator, &loans, &closing)?,
+    }
+    // The rest are printed, but a script still sees the run fail
+    if failed > 0 {
+        return Err(trf("{} of {} scenarios in {} could not be quoted", &[&failed, &total, &file]).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::interest::{InterestMethod, LoanCost};
+
+// Everything a quote depends on. The bank only matters through the rate it
+// offers, so two banks quoting the same rate share an entry. Decimals hash by
+// value, so 6.5 and 6.50 are the same key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QuoteKey {
+    pub method: InterestMethod,
+    pub principal: Decimal,
+    pub annual_rate: Decimal,
+    pub num_payments: u32,
+}
+
+// Comparisons quote a handful of banks a few ways each, so this holds every
+// quote a session revisits while keeping a long-running server from growing
+// without limit
+const MAX_QUOTES: usize = 4096;
+
+// Quotes already computed this session. `None` is cached too, for loans that
+// cannot exist at the given inputs. Once full it starts over empty, which
+// only costs recomputing quotes that are asked for again.
+#[derive(Debug, Default)]
+pub struct QuoteCache {
+    quotes: RefCell<HashMap<QuoteKey, Option<LoanCost>>>,
+}
+
+impl QuoteCache {
+    pub fn get_or_insert_with(
+        &self,
+        key: QuoteKey,
+        compute: impl FnOnce() -> Option<LoanCost>,
+    ) -> Option<LoanCost> {
+        if let Some(cost) = self.quotes.borrow().get(&key) {
+            return cost.clone();
+        }
+
+        let cost = compute();
+        let mut quotes = self.quotes.borrow_mut();
+        if quotes.len() >= MAX_QUOTES {
+            quotes.clear();
+        }
+        quotes.insert(key, cost.clone());
+        cost
+    }
+}
+
+// An annuity factor as `annuity_factor` gives it, or `None` where it
+// overflows
+pub type CachedFactor = Option<Option<Decimal>>;
+
+// Annuity factors by rate and number of monthly payments. A whole schedule
+// or quote hangs off one of these, and working it out costs more than
+// walking the schedule.
+#[derive(Debug, Default)]
+pub struct PaymentFactors {
+    factors: RefCell<HashMap<(Decimal, u32), CachedFactor>>,
+}
+
+impl PaymentFactors {
+    pub fn get_or_insert_with(
+        &self,
+        annual_rate: Decimal,
+        num_payments: u32,
+        compute: impl FnOnce() -> CachedFactor,
+    ) -> CachedFactor {
+        if let Some(factor) = self.factors.borrow().get(&(annual_rate, num_payments)) {
+            return *factor;
+        }
+
+        let factor = compute();
+        let mut factors = self.factors.borrow_mut();
+        if factors.len() >= MAX_QUOTES {
+            factors.clear();
+        }
+        factors.insert((annual_rate, num_payments), factor);
+        factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_stays_bounded() {
+        let cache = QuoteCache::default();
+        for principal in 0..(MAX_QUOTES as u32 + 10) {
+            let key = QuoteKey {
+                method: InterestMethod::Amortizing,
+                principal: Decimal::from(principal),
+                annual_rate: Decimal::from(6),
+                num_payments: 360,
+            };
+            cache.get_or_insert_with(key, || None);
+        }
+        assert!(cache.quotes.borrow().len() <= MAX_QUOTES);
+    }
+
+    #[test]
+    fn cached_payments_match_the_formula() {
+        let calculator = crate::LoanCalculator::builder().build().unwrap();
+        // 6.50 hashes as 6.5 and reuses its factor
+        for rate in [Decimal::ZERO, Decimal::new(65, 1), Decimal::new(650, 2), Decimal::new(1999, 2)] {
+            for principal in [Decimal::from(1000), Decimal::from(350_000)] {
+                let expected = crate::monthly_payment(principal, rate, 360).unwrap();
+                assert_eq!(calculator.calculate_monthly_payment(principal, rate, 360), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::interest::InterestMethod;
+use crate::money::money_format;
+use crate::schedule::{PaymentStrategy, Schedule};
+use crate::LoanCalculator;
+
+const WIDTH: u32 = 1000;
+const PANEL_HEIGHT: u32 = 400;
+const FOOTNOTE_HEIGHT: u32 = 24;
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(0.0)
+}
+
+// Writes the balance, payment-split and comparison charts as one image.
+// The file extension picks the backend: SVG for documents, PNG for slides.
+// `footnote` is printed along the bottom edge.
+pub fn write_charts(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    footnote: &str,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let costs: Vec<(String, Decimal)> = offers
+        .iter()
+        .filter_map(|(name, rate)| {
+            calculator
+                .calculate_cost(interest_method, loan_amount, *rate, loan_term)
+                .map(|cost| (name.clone(), cost.total_interest))
+        })
+        .collect();
+
+    // Only amortizing loans have a month-by-month schedule to plot
+    let schedules: Vec<(String, Schedule)> = if *interest_method == InterestMethod::Amortizing {
+        offers
+            .iter()
+            .map(|(name, rate)| {
+                let schedule =
+                    calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+                (name.clone(), schedule)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let panels = if schedules.is_empty() { 1 } else { 3 };
+    let size = (WIDTH, PANEL_HEIGHT * panels + FOOTNOTE_HEIGHT);
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("svg") => draw(
+            &SVGBackend::new(path, size).into_drawing_area(),
+            loan_amount,
+            &schedules,
+            &costs,
+            footnote,
+        ),
+        Some("png") => draw(
+            &BitMapBackend::new(path, size).into_drawing_area(),
+            loan_amount,
+            &schedules,
+            &costs,
+            footnote,
+        ),
+        _ => Err(format!("Chart path '{}' must end in .svg or .png", path).into()),
+    }
+}
+
+fn draw<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    loan_amount: Decimal,
+    schedules: &[(String, Schedule)],
+    costs: &[(String, Decimal)],
+    footnote: &str,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let (charts, footer) = root.split_vertically(root.dim_in_pixel().1 - FOOTNOTE_HEIGHT);
+    footer.titled(footnote, ("sans-serif", 12))?;
+
+    // The payment split is shown for the offer with the least interest
+    let (cheapest, _) = costs
+        .iter()
+        .min_by_key(|(_, total_interest)| *total_interest)
+        .ok_or("No offers to chart")?;
+    if let Some((_, schedule)) = schedules.iter().find(|(name, _)| name == cheapest) {
+        let panels = charts.split_evenly((3, 1));
+        draw_balances(&panels[0], loan_amount, schedules)?;
+        draw_payment_split(&panels[1], cheapest, schedule)?;
+        draw_comparison(&panels[2], costs)?;
+    } else {
+        draw_comparison(&charts, costs)?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+
+fn draw_balances<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    loan_amount: Decimal,
+    schedules: &[(String, Schedule)],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let months = schedules.iter().map(|(_, schedule)| schedule.num_payments()).max().unwrap_or(1);
+    let mut chart = ChartBuilder::on(area)
+        .caption("Remaining Balance", ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(35)
+        .y_label_area_size(80)
+        .build_cartesian_2d(0u32..months, 0.0..to_f64(loan_amount))?;
+    chart
+        .configure_mesh()
+        .x_desc("Payment")
+        .y_label_formatter(&|value| money_format().with_symbol(&format!("{:.0}", value)))
+        .draw()?;
+
+    for (index, (name, schedule)) in schedules.iter().enumerate() {
+        let color = Palette99::pick(index).to_rgba();
+        let points = std::iter::once((0, to_f64(loan_amount))).chain(
+            schedule
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(month, row)| (month as u32 + 1, to_f64(row.balance))),
+        );
+        chart
+            .draw_series(LineSeries::new(points, color.stroke_width(2)))?
+            .label(name.as_str())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+    Ok(())
+}
+
+// Stacks each loan year's principal under its interest for one offer
+fn draw_payment_split<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    name: &str,
+    schedule: &Schedule,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let years: Vec<(f64, f64)> = schedule
+        .rows
+        .chunks(12)
+        .map(|rows| {
+            let paid: Decimal = rows.iter().map(|row| row.payment).sum();
+            let interest: Decimal = rows.iter().map(|row| row.interest).sum();
+            (to_f64(paid - interest), to_f64(interest))
+        })
+        .collect();
+    let highest = years.iter().map(|(principal, interest)| principal + interest).fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(format!("Principal vs. Interest by Loan Year ({})", name), ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(35)
+        .y_label_area_size(80)
+        .build_cartesian_2d((1u32..years.len() as u32).into_segmented(), 0.0..highest * 1.1)?;
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_desc("Loan Year")
+        .y_label_formatter(&|value| money_format().with_symbol(&format!("{:.0}", value)))
+        .draw()?;
+
+    chart
+        .draw_series(years.iter().enumerate().map(|(index, (principal, _))| {
+            let year = index as u32 + 1;
+            Rectangle::new(
+                [(SegmentValue::Exact(year), 0.0), (SegmentValue::Exact(year + 1), *principal)],
+                BLUE.filled(),
+            )
+        }))?
+        .label("Principal")
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 15, y + 5)], BLUE.filled()));
+    chart
+        .draw_series(years.iter().enumerate().map(|(index, (principal, interest))| {
+            let year = index as u32 + 1;
+            Rectangle::new(
+                [
+                    (SegmentValue::Exact(year), *principal),
+                    (SegmentValue::Exact(year + 1), principal + interest),
+                ],
+                RED.mix(0.7).filled(),
+            )
+        }))?
+        .label("Interest")
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 15, y + 5)], RED.mix(0.7).filled()));
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+    Ok(())
+}
+
+fn draw_comparison<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    costs: &[(String, Decimal)],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let names: Vec<&str> = costs.iter().map(|(name, _)| name.as_str()).collect();
+    let highest = costs.iter().map(|(_, total_interest)| to_f64(*total_interest)).fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption("Total Interest by Offer", ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(35)
+        .y_label_area_size(80)
+        .build_cartesian_2d((0..names.len() - 1).into_segmented(), 0.0..highest * 1.1)?;
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_label_formatter(&|value| match value {
+            SegmentValue::CenterOf(index) => names.get(*index).unwrap_or(&"").to_string(),
+            _ => String::new(),
+        })
+        .y_label_formatter(&|value| money_format().with_symbol(&format!("{:.0}", value)))
+        .draw()?;
+
+    chart.draw_series(costs.iter().enumerate().map(|(index, (_, total_interest))| {
+        let color = Palette99::pick(index).filled();
+        let mut bar = Rectangle::new(
+            [
+                (SegmentValue::Exact(index), 0.0),
+                (SegmentValue::Exact(index + 1), to_f64(*total_interest)),
+            ],
+            color,
+        );
+        bar.set_margin(0, 0, 20, 20);
+        bar
+    }))?;
+    Ok(())
+}
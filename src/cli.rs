@@ -0,0 +1,566 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use rust_decimal::prelude::FromPrimitive;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use loancalc::conventions::Compounding;
+use loancalc::depreciation::DepreciationCurve;
+use loancalc::frequency::PaymentFrequency;
+use loancalc::purchase::DownPayment;
+use loancalc::revolving::Debt;
+use loancalc::scenarios::Scenario;
+use loancalc::schedule::Prepayment;
+use loancalc::{Decimal, LoanType, RateDisplay};
+
+// With --type, --amount (--price and --down, or --payment), --term and --score all
+// given, the calculator runs without prompting: closing today, payments due
+// on the 1st, amortizing interest, no collateral and no follow-up analysis.
+// Anything left out is asked for as usual, or read from stdin when it is
+// not a terminal.
+#[derive(Debug, Parser)]
+#[command(name = "loancalc", version, about = "Compare loan offers across banks")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Bank config to use, instead of $LOANS_CONFIG, ~/.config/loans/banks.yaml,
+    /// the working directory's banks.yaml or the built-in banks
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Loan type: home, car, personal or student
+    #[arg(long = "type", value_name = "TYPE", value_parser = parse_loan_type)]
+    pub loan_type: Option<LoanType>,
+
+    /// Loan amount in dollars
+    #[arg(long)]
+    pub amount: Option<f64>,
+
+    /// Purchase price in dollars, financing the price less --down instead of --amount
+    #[arg(long, conflicts_with = "amount")]
+    pub price: Option<f64>,
+
+    /// Down payment on --price, in dollars or as a percent like 20%
+    #[arg(long, value_name = "AMOUNT|PERCENT", requires = "price", value_parser = parse_down_payment)]
+    pub down: Option<DownPayment>,
+
+    /// Monthly payment you can afford, to show the most each bank would lend instead
+    #[arg(long, value_name = "AMOUNT", conflicts_with_all = ["amount", "price"])]
+    pub payment: Option<f64>,
+
+    /// Loan term in years, or in months like 66m
+    #[arg(long)]
+    pub term: Option<String>,
+
+    /// Credit score
+    #[arg(long, value_parser = clap::value_parser!(u16).range(300..=850))]
+    pub score: Option<u16>,
+
+    /// Yearly property tax in dollars, for the full monthly cost of a home loan
+    #[arg(long, value_name = "AMOUNT")]
+    pub property_tax: Option<f64>,
+
+    /// Yearly homeowner's insurance in dollars
+    #[arg(long, value_name = "AMOUNT")]
+    pub insurance: Option<f64>,
+
+    /// Monthly HOA dues in dollars
+    #[arg(long, value_name = "AMOUNT")]
+    pub hoa: Option<f64>,
+
+    /// Sales tax in percent on a car's --price less the trade-in
+    #[arg(long, value_name = "PERCENT", requires = "price")]
+    pub sales_tax: Option<f64>,
+
+    /// Value in dollars of a car traded in toward --price
+    #[arg(long, value_name = "AMOUNT", requires = "price")]
+    pub trade_in: Option<f64>,
+
+    /// Still owed on the trade-in in dollars; any of it above the trade-in's value is financed
+    #[arg(long, value_name = "AMOUNT", requires = "trade_in")]
+    pub trade_in_payoff: Option<f64>,
+
+    /// Dealer documentation, title and registration fees in dollars, financed with a car's --price
+    #[arg(long, value_name = "AMOUNT", requires = "price")]
+    pub dealer_fees: Option<f64>,
+
+    /// Percent of a car's value lost each year, like 20,15,13 (the last repeating), to show when a car loan is underwater
+    #[arg(long, value_name = "PERCENTS", value_parser = DepreciationCurve::parse)]
+    pub depreciation: Option<DepreciationCurve>,
+
+    /// Marginal income tax rate in percent, to estimate the mortgage interest deduction on a home loan
+    #[arg(long, value_name = "PERCENT")]
+    pub tax_rate: Option<f64>,
+
+    /// How often payments are made: weekly, bi-weekly, semi-monthly or monthly
+    #[arg(long, value_name = "FREQUENCY", value_parser = parse_frequency)]
+    pub frequency: Option<PaymentFrequency>,
+
+    /// Gross monthly income in dollars, to check offers against each bank's DTI limits
+    #[arg(long, value_name = "AMOUNT")]
+    pub income: Option<f64>,
+
+    /// Monthly payments on existing debts in dollars, counted in the back-end DTI
+    #[arg(long, value_name = "AMOUNT", requires = "income")]
+    pub debts: Option<f64>,
+
+    /// Months left in school before a student loan enters repayment
+    #[arg(long, value_name = "MONTHS")]
+    pub in_school: Option<u32>,
+
+    /// Grace period in months between school and repayment (default 6 with --in-school)
+    #[arg(long, value_name = "MONTHS")]
+    pub grace: Option<u32>,
+
+    /// Date the loan closes, YYYY-MM-DD, with payments due on the 1st (default today); adds the payoff projection
+    #[arg(long, value_name = "DATE")]
+    pub closing: Option<chrono::NaiveDate>,
+
+    /// Date the first payment is due, YYYY-MM-DD, with later payments on the same day of the month; adds the payoff projection
+    #[arg(long, value_name = "DATE", conflicts_with = "closing")]
+    pub first_payment: Option<chrono::NaiveDate>,
+
+    /// One-time prepayment such as 10000@24 ($10,000 with payment 24); repeat for more
+    #[arg(long, value_name = "AMOUNT@MONTH", value_parser = parse_prepayment)]
+    pub prepay: Vec<Prepayment>,
+
+    /// Run a saved scenario again; flags given alongside it override its inputs
+    #[arg(long, value_name = "NAME")]
+    pub scenario: Option<String>,
+
+    /// Save this run's inputs as a scenario under NAME
+    #[arg(long, value_name = "NAME")]
+    pub save_scenario: Option<String>,
+
+    /// Print the comparison as a table, or as JSON on stdout for scripts
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, conflicts_with = "template")]
+    pub output: OutputFormat,
+
+    /// Also write the results: arrow, parquet, md or html, or chart, application, disclosure or pdf followed by a path
+    #[arg(long, num_args = 1..=2, value_names = ["FORMAT", "PATH"])]
+    pub export: Option<Vec<String>>,
+
+    /// Write the comparison, and any schedule browsed, to CSV when the session ends
+    #[arg(long, value_name = "PATH")]
+    pub export_csv: Option<String>,
+
+    /// Rates to show per bank: midpoint, or range for best and worst case too (default from banks.yaml)
+    #[arg(long, value_name = "DISPLAY", value_parser = parse_rate_display)]
+    pub rates: Option<RateDisplay>,
+
+    /// How quoted rates compound for banks that do not say in banks.yaml: monthly, or semi-annual as Canadian fixed mortgages are quoted
+    #[arg(long, value_name = "COMPOUNDING", global = true, value_parser = parse_compounding)]
+    pub compounding: Option<Compounding>,
+
+    /// Order the comparison by payment, interest or rate (default: the order banks are configured in)
+    #[arg(long, value_enum)]
+    pub sort: Option<SortBy>,
+
+    /// Leave out offers with a monthly payment above AMOUNT
+    #[arg(long, value_name = "AMOUNT")]
+    pub max_payment: Option<f64>,
+
+    /// Leave out offers priced below PERCENT
+    #[arg(long, value_name = "PERCENT")]
+    pub min_rate: Option<f64>,
+
+    /// Leave out offers priced above PERCENT
+    #[arg(long, value_name = "PERCENT")]
+    pub max_rate: Option<f64>,
+
+    /// Comparison table columns, comma separated: rate, apr, payment, total_interest, total_cost, fees, rating, balance
+    #[arg(long)]
+    pub columns: Option<String>,
+
+    /// Render the results through a Handlebars template
+    #[arg(long, value_name = "FILE")]
+    pub template: Option<String>,
+
+    /// Currency amounts are in, as an ISO code like EUR or JPY (default from banks.yaml, else USD)
+    #[arg(long, value_name = "CODE", global = true)]
+    pub currency: Option<String>,
+
+    /// Locale whose separators amounts are written with, like de_DE (default from the environment)
+    #[arg(long, value_name = "LOCALE", global = true)]
+    pub locale: Option<String>,
+
+    /// Language of prompts and tables: en, es or fr (default from the locale)
+    #[arg(long, value_name = "LANG", global = true)]
+    pub lang: Option<String>,
+
+    /// Date order: iso, us or dmy (default from the locale)
+    #[arg(long, value_name = "FORMAT")]
+    pub date_format: Option<String>,
+
+    /// Print long tables without a pager
+    #[arg(long)]
+    pub no_pager: bool,
+
+    /// Fetch market rates now instead of reusing ones fetched recently
+    #[arg(long)]
+    pub refresh_rates: bool,
+
+    /// Leave this comparison out of the history
+    #[arg(long)]
+    pub no_history: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SortBy {
+    Payment,
+    Interest,
+    Rate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum BatchFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List the configured banks and check the config for problems, or change them
+    Banks {
+        #[command(subcommand)]
+        command: Option<BanksCommand>,
+    },
+    /// Work with the bank config
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Browse past comparisons and reopen their results
+    History {
+        #[command(subcommand)]
+        command: Option<HistoryCommand>,
+    },
+    /// List or delete saved scenarios
+    Scenarios {
+        #[command(subcommand)]
+        command: Option<ScenariosCommand>,
+    },
+    /// Install curated lender data as banks.yaml
+    Data {
+        #[command(subcommand)]
+        command: DataCommand,
+    },
+    /// Compare every loan listed in a YAML, TOML or JSON file of scenarios
+    Batch {
+        path: PathBuf,
+        /// Print one combined table, or CSV or JSON on stdout
+        #[arg(long, value_enum, default_value_t = BatchFormat::Table)]
+        format: BatchFormat,
+    },
+    /// Compare renting against buying a home with the recommended mortgage over the years given
+    RentVsBuy(RentVsBuyArgs),
+    /// Plan paying off credit cards and other revolving debts from one monthly budget, highest APR first against smallest balance first
+    Debts {
+        /// YAML, TOML or JSON file listing the debts, and optionally the budget
+        path: Option<PathBuf>,
+        /// A debt as NAME:BALANCE:APR:MINIMUM, like Visa:5200:22.9:150; repeat for each debt
+        #[arg(long = "debt", value_name = "DEBT", value_parser = parse_debt)]
+        debts: Vec<Debt>,
+        /// Total paid toward the debts each month, in dollars
+        #[arg(long, value_name = "AMOUNT")]
+        budget: Option<f64>,
+    },
+    /// Answer comparisons over HTTP: POST /compare with a JSON loan for the `--output json` document
+    Serve {
+        /// Address and port to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+}
+
+// Anything without a default is asked for, or is an error when stdin is
+// not a terminal; property tax and insurance are then left out.
+#[derive(Debug, Args)]
+pub struct RentVsBuyArgs {
+    /// Purchase price of the home in dollars
+    #[arg(long)]
+    pub price: Option<f64>,
+
+    /// Down payment, in dollars or as a percent like 20%
+    #[arg(long, value_name = "AMOUNT|PERCENT", default_value = "20%", value_parser = parse_down_payment)]
+    pub down: DownPayment,
+
+    /// Mortgage term in years, or in months like 180m
+    #[arg(long, default_value = "30")]
+    pub term: String,
+
+    /// Credit score
+    #[arg(long, value_parser = clap::value_parser!(u16).range(300..=850))]
+    pub score: Option<u16>,
+
+    /// Monthly rent in dollars for a comparable home
+    #[arg(long, value_name = "AMOUNT")]
+    pub rent: Option<f64>,
+
+    /// Yearly rent increase in percent
+    #[arg(long, value_name = "PERCENT", default_value_t = 3.0, allow_negative_numbers = true)]
+    pub rent_increase: f64,
+
+    /// Monthly renter's insurance in dollars
+    #[arg(long, value_name = "AMOUNT", default_value_t = 15.0)]
+    pub renters_insurance: f64,
+
+    /// Yearly property tax in dollars
+    #[arg(long, value_name = "AMOUNT")]
+    pub property_tax: Option<f64>,
+
+    /// Yearly homeowner's insurance in dollars
+    #[arg(long, value_name = "AMOUNT")]
+    pub insurance: Option<f64>,
+
+    /// Monthly HOA dues in dollars
+    #[arg(long, value_name = "AMOUNT", default_value_t = 0.0)]
+    pub hoa: f64,
+
+    /// Yearly maintenance in percent of the home's value
+    #[arg(long, value_name = "PERCENT", default_value_t = 1.0)]
+    pub maintenance: f64,
+
+    /// Yearly change in the home's value in percent
+    #[arg(long, value_name = "PERCENT", default_value_t = 3.0, allow_negative_numbers = true)]
+    pub appreciation: f64,
+
+    /// Cost of selling in percent of the sale price
+    #[arg(long, value_name = "PERCENT", default_value_t = 6.0)]
+    pub selling_costs: f64,
+
+    /// Years to compare over
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..=40))]
+    pub years: u32,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BanksCommand {
+    /// List the configured banks and check the config for problems
+    List,
+    /// Add a bank, prompting for its name, rate ranges and minimum score
+    Add,
+    /// Change a bank's name, rate ranges or minimum score
+    Edit { name: Option<String> },
+    /// Remove a bank from the config
+    Remove { name: Option<String> },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HistoryCommand {
+    /// List past comparisons, newest first
+    List,
+    /// Show the full results of a past comparison
+    Show { run: usize },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ScenariosCommand {
+    /// List saved scenarios
+    List,
+    /// Delete a saved scenario
+    Delete { name: String },
+    /// Compare two saved scenarios at today's rates
+    Compare { first: String, second: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Check a bank config for entries that load but look wrong
+    Lint {
+        /// YAML, TOML or JSON config (default: the one --config or the usual search finds)
+        path: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DataCommand {
+    /// Show the bundled data packs
+    List,
+    /// Verify a bundled pack and make it the active banks.yaml
+    Install { pack: String },
+    /// Fetch newer rates for the installed pack
+    Update,
+}
+
+fn parse_loan_type(name: &str) -> Result<LoanType, String> {
+    LoanType::from_name(name).ok_or_else(|| "expected home, car, personal or student".to_string())
+}
+
+fn parse_frequency(name: &str) -> Result<PaymentFrequency, String> {
+    PaymentFrequency::from_name(name).ok_or_else(|| "expected weekly, bi-weekly, semi-monthly or monthly".to_string())
+}
+
+fn parse_rate_display(name: &str) -> Result<RateDisplay, String> {
+    RateDisplay::from_name(name).ok_or_else(|| "expected midpoint or range".to_string())
+}
+
+fn parse_compounding(name: &str) -> Result<Compounding, String> {
+    Compounding::from_name(name).ok_or_else(|| "expected monthly or semi-annual".to_string())
+}
+
+fn parse_down_payment(input: &str) -> Result<DownPayment, String> {
+    DownPayment::parse(input).ok_or_else(|| "expected an amount like 60000 or a percent like 20%".to_string())
+}
+
+fn parse_debt(input: &str) -> Result<Debt, String> {
+    let fields: Vec<&str> = input.rsplitn(4, ':').collect();
+    let [minimum, apr, balance, name] = fields[..] else {
+        return Err("expected NAME:BALANCE:APR:MINIMUM, such as Visa:5200:22.9:150".to_string());
+    };
+    let amount = |field: &str, what: &str| {
+        Decimal::from_str(&field.trim().trim_start_matches('$').replace(',', ""))
+            .map_err(|_| format!("'{}' is not a {}", field, what))
+    };
+    Ok(Debt {
+        name: name.trim().to_string(),
+        balance: amount(balance, "balance")?,
+        apr: amount(apr.trim_end_matches('%'), "percent")?,
+        minimum: amount(minimum, "minimum payment")?,
+    })
+}
+
+fn parse_prepayment(input: &str) -> Result<Prepayment, String> {
+    let (amount, month) = input
+        .split_once('@')
+        .ok_or("expected AMOUNT@MONTH, such as 10000@24")?;
+    let amount = Decimal::from_str(&amount.trim().trim_start_matches('$').replace(',', ""))
+        .ok()
+        .filter(|amount| *amount > Decimal::ZERO)
+        .ok_or_else(|| format!("'{}' is not an amount above 0", amount))?;
+    let month = month
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|month| *month >= 1)
+        .ok_or_else(|| format!("'{}' is not a payment number", month))?;
+    Ok(Prepayment { amount, month })
+}
+
+impl Cli {
+    pub fn has_housing_costs(&self) -> bool {
+        self.property_tax.is_some() || self.insurance.is_some() || self.hoa.is_some()
+    }
+
+    pub fn has_vehicle_costs(&self) -> bool {
+        self.sales_tax.is_some() || self.trade_in.is_some() || self.dealer_fees.is_some()
+    }
+
+    pub fn has_deferment(&self) -> bool {
+        self.in_school.is_some() || self.grace.is_some()
+    }
+
+    // Fills in whatever the command line left out from a saved scenario.
+    // Housing costs and deferment only carry over while the loan type does.
+    pub fn apply_scenario(&mut self, scenario: &Scenario) -> Result<(), String> {
+        let loan_type = parse_loan_type(&scenario.loan_type)?;
+        let same_type = self.loan_type.as_ref().is_none_or(|given| given.get_name() == loan_type.get_name());
+        self.loan_type.get_or_insert(loan_type);
+        if self.amount.is_none() && self.price.is_none() && self.payment.is_none() {
+            self.amount = scenario.amount;
+            self.price = scenario.price;
+            if self.down.is_none() {
+                self.down = scenario.down_payment.and_then(Decimal::from_f64).map(DownPayment::Amount);
+            }
+            if !self.has_vehicle_costs() {
+                self.sales_tax = scenario.sales_tax_rate;
+                self.trade_in = scenario.trade_in_value;
+                self.trade_in_payoff = scenario.trade_in_payoff;
+                self.dealer_fees = scenario.dealer_fees;
+            }
+        }
+        self.term.get_or_insert_with(|| format!("{}m", scenario.term_months));
+        self.score.get_or_insert(scenario.credit_score);
+        if self.frequency.is_none() {
+            self.frequency = scenario.payment_frequency.as_deref().map(parse_frequency).transpose()?;
+        }
+        if same_type && !self.has_housing_costs() {
+            self.property_tax = scenario.property_tax;
+            self.insurance = scenario.insurance;
+            self.hoa = scenario.hoa_dues;
+        }
+        if same_type && !self.has_deferment() {
+            self.in_school = scenario.in_school_months;
+            self.grace = scenario.grace_months;
+        }
+        if self.income.is_none() {
+            self.income = scenario.gross_monthly_income;
+            self.debts = scenario.monthly_debts;
+        }
+        if self.prepay.is_empty() {
+            self.prepay = scenario
+                .prepayments
+                .iter()
+                .filter_map(|prepayment| {
+                    Some(Prepayment {
+                        amount: Decimal::from_f64(prepayment.amount)?,
+                        month: prepayment.month,
+                    })
+                })
+                .collect();
+        }
+        Ok(())
+    }
+
+    // Fills in whatever the command line left out from one answer per line,
+    // taken in the order the questions are asked: loan type, amount, term
+    // and credit score
+    pub fn apply_answers<'a>(&mut self, answers: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+        let mut answers = answers.into_iter();
+        let mut next = |question: &str| answers.next().ok_or_else(|| format!("no answer given for the {}", question));
+        if self.loan_type.is_none() {
+            self.loan_type = Some(parse_loan_type(next("loan type")?)?);
+        }
+        if self.amount.is_none() && self.price.is_none() && self.payment.is_none() {
+            let answer = next("loan amount")?;
+            let amount = answer.trim_start_matches('$').replace(',', "");
+            self.amount = Some(amount.parse().map_err(|_| format!("loan amount '{}' is not a number", answer))?);
+        }
+        if self.term.is_none() {
+            self.term = Some(next("loan term")?.to_string());
+        }
+        if self.score.is_none() {
+            let answer = next("credit score")?;
+            let score = answer.parse().ok().filter(|score| (300..=850).contains(score));
+            self.score = Some(score.ok_or_else(|| format!("credit score '{}' is not between 300 and 850", answer))?);
+        }
+        match answers.next() {
+            Some(extra) => Err(format!("unexpected answer '{}' after the credit score", extra)),
+            None => Ok(()),
+        }
+    }
+
+    // The flags still needed to run without prompting
+    pub fn missing_inputs(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if self.loan_type.is_none() {
+            missing.push("--type");
+        }
+        if self.price.is_some() {
+            if self.down.is_none() {
+                missing.push("--down");
+            }
+        } else if self.amount.is_none() && self.payment.is_none() {
+            missing.push("--amount");
+        }
+        if self.term.is_none() {
+            missing.push("--term");
+        }
+        if self.score.is_none() {
+            missing.push("--score");
+        }
+        missing
+    }
+
+    pub fn is_scripted(&self) -> bool {
+        self.missing_inputs().is_empty()
+    }
+}
@@ -0,0 +1,46 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::{format_money, Bank};
+
+#[derive(Debug, Clone)]
+pub struct Collateral {
+    pub description: String,
+    pub value: Decimal,
+}
+
+pub fn combined_value(collateral: &[Collateral]) -> Decimal {
+    collateral.iter().map(|item| item.value).sum()
+}
+
+// Loan-to-value as a percentage of the combined collateral value
+pub fn loan_to_value(loan_amount: Decimal, collateral: &[Collateral]) -> Decimal {
+    loan_amount / combined_value(collateral) * dec!(100)
+}
+
+impl Bank {
+    // Reason the bank would decline the collateral package, if any
+    pub fn check_collateral(&self, loan_amount: Decimal, collateral: &[Collateral]) -> Option<String> {
+        if collateral.is_empty() {
+            return None;
+        }
+
+        if collateral.len() > 1 && !self.allows_cross_collateral {
+            return Some(format!(
+                "does not accept loans secured by multiple assets ({} items offered)",
+                collateral.len()
+            ));
+        }
+
+        let ltv = loan_to_value(loan_amount, collateral);
+        match self.max_ltv {
+            Some(max_ltv) if ltv > max_ltv => Some(format!(
+                "LTV of {:.1}% on {} of collateral exceeds its {:.1}% maximum",
+                ltv.round_dp(1),
+                format_money(combined_value(collateral)),
+                max_ltv.round_dp(1)
+            )),
+            _ => None,
+        }
+    }
+}
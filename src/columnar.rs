@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Decimal128Array, RecordBatch, StringArray, UInt32Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use rust_decimal::Decimal;
+
+use crate::interest::{InterestMethod, LoanCost};
+use crate::schedule::{PaymentStrategy, Schedule};
+use crate::LoanCalculator;
+
+// Money is stored to the cent and rates to 1/10000 of a percent
+const MONEY_PRECISION: u8 = 18;
+const MONEY_SCALE: u32 = 2;
+const RATE_PRECISION: u8 = 9;
+const RATE_SCALE: u32 = 4;
+
+pub struct ExportData {
+    pub interest_method: InterestMethod,
+    pub loan_amount: Decimal,
+    pub loan_term: u32,
+    pub comparison: Vec<(String, Decimal, LoanCost)>,
+    // Only amortizing loans have a month-by-month schedule
+    pub schedules: Vec<(String, Decimal, Schedule)>,
+    // Stored as schema metadata on every table
+    pub provenance: Vec<(String, String)>,
+}
+
+impl ExportData {
+    pub fn new(
+        calculator: &LoanCalculator,
+        interest_method: InterestMethod,
+        offers: &[(String, Decimal)],
+        loan_amount: Decimal,
+        loan_term: u32,
+        provenance: Vec<(String, String)>,
+    ) -> Self {
+        let comparison = offers
+            .iter()
+            .filter_map(|(name, rate)| {
+                calculator
+                    .calculate_cost(&interest_method, loan_amount, *rate, loan_term)
+                    .map(|cost| (name.clone(), *rate, cost))
+            })
+            .collect();
+
+        let schedules = if interest_method == InterestMethod::Amortizing {
+            offers
+                .iter()
+                .map(|(name, rate)| {
+                    let schedule = calculator.generate_schedule(
+                        loan_amount,
+                        *rate,
+                        loan_term,
+                        &PaymentStrategy::Standard,
+                    );
+                    (name.clone(), *rate, schedule)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            interest_method,
+            loan_amount,
+            loan_term,
+            comparison,
+            schedules,
+            provenance,
+        }
+    }
+
+    pub fn comparison_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let method = self.interest_method.get_description();
+        let rows = &self.comparison;
+        let schema = Schema::new(vec![
+            Field::new("bank", DataType::Utf8, false),
+            Field::new("interest_method", DataType::Utf8, false),
+            Field::new("loan_amount", money_type(), false),
+            Field::new("term_months", DataType::UInt32, false),
+            Field::new("rate", rate_type(), false),
+            Field::new("effective_rate", rate_type(), true),
+            Field::new("monthly_payment", money_type(), false),
+            Field::new("total_interest", money_type(), false),
+            Field::new("total_payment", money_type(), false),
+        ])
+        .with_metadata(self.metadata());
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|(name, _, _)| name))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|_| &method))),
+            money_column(rows.iter().map(|_| Some(self.loan_amount)))?,
+            Arc::new(UInt32Array::from_iter_values(rows.iter().map(|_| self.loan_term))),
+            rate_column(rows.iter().map(|(_, rate, _)| Some(*rate)))?,
+            rate_column(rows.iter().map(|(_, _, cost)| cost.effective_rate))?,
+            money_column(rows.iter().map(|(_, _, cost)| Some(cost.monthly_payment)))?,
+            money_column(rows.iter().map(|(_, _, cost)| Some(cost.total_interest)))?,
+            money_column(rows.iter().map(|(_, _, cost)| Some(cost.total_payment)))?,
+        ];
+
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+
+    // One row per payment across every offer's schedule
+    pub fn schedules_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let rows: Vec<_> = self
+            .schedules
+            .iter()
+            .flat_map(|(name, rate, schedule)| {
+                schedule
+                    .rows
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, row)| (name, *rate, index as u32 + 1, row))
+            })
+            .collect();
+        let schema = Schema::new(vec![
+            Field::new("bank", DataType::Utf8, false),
+            Field::new("rate", rate_type(), false),
+            Field::new("payment_number", DataType::UInt32, false),
+            Field::new("payment", money_type(), false),
+            Field::new("interest", money_type(), false),
+            Field::new("principal", money_type(), false),
+            Field::new("balance", money_type(), false),
+        ])
+        .with_metadata(self.metadata());
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|(name, _, _, _)| *name))),
+            rate_column(rows.iter().map(|(_, rate, _, _)| Some(*rate)))?,
+            Arc::new(UInt32Array::from_iter_values(rows.iter().map(|(_, _, number, _)| *number))),
+            money_column(rows.iter().map(|(_, _, _, row)| Some(row.payment)))?,
+            money_column(rows.iter().map(|(_, _, _, row)| Some(row.interest)))?,
+            money_column(rows.iter().map(|(_, _, _, row)| Some(row.payment - row.interest)))?,
+            money_column(rows.iter().map(|(_, _, _, row)| Some(row.balance)))?,
+        ];
+
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        self.provenance.iter().cloned().collect()
+    }
+}
+
+fn money_type() -> DataType {
+    DataType::Decimal128(MONEY_PRECISION, MONEY_SCALE as i8)
+}
+
+fn rate_type() -> DataType {
+    DataType::Decimal128(RATE_PRECISION, RATE_SCALE as i8)
+}
+
+fn to_scaled(value: Decimal, scale: u32) -> i128 {
+    let mut value = value.round_dp(scale);
+    value.rescale(scale);
+    value.mantissa()
+}
+
+fn money_column(values: impl Iterator<Item = Option<Decimal>>) -> Result<ArrayRef, ArrowError> {
+    let array = values
+        .map(|value| value.map(|value| to_scaled(value, MONEY_SCALE)))
+        .collect::<Decimal128Array>()
+        .with_precision_and_scale(MONEY_PRECISION, MONEY_SCALE as i8)?;
+    Ok(Arc::new(array))
+}
+
+fn rate_column(values: impl Iterator<Item = Option<Decimal>>) -> Result<ArrayRef, ArrowError> {
+    let array = values
+        .map(|value| value.map(|value| to_scaled(value, RATE_SCALE)))
+        .collect::<Decimal128Array>()
+        .with_precision_and_scale(RATE_PRECISION, RATE_SCALE as i8)?;
+    Ok(Arc::new(array))
+}
+
+impl ExportData {
+    // Each table with its file suffix. The batches can be handed to Polars or
+    // any other Arrow consumer as they are, without copying.
+    pub fn batches(&self) -> Result<Vec<(&'static str, RecordBatch)>, ArrowError> {
+        let mut batches = vec![("comparison", self.comparison_batch()?)];
+        if !self.schedules.is_empty() {
+            batches.push(("schedules", self.schedules_batch()?));
+        }
+        Ok(batches)
+    }
+}
+
+// Writes `<prefix>_comparison.arrow` and, for amortizing loans,
+// `<prefix>_schedules.arrow` in the Arrow IPC file format. Returns the files
+// written.
+pub fn write_arrow_ipc(data: &ExportData, prefix: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut written = Vec::new();
+    for (name, batch) in data.batches()? {
+        let path = format!("{}_{}.arrow", prefix, name);
+        let file = File::create(&path)?;
+        let mut writer = arrow_ipc::writer::FileWriter::try_new(file, &batch.schema())?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+// Same layout as the Arrow export, as `<prefix>_<table>.parquet`
+#[cfg(feature = "parquet")]
+pub fn write_parquet(data: &ExportData, prefix: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut written = Vec::new();
+    for (name, batch) in data.batches()? {
+        let path = format!("{}_{}.parquet", prefix, name);
+        let file = File::create(&path)?;
+        // Parquet readers that ignore the embedded Arrow schema still see
+        // the provenance as file metadata
+        let metadata = data
+            .provenance
+            .iter()
+            .map(|(key, value)| parquet::file::metadata::KeyValue::new(key.clone(), value.clone()))
+            .collect();
+        let properties = parquet::file::properties::WriterProperties::builder()
+            .set_key_value_metadata(Some(metadata))
+            .build();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        written.push(path);
+    }
+    Ok(written)
+}
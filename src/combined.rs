@@ -0,0 +1,67 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::schedule::Schedule;
+
+// Several loans carried at the same time, aggregated month by month
+#[derive(Debug, Clone, Default)]
+pub struct CombinedSchedule {
+    parts: Vec<(Decimal, Decimal, Schedule)>,
+}
+
+impl CombinedSchedule {
+    pub fn add(&mut self, principal: Decimal, annual_rate: Decimal, schedule: Schedule) {
+        self.parts.push((principal, annual_rate, schedule));
+    }
+
+    // Rate weighted by each loan's starting balance
+    pub fn blended_rate(&self) -> Decimal {
+        let total_principal: Decimal = self.parts.iter().map(|(principal, _, _)| *principal).sum();
+        if total_principal.is_zero() {
+            return dec!(0);
+        }
+
+        let weighted: Decimal = self
+            .parts
+            .iter()
+            .map(|(principal, rate, _)| *principal * *rate)
+            .sum();
+        weighted / total_principal
+    }
+
+    pub fn num_payments(&self) -> u32 {
+        self.parts
+            .iter()
+            .map(|(_, _, schedule)| schedule.num_payments())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn payment_in(&self, month: u32) -> Decimal {
+        self.parts
+            .iter()
+            .map(|(_, _, schedule)| schedule.payment_in(month))
+            .sum()
+    }
+
+    pub fn interest_in(&self, month: u32) -> Decimal {
+        self.parts
+            .iter()
+            .map(|(_, _, schedule)| schedule.interest_in(month))
+            .sum()
+    }
+
+    pub fn balance_after(&self, month: u32) -> Decimal {
+        self.parts
+            .iter()
+            .map(|(_, _, schedule)| schedule.balance_after(month))
+            .sum()
+    }
+
+    pub fn total_interest(&self) -> Decimal {
+        self.parts
+            .iter()
+            .map(|(_, _, schedule)| schedule.total_interest())
+            .sum()
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use crate::Error;
+
+// Names a config directory is searched for, in order
+pub const CONFIG_FILES: [&str; 4] = ["banks.yaml", "banks.yml", "banks.toml", "banks.json"];
+
+// Points at a bank config to use in place of the usual search
+pub const CONFIG_ENV: &str = "LOANS_CONFIG";
+
+// The banks shipped with the source, used when no config is found
+pub const DEFAULT_CONFIG: &str = include_str!("../banks.yaml");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    File(PathBuf),
+    Builtin,
+}
+
+impl ConfigSource {
+    pub fn get_description(&self) -> String {
+        match self {
+            ConfigSource::File(path) => path.display().to_string(),
+            ConfigSource::Builtin => "the built-in banks".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    // By extension; None for anything else
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "toml" => Some(ConfigFormat::Toml),
+            "json" => Some(ConfigFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+fn find_in(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILES.iter().map(|file| dir.join(file)).find(|path| path.exists())
+}
+
+// $XDG_CONFIG_HOME/loans, or ~/.config/loans when it is unset
+pub fn user_config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("loans"))
+}
+
+// $XDG_DATA_HOME/loans, or ~/.local/share/loans, for what the tool saves
+// between runs
+pub fn user_data_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))?;
+    Some(base.join("loans"))
+}
+
+// Written beside `path` and renamed over it, so an interrupted write never
+// leaves half a file
+pub fn write_atomically(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("loans");
+    let staging = path.with_file_name(format!(".{}.tmp", file_name));
+    std::fs::write(&staging, contents)?;
+    std::fs::rename(&staging, path)
+}
+
+// An explicit path (--config), then LOANS_CONFIG, then the user config
+// directory, then the working directory, then the built-in banks. Explicit
+// paths are used even when missing, so the error names them.
+pub fn resolve_config(explicit: Option<&Path>) -> ConfigSource {
+    if let Some(path) = explicit {
+        return ConfigSource::File(path.to_path_buf());
+    }
+    if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        return ConfigSource::File(PathBuf::from(path));
+    }
+    user_config_dir()
+        .and_then(|dir| find_in(&dir))
+        .or_else(|| find_in(Path::new("")))
+        .map(ConfigSource::File)
+        .unwrap_or(ConfigSource::Builtin)
+}
+
+// Every format goes through serde_path_to_error, so a bad value is reported
+// with the field it sits in, such as banks[1].min_credit_score
+pub fn parse_config<T: DeserializeOwned>(contents: &str, format: ConfigFormat, file: &str) -> Result<T, Error> {
+    let (field, message) = match format {
+        ConfigFormat::Yaml => match serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(contents)) {
+            Ok(config) => return Ok(config),
+            Err(e) => {
+                let field = e.path().to_string();
+                let message = without_yaml_location(e.into_inner().to_string(), &field);
+                (field, message)
+            }
+        },
+        ConfigFormat::Toml => match serde_path_to_error::deserialize(toml::Deserializer::new(contents)) {
+            Ok(config) => return Ok(config),
+            Err(e) => (e.path().to_string(), e.into_inner().message().to_string()),
+        },
+        ConfigFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_str(contents);
+            match serde_path_to_error::deserialize(&mut deserializer) {
+                Ok(config) => return Ok(config),
+                Err(e) => (e.path().to_string(), e.into_inner().to_string()),
+            }
+        }
+    };
+    Err(Error::Parse {
+        file: file.to_string(),
+        // The document itself is `.`; there is no field to name
+        field: (field != ".").then_some(field),
+        message,
+    })
+}
+
+// serde_yaml starts its messages with where the error is: the field itself,
+// or for an unknown field the entry holding it. Both are part of the path
+// the error already names.
+fn without_yaml_location(message: String, field: &str) -> String {
+    match message.split_once(": ") {
+        Some((location, rest))
+            if field
+                .strip_prefix(location)
+                .is_some_and(|below| below.is_empty() || below.starts_with(['.', '['])) =>
+        {
+            rest.to_string()
+        }
+        _ => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BanksConfig;
+
+    fn parse_error(contents: &str, format: ConfigFormat) -> String {
+        parse_config::<BanksConfig>(contents, format, "banks").unwrap_err().to_string()
+    }
+
+    #[test]
+    fn errors_name_the_file_and_nested_field_once() {
+        let yaml = "banks:\n  - name: A\n    home_loan_range:\n      min: low\n      max: 7\n";
+        assert_eq!(
+            parse_error(yaml, ConfigFormat::Yaml),
+            "banks: banks[0].home_loan_range.min: invalid type: string \"low\", expected f64 at line 4 column 12"
+        );
+        let yaml = "interest_conventions:\n  car: actual_365\n  boat: monthly\n";
+        assert_eq!(
+            parse_error(yaml, ConfigFormat::Yaml),
+            "banks: interest_conventions.boat: unknown field `boat`, expected one of `home`, `car`, `personal`, `student` at line 3 column 3"
+        );
+        let toml = "[[banks]]\nname = 'A'\n[banks.home_loan_range]\nmin = 'low'\nmax = 7\n";
+        assert_eq!(
+            parse_error(toml, ConfigFormat::Toml),
+            "banks: banks[0].home_loan_range.min: invalid type: string \"low\", expected f64"
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+
+use crate::dates::{Calendar, Closing};
+use crate::interest::InterestMethod;
+use crate::{LoanCalculator, LoanType};
+
+// 10^0 through 10^36, for taking Decimal mantissas apart in cent arithmetic
+const POWERS_OF_TEN: [u128; 37] = {
+    let mut powers = [1u128; 37];
+    let mut index = 1;
+    while index < powers.len() {
+        powers[index] = powers[index - 1] * 10;
+        index += 1;
+    }
+    powers
+};
+
+/// How amounts are rounded to the cent: half-up as most servicers bill, or
+/// banker's rounding, which sends halves to the even cent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    #[default]
+    HalfUp,
+    Bankers,
+}
+
+impl RoundingMode {
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            RoundingMode::HalfUp => "half up",
+            RoundingMode::Bankers => "banker's",
+        }
+    }
+}
+
+/// Rounding to the smallest unit of the currency amounts are billed in.
+/// Methods here call that unit a cent, though it is the whole yen or won in
+/// currencies without one, and a thousandth of a Kuwaiti dinar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rounding {
+    pub mode: RoundingMode,
+    pub minor_units: u32,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Rounding::cents(RoundingMode::default())
+    }
+}
+
+impl Rounding {
+    pub fn new(mode: RoundingMode, minor_units: u32) -> Self {
+        Rounding { mode, minor_units }
+    }
+
+    // Two decimals, the cents of most currencies
+    pub fn cents(mode: RoundingMode) -> Self {
+        Rounding::new(mode, 2)
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        self.mode.get_description()
+    }
+
+    pub fn round_cents(&self, amount: Decimal) -> Decimal {
+        let strategy = match self.mode {
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::Bankers => RoundingStrategy::MidpointNearestEven,
+        };
+        amount.round_dp_with_strategy(self.minor_units, strategy)
+    }
+
+    /// `round_cents(amount * rate)`, the interest a schedule bills every
+    /// period, without building the full-precision product.
+    pub fn round_product(&self, amount: Decimal, rate: Decimal) -> Decimal {
+        self.try_round_product(amount, rate).unwrap_or_else(|| self.round_cents(amount * rate))
+    }
+
+    // The product is exact in 128 bits, where Decimal rounds it to 28 digits
+    // before it is rounded to the cent. The two only disagree when the exact
+    // product lies within a hair of a half cent, and that is left to Decimal.
+    pub(crate) fn try_round_product(&self, amount: Decimal, rate: Decimal) -> Option<Decimal> {
+        if amount.is_sign_negative() || rate.is_sign_negative() {
+            return None;
+        }
+        let product = (amount.mantissa() as u128).checked_mul(rate.mantissa() as u128)?;
+        let scale = amount.scale() + rate.scale();
+        if scale <= self.minor_units {
+            return Decimal::try_from_i128_with_scale(product.try_into().ok()?, scale).ok();
+        }
+        let divisor = *POWERS_OF_TEN.get((scale - self.minor_units) as usize)?;
+        let cents = product / divisor;
+        let rest = product - cents * divisor;
+        let exact = product < 1 << 96 && scale <= 28;
+        if !exact && (cents > 1_000_000_000_000_000 || (2 * rest).abs_diff(divisor) < divisor / 1_000_000) {
+            return None;
+        }
+        let round_up = match self.mode {
+            RoundingMode::HalfUp => 2 * rest >= divisor,
+            RoundingMode::Bankers => 2 * rest > divisor || (2 * rest == divisor && cents % 2 == 1),
+        };
+        Decimal::try_from_i128_with_scale((cents + u128::from(round_up)).try_into().ok()?, self.minor_units).ok()
+    }
+
+    // An amount with no more decimals than the currency has as a count of
+    // cents
+    pub(crate) fn cents_of(&self, amount: Decimal) -> Option<i128> {
+        let scale = amount.scale();
+        if scale > self.minor_units {
+            return None;
+        }
+        let factor = *POWERS_OF_TEN.get((self.minor_units - scale) as usize)?;
+        amount.mantissa().checked_mul(factor as i128)
+    }
+
+    // A count of cents as an amount, without the range checks of
+    // `Decimal::from_i128_with_scale` for the counts that fit in 64 bits
+    pub(crate) fn amount_of(&self, cents: i128) -> Decimal {
+        match i64::try_from(cents) {
+            Ok(count) => {
+                let magnitude = count.unsigned_abs();
+                Decimal::from_parts(magnitude as u32, (magnitude >> 32) as u32, 0, count < 0, self.minor_units)
+            }
+            Err(_) => Decimal::from_i128_with_scale(cents, self.minor_units),
+        }
+    }
+}
+
+/// A period rate turned into binary fixed point once, so a schedule can
+/// bill interest on a balance of whole cents every period with one integer
+/// multiplication. Each amount it bills is the one [`Rounding::round_product`]
+/// gives.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CentRate {
+    rounding: Rounding,
+    rate: Decimal,
+    // The rate in units of 2^-RATE_BITS, for rates from 0 up to 64
+    fixed: Option<u128>,
+}
+
+const RATE_BITS: u32 = 90;
+
+// Truncating the rate and the product moves it by far less than this share
+// of a cent, so anything farther from a half cent rounds as Decimal rounds it
+const HALF_CENT_MARGIN: u128 = 1 << (RATE_BITS - 20);
+
+impl CentRate {
+    pub(crate) fn new(rounding: Rounding, rate: Decimal) -> Self {
+        CentRate {
+            rounding,
+            rate,
+            fixed: fixed_point(rate),
+        }
+    }
+
+    pub(crate) fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    pub(crate) fn interest(&self, balance: Decimal) -> Decimal {
+        let cents = self.rounding.cents_of(balance).and_then(|cents| u64::try_from(cents).ok());
+        match cents.and_then(|cents| self.interest_cents(cents)) {
+            Some(interest) => self.rounding.amount_of(i128::from(interest)),
+            None => self.rounding.round_product(balance, self.rate),
+        }
+    }
+
+    pub(crate) fn interest_cents(&self, balance: u64) -> Option<u64> {
+        let half = 1u128 << (RATE_BITS - 1);
+        match self.fixed {
+            // A balance under 2^32 cents times a rate under 2^96 fits
+            Some(fixed) if balance >> 32 == 0 => {
+                let product = u128::from(balance) * fixed;
+                let fraction = product & ((1 << RATE_BITS) - 1);
+                if fraction.abs_diff(half) >= HALF_CENT_MARGIN {
+                    let cents = (product >> RATE_BITS) + u128::from(fraction > half);
+                    return cents.try_into().ok();
+                }
+            }
+            _ => {}
+        }
+        // Near a half cent, or out of range, Decimal decides
+        let balance = self.rounding.amount_of(i128::from(balance));
+        let interest = self.rounding.round_product(balance, self.rate);
+        self.rounding.cents_of(interest)?.try_into().ok()
+    }
+}
+
+// floor(rate * 2^RATE_BITS) by long division of the mantissa, 30 bits at a
+// time, or None for rates that are negative or 64 and up
+fn fixed_point(rate: Decimal) -> Option<u128> {
+    if rate.is_sign_negative() {
+        return None;
+    }
+    let mantissa = rate.mantissa() as u128;
+    let divisor = POWERS_OF_TEN[rate.scale() as usize];
+    let whole = mantissa / divisor;
+    if whole >= 64 {
+        return None;
+    }
+    let mut rest = mantissa % divisor;
+    let mut fixed = whole;
+    for _ in 0..RATE_BITS / 30 {
+        rest <<= 30;
+        fixed = (fixed << 30) | (rest / divisor);
+        rest %= divisor;
+    }
+    Some(fixed)
+}
+
+/// How a lender counts interest between payments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum InterestConvention {
+    // A twelfth of the yearly rate each month, whatever its length
+    #[default]
+    #[serde(rename = "monthly")]
+    Monthly,
+    // Simple interest on the actual days between due dates, as most car
+    // loans accrue
+    #[serde(rename = "actual_365")]
+    Actual365,
+    // Every month counted as 30 days of a 360-day year, which only differs
+    // from monthly for the days before the first full period
+    #[serde(rename = "30_360")]
+    Thirty360,
+}
+
+impl InterestConvention {
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            InterestConvention::Monthly => "monthly",
+            InterestConvention::Actual365 => "actual/365",
+            InterestConvention::Thirty360 => "30/360",
+        }
+    }
+
+    // Days of interest between closing and the first full period, and the
+    // days of the year they are a share of
+    pub fn stub_days(&self, closing: &Closing) -> (u32, Decimal) {
+        match self {
+            InterestConvention::Thirty360 => (days_30_360(closing.date, closing.period_start()), dec!(360)),
+            InterestConvention::Monthly | InterestConvention::Actual365 => (closing.stub_days(), dec!(365)),
+        }
+    }
+}
+
+// The US (bond basis) 30/360 day count
+fn days_30_360(start: NaiveDate, end: NaiveDate) -> u32 {
+    let start_day = start.day().min(30);
+    let end_day = if start_day == 30 { end.day().min(30) } else { end.day() };
+    let days = (end.year() - start.year()) * 360
+        + (end.month() as i32 - start.month() as i32) * 30
+        + end_day as i32
+        - start_day as i32;
+    days.max(0) as u32
+}
+
+/// How often a quoted rate compounds. Canadian fixed mortgages are quoted
+/// compounded semi-annually, so the same quote costs less each month than a
+/// rate compounded monthly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compounding {
+    #[default]
+    Monthly,
+    SemiAnnual,
+}
+
+impl Compounding {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(['-', ' '], "_").as_str() {
+            "monthly" => Some(Compounding::Monthly),
+            "semi_annual" | "semi_annually" => Some(Compounding::SemiAnnual),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Compounding::Monthly => "monthly",
+            Compounding::SemiAnnual => "semi_annual",
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            Compounding::Monthly => "compounded monthly",
+            Compounding::SemiAnnual => "compounded semi-annually",
+        }
+    }
+
+    // The rate compounded monthly with the same effective yearly rate, which
+    // is what every payment formula expects
+    pub fn monthly_equivalent(&self, quoted_rate: Decimal) -> Decimal {
+        match self {
+            Compounding::Monthly => quoted_rate,
+            Compounding::SemiAnnual => dec!(1200) * (sixth_root(dec!(1) + quoted_rate / dec!(200)) - dec!(1)),
+        }
+    }
+
+    pub fn quoted_equivalent(&self, monthly_rate: Decimal) -> Decimal {
+        match self {
+            Compounding::Monthly => monthly_rate,
+            Compounding::SemiAnnual => {
+                let growth = crate::checked_pow(dec!(1) + monthly_rate / dec!(1200), 6).unwrap_or(dec!(1));
+                dec!(200) * (growth - dec!(1))
+            }
+        }
+    }
+}
+
+// Newton's method from the f64 estimate, which is already within a few
+// digits, so a handful of steps reaches Decimal precision
+fn sixth_root(value: Decimal) -> Decimal {
+    let mut root = value
+        .to_f64()
+        .and_then(|value| Decimal::from_f64(value.powf(1.0 / 6.0)))
+        .unwrap_or(dec!(1));
+    for _ in 0..4 {
+        let Some(fifth) = crate::checked_pow(root, 5) else {
+            break;
+        };
+        root -= (fifth * root - value) / (dec!(6) * fifth);
+    }
+    root
+}
+
+/// The interest convention per loan type; types left out are monthly.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InterestConventions {
+    #[serde(default)]
+    pub home: Option<InterestConvention>,
+    #[serde(default)]
+    pub car: Option<InterestConvention>,
+    #[serde(default)]
+    pub personal: Option<InterestConvention>,
+    #[serde(default)]
+    pub student: Option<InterestConvention>,
+}
+
+impl InterestConventions {
+    pub fn get(&self, loan_type: &LoanType) -> InterestConvention {
+        let convention = match loan_type {
+            LoanType::Home => self.home,
+            LoanType::Car => self.car,
+            LoanType::Personal => self.personal,
+            LoanType::Student => self.student,
+        };
+        convention.unwrap_or_default()
+    }
+}
+
+impl LoanCalculator {
+    // To the smallest unit of the calculator's currency, cents unless
+    // banks.yaml or `set_currency` names one without them
+    pub fn rounding(&self) -> Rounding {
+        Rounding::new(self.rounding, self.currency.unwrap_or_default().minor_units)
+    }
+
+    // The bank's own compounding, or the calculator's for banks that do not
+    // say and for rates that come from no bank
+    pub fn compounding(&self, bank_name: &str) -> Compounding {
+        self.get_bank(bank_name)
+            .and_then(|bank| bank.compounding)
+            .unwrap_or(self.compounding)
+    }
+
+    pub fn set_compounding(&mut self, compounding: Compounding) {
+        self.compounding = compounding;
+    }
+
+    // A rate as `bank_name` quotes it, converted to compound monthly
+    pub fn monthly_rate(&self, bank_name: &str, quoted_rate: Decimal) -> Decimal {
+        self.compounding(bank_name).monthly_equivalent(quoted_rate)
+    }
+
+    // The rate `bank_name` would quote for a rate compounded monthly
+    pub fn quoted_rate(&self, bank_name: &str, monthly_rate: Decimal) -> Decimal {
+        self.compounding(bank_name).quoted_equivalent(monthly_rate)
+    }
+
+    pub fn interest_convention(&self, loan_type: &LoanType) -> InterestConvention {
+        self.interest_conventions.get(loan_type)
+    }
+
+    // How a loan of this type is priced unless the user picks another method
+    pub fn default_interest_method(&self, loan_type: &LoanType, calendar: &Calendar) -> InterestMethod {
+        match self.interest_convention(loan_type) {
+            InterestConvention::Actual365 => InterestMethod::SimpleDaily {
+                days_early: 0,
+                calendar: calendar.clone(),
+            },
+            InterestConvention::Monthly | InterestConvention::Thirty360 => InterestMethod::Amortizing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cent_arithmetic_bills_what_decimal_does() {
+        let mut seed = 12345u64;
+        let mut next = move |bound: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        let modes = [RoundingMode::HalfUp, RoundingMode::Bankers];
+        for (mode, minor_units) in modes.into_iter().flat_map(|mode| [(mode, 0), (mode, 2), (mode, 3)]) {
+            let rounding = Rounding::new(mode, minor_units);
+            for _ in 0..20_000 {
+                let balance = Decimal::new(next(100_000_000_000) as i64, minor_units);
+                let annual_rate = Decimal::new(next(3_000) as i64, 2);
+                let rate = annual_rate / dec!(100) / Decimal::from([12, 26, 365][next(3) as usize]);
+                let expected = rounding.round_cents(balance * rate);
+                assert_eq!(rounding.round_product(balance, rate), expected, "{} at {}", balance, rate);
+                assert_eq!(CentRate::new(rounding, rate).interest(balance), expected, "{} at {}", balance, rate);
+            }
+        }
+        // Exact half cents go up, or to the even cent
+        assert_eq!(Rounding::cents(RoundingMode::HalfUp).round_product(dec!(0.25), dec!(0.1)), dec!(0.03));
+        assert_eq!(Rounding::cents(RoundingMode::Bankers).round_product(dec!(0.25), dec!(0.1)), dec!(0.02));
+        assert_eq!(CentRate::new(Rounding::cents(RoundingMode::Bankers), dec!(0.1)).interest(dec!(0.35)), dec!(0.04));
+        // Yen round to the whole yen and dinars to the fils
+        assert_eq!(Rounding::new(RoundingMode::HalfUp, 0).round_product(dec!(25), dec!(0.1)), dec!(3));
+        assert_eq!(Rounding::new(RoundingMode::Bankers, 0).round_product(dec!(25), dec!(0.1)), dec!(2));
+        assert_eq!(Rounding::new(RoundingMode::HalfUp, 3).round_product(dec!(0.025), dec!(0.1)), dec!(0.003));
+        assert_eq!(CentRate::new(Rounding::new(RoundingMode::HalfUp, 0), dec!(0.004)).interest(dec!(1000)), dec!(4));
+    }
+
+    #[test]
+    fn monthly_rates_need_no_conversion() {
+        assert_eq!(Compounding::Monthly.monthly_equivalent(dec!(6.5)), dec!(6.5));
+    }
+
+    #[test]
+    fn semi_annual_rate_has_the_same_yearly_yield() {
+        // 6% compounded semi-annually is 1.03^(1/6) - 1 a month
+        let monthly = Compounding::SemiAnnual.monthly_equivalent(dec!(6));
+        assert!((monthly - dec!(5.926346)).abs() < dec!(0.000001), "{}", monthly);
+
+        let yearly = crate::checked_pow(dec!(1) + monthly / dec!(1200), 12).unwrap();
+        assert!((yearly - dec!(1.0609)).abs() < dec!(0.0000000001), "{}", yearly);
+        assert!((Compounding::SemiAnnual.quoted_equivalent(monthly) - dec!(6)).abs() < dec!(0.0000000001));
+    }
+}
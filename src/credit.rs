@@ -0,0 +1,58 @@
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+
+// Percentage points added to the rate for scores at or above `min_score`,
+// up to the next tier; negative changes are discounts
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreditTierConfig {
+    pub min_score: u16,
+    pub rate_change: f64,
+}
+
+// The adjustments used when banks.yaml does not list any
+pub fn default_credit_tiers() -> Vec<CreditTierConfig> {
+    [(800, -0.5), (750, -0.25), (700, 0.0), (650, 0.5), (600, 1.0), (300, 2.0)]
+        .into_iter()
+        .map(|(min_score, rate_change)| CreditTierConfig { min_score, rate_change })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct CreditTiers {
+    // Highest score first
+    tiers: Vec<(u16, Decimal)>,
+}
+
+impl TryFrom<Vec<CreditTierConfig>> for CreditTiers {
+    type Error = String;
+
+    fn try_from(config: Vec<CreditTierConfig>) -> Result<Self, String> {
+        if config.is_empty() {
+            return Err("credit_tiers must list at least one tier".to_string());
+        }
+        let mut tiers = Vec::with_capacity(config.len());
+        for tier in config {
+            if !(300..=850).contains(&tier.min_score) {
+                return Err(format!("credit tier min_score of {} is not a credit score", tier.min_score));
+            }
+            let rate_change = Decimal::from_f64(tier.rate_change)
+                .ok_or_else(|| format!("credit tier rate_change of {} is not a usable number", tier.rate_change))?;
+            tiers.push((tier.min_score, rate_change));
+        }
+        tiers.sort_by_key(|(min_score, _)| std::cmp::Reverse(*min_score));
+        Ok(CreditTiers { tiers })
+    }
+}
+
+impl CreditTiers {
+    // Scores below the lowest tier get its change too
+    pub fn rate_change(&self, credit_score: u16) -> Decimal {
+        self.tiers
+            .iter()
+            .find(|(min_score, _)| credit_score >= *min_score)
+            .or(self.tiers.last())
+            .map(|(_, rate_change)| *rate_change)
+            .unwrap_or(dec!(0))
+    }
+}
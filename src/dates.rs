@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::{LoanCalculator, LoanType};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateFormat {
+    Iso,
+    MonthFirst,
+    DayFirst,
+}
+
+impl DateFormat {
+    pub fn from_name(name: &str) -> Option<DateFormat> {
+        match name.to_lowercase().as_str() {
+            "iso" | "yyyy-mm-dd" => Some(DateFormat::Iso),
+            "us" | "mm/dd/yyyy" => Some(DateFormat::MonthFirst),
+            "dmy" | "dd/mm/yyyy" => Some(DateFormat::DayFirst),
+            _ => None,
+        }
+    }
+
+    // POSIX locale names like en_US.UTF-8. Most regions write the day first;
+    // the C locale and a few languages use year-first dates.
+    pub fn from_locale(locale: &str) -> DateFormat {
+        let name = locale.split(['.', '@']).next().unwrap_or("");
+        let language = name.split('_').next().unwrap_or("");
+        match (language, name) {
+            ("" | "C" | "POSIX", _) => DateFormat::Iso,
+            (_, "en_US" | "en_PH" | "es_US") => DateFormat::MonthFirst,
+            ("sv" | "lt" | "hu" | "zh" | "ja" | "ko", _) => DateFormat::Iso,
+            _ => DateFormat::DayFirst,
+        }
+    }
+
+    pub fn format(&self, date: NaiveDate) -> String {
+        let pattern = match self {
+            DateFormat::Iso => "%Y-%m-%d",
+            DateFormat::MonthFirst => "%m/%d/%Y",
+            DateFormat::DayFirst => "%d/%m/%Y",
+        };
+        date.format(pattern).to_string()
+    }
+}
+
+// How the lender collects interest for the days between closing and the
+// start of the first full monthly period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StubInterest {
+    Prepaid,
+    OddFirstPayment,
+}
+
+impl StubInterest {
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            StubInterest::Prepaid => "collected at closing as prepaid interest",
+            StubInterest::OddFirstPayment => "added to the first payment",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaymentDay {
+    Day(u32),
+    LastDay,
+}
+
+impl PaymentDay {
+    pub fn get_description(&self) -> String {
+        match self {
+            PaymentDay::Day(day) => {
+                let suffix = match (day % 10, day % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("the {}{}", day, suffix)
+            }
+            PaymentDay::LastDay => "the last day of the month".to_string(),
+        }
+    }
+
+    // Due days past the end of a short month fall on its last day
+    fn in_month(&self, year: i32, month: u32) -> NaiveDate {
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        let last_day = NaiveDate::from_ymd_opt(next_year, next_month, 1).unwrap().pred_opt().unwrap();
+        match self {
+            PaymentDay::Day(day) if *day < last_day.day() => last_day.with_day(*day).unwrap(),
+            _ => last_day,
+        }
+    }
+}
+
+// Due dates of a loan's monthly payments. Payment `n` closes the period that
+// starts at payment `n - 1`, with payment 0 standing for the period start.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Calendar {
+    pub period_start: NaiveDate,
+    pub payment_day: PaymentDay,
+    // When set, due dates on weekends or these holidays move to the next
+    // business day
+    pub holidays: Option<Arc<[NaiveDate]>>,
+}
+
+impl Calendar {
+    pub fn payment_date(&self, number: u32) -> NaiveDate {
+        if number == 0 {
+            return self.period_start;
+        }
+
+        let index = self.period_start.year() * 12 + self.period_start.month0() as i32 + number as i32;
+        let mut date = self.payment_day.in_month(index.div_euclid(12), index.rem_euclid(12) as u32 + 1);
+        if let Some(holidays) = &self.holidays {
+            while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) || holidays.contains(&date) {
+                date = date.succ_opt().unwrap();
+            }
+        }
+        date
+    }
+
+    // Actual days of interest covered by payment `number`
+    pub fn days_in_period(&self, number: u32) -> u32 {
+        (self.payment_date(number) - self.payment_date(number - 1)).num_days() as u32
+    }
+
+    // Calendar years with payments due among the first `num_payments`, each
+    // with the range of payments due in it
+    pub fn payment_years(&self, num_payments: u32) -> Vec<(i32, std::ops::RangeInclusive<u32>)> {
+        let mut years: Vec<(i32, std::ops::RangeInclusive<u32>)> = Vec::new();
+        for number in 1..=num_payments {
+            let year = self.payment_date(number).year();
+            match years.last_mut() {
+                Some((last_year, months)) if *last_year == year => *months = *months.start()..=number,
+                _ => years.push((year, number..=number)),
+            }
+        }
+        years
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Closing {
+    pub date: NaiveDate,
+    pub payment_day: PaymentDay,
+    pub holidays: Option<Arc<[NaiveDate]>>,
+    pub stub_interest: StubInterest,
+}
+
+impl Closing {
+    // A loan closing on the due day a month before `first_payment`, so there
+    // is no interest to collect before the first full period
+    pub fn for_first_payment(first_payment: NaiveDate) -> Closing {
+        let is_last_day = first_payment.succ_opt().is_some_and(|next| next.month() != first_payment.month());
+        let payment_day = if is_last_day { PaymentDay::LastDay } else { PaymentDay::Day(first_payment.day()) };
+        let previous = first_payment.with_day(1).unwrap() - chrono::Months::new(1);
+        Closing {
+            date: payment_day.in_month(previous.year(), previous.month()),
+            payment_day,
+            holidays: None,
+            stub_interest: StubInterest::Prepaid,
+        }
+    }
+
+    // Interest is paid in arrears, so full periods start on the first due day
+    // on or after closing and the first payment is due a month later
+    pub fn calendar(&self) -> Calendar {
+        let this_month = self.payment_day.in_month(self.date.year(), self.date.month());
+        let period_start = if this_month >= self.date {
+            this_month
+        } else {
+            let next_month = this_month.with_day(1).unwrap() + chrono::Months::new(1);
+            self.payment_day.in_month(next_month.year(), next_month.month())
+        };
+        Calendar {
+            period_start,
+            payment_day: self.payment_day,
+            holidays: self.holidays.clone(),
+        }
+    }
+
+    pub fn period_start(&self) -> NaiveDate {
+        self.calendar().period_start
+    }
+
+    pub fn first_payment_date(&self) -> NaiveDate {
+        self.calendar().payment_date(1)
+    }
+
+    pub fn stub_days(&self) -> u32 {
+        (self.period_start() - self.date).num_days() as u32
+    }
+}
+
+impl LoanCalculator {
+    // Per diem interest from closing to the start of the first full period,
+    // with days counted by the loan type's interest convention
+    pub fn calculate_stub_interest(
+        &self,
+        loan_type: &LoanType,
+        principal: Decimal,
+        annual_rate: Decimal,
+        closing: &Closing,
+    ) -> Decimal {
+        let (days, days_in_year) = self.interest_convention(loan_type).stub_days(closing);
+        principal * annual_rate / dec!(100) / days_in_year * Decimal::from(days)
+    }
+}
@@ -0,0 +1,248 @@
+use std::io::IsTerminal;
+use std::path::Path;
+
+use colored::Colorize;
+use dialoguer::{Input, Select};
+use prettytable::{row, Table};
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+
+use loancalc::config::{self, ConfigFormat};
+use loancalc::revolving::{self, Debt, PayoffPlan, PAYOFF_ORDERS};
+use loancalc::format_money;
+
+use crate::i18n::{format_months, tr, trf};
+
+// A file of debts to pay off, named like the --debt fields:
+//
+// budget: 1200
+// debts:
+//   - name: Visa
+//     balance: 5200
+//     apr: 22.9
+//     minimum: 150
+//   - name: Store card
+//     balance: 900
+//     apr: 27.5
+//     minimum: 35
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DebtsFile {
+    #[serde(default)]
+    budget: Option<f64>,
+    debts: Vec<DebtEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DebtEntry {
+    name: String,
+    balance: f64,
+    apr: f64,
+    minimum: f64,
+}
+
+fn read_debts(path: &Path) -> Result<(Vec<Debt>, Option<f64>), Box<dyn std::error::Error>> {
+    let file = path.display().to_string();
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", file, e))?;
+    // YAML unless the extension says otherwise
+    let listed: DebtsFile = config::parse_config(
+        &contents,
+        ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Yaml),
+        &file,
+    )?;
+    let debts = listed
+        .debts
+        .into_iter()
+        .map(|entry| {
+            let amount = |value: f64, field: &str| {
+                Decimal::from_f64(value).ok_or_else(|| format!("{}: {}: {} must be a number", file, entry.name, field))
+            };
+            Ok(Debt {
+                balance: amount(entry.balance, "balance")?,
+                apr: amount(entry.apr, "apr")?,
+                minimum: amount(entry.minimum, "minimum")?,
+                name: entry.name.clone(),
+            })
+        })
+        .collect::<Result<Vec<Debt>, String>>()?;
+    Ok((debts, listed.budget))
+}
+
+fn get_debts() -> Result<Vec<Debt>, Box<dyn std::error::Error>> {
+    let mut debts = Vec::new();
+    loop {
+        let name: String = Input::new()
+            .with_prompt(tr("Debt name"))
+            .with_initial_text(trf("Card {}", &[&(debts.len() + 1)]))
+            .interact_text()?;
+        let balance = crate::get_money_input(tr("Balance ($)"), "5000")?;
+        let apr = crate::get_rate_input(tr("APR (%)"), "22.9")?;
+        let minimum = crate::get_money_input(tr("Minimum monthly payment ($)"), "150")?;
+        debts.push(Debt {
+            name,
+            balance,
+            apr,
+            minimum,
+        });
+        eprintln!("\n{}", tr("Add another debt?"));
+        let options = vec![tr("Yes"), tr("No")];
+        if Select::new().items(&options).default(1).interact()? == 1 {
+            return Ok(debts);
+        }
+    }
+}
+
+// The month a plan's payments end, counting from next month's payment
+fn payoff_date(month: u32) -> String {
+    let today = chrono::Local::now().date_naive();
+    today
+        .checked_add_months(chrono::Months::new(month))
+        .map(crate::format_date)
+        .unwrap_or_default()
+}
+
+// A plan's debts in the order it pays them off
+fn plan_table(plan: &PayoffPlan, debts: &[Debt]) -> Table {
+    let mut table = Table::new();
+    table.set_titles(row![tr("Order"), tr("Debt"), tr("Balance"), tr("APR"), tr("Paid Off"), tr("Interest")]);
+    for (position, payoff) in plan.debts.iter().enumerate() {
+        let Some(debt) = debts.iter().find(|debt| debt.name == payoff.name) else {
+            continue;
+        };
+        table.add_row(row![
+            position + 1,
+            payoff.name,
+            format_money(debt.balance),
+            format!("{:.2}%", debt.apr.round_dp(2)),
+            trf("Month {} ({})", &[&payoff.paid_off_month, &payoff_date(payoff.paid_off_month)]),
+            format_money(payoff.interest)
+        ]);
+    }
+    table
+}
+
+// Each plan's finish side by side
+fn summary_table(plans: &[PayoffPlan]) -> Table {
+    let mut summary = Table::new();
+    summary.set_titles(row![
+        tr("Strategy"),
+        tr("Debt-Free In"),
+        tr("Debt-Free By"),
+        tr("First Debt Paid Off"),
+        tr("Total Interest")
+    ]);
+    for plan in plans {
+        summary.add_row(row![
+            tr(plan.order.get_name()),
+            format_months(plan.months()),
+            payoff_date(plan.months()),
+            plan.debts
+                .first()
+                .map(|first| trf("{} in month {}", &[&first.name, &first.paid_off_month]))
+                .unwrap_or_default(),
+            format_money(plan.total_interest())
+        ]);
+    }
+    summary
+}
+
+pub fn run(path: Option<&Path>, flagged: &[Debt], budget: Option<f64>) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut debts, listed_budget) = match path {
+        Some(path) => read_debts(path)?,
+        None => (Vec::new(), None),
+    };
+    debts.extend(flagged.iter().cloned());
+    let interactive = std::io::stdin().is_terminal();
+    if debts.is_empty() {
+        if !interactive {
+            return Err("stdin is not a terminal, so give a file of debts or --debt for each one".into());
+        }
+        debts = get_debts()?;
+    }
+    let minimums: Decimal = debts.iter().map(|debt| debt.minimum).sum();
+    let budget = match budget.or(listed_budget) {
+        Some(budget) => crate::flag_amount(Some(budget), "--budget")?,
+        None if interactive => crate::get_money_input(
+            tr("Monthly budget for all the debts ($)"),
+            &(minimums + Decimal::from(100)).to_string(),
+        )?,
+        None => return Err("stdin is not a terminal, so --budget must be given".into()),
+    };
+
+    let plans = PAYOFF_ORDERS
+        .iter()
+        .map(|order| revolving::plan_payoff(&debts, budget, *order))
+        .collect::<Result<Vec<PayoffPlan>, _>>()?;
+    let owed: Decimal = debts.iter().map(|debt| debt.balance).sum();
+    println!(
+        "\n{}",
+        trf(
+            "Paying {} a month toward {} owed ({} in minimums):",
+            &[&format_money(budget), &format_money(owed), &format_money(minimums)],
+        )
+    );
+    for plan in &plans {
+        println!("\n{}:", tr(plan.order.get_description()));
+        crate::print_table(&plan_table(plan, &debts));
+    }
+
+    println!("\n{}", tr("Avalanche vs. Snowball:"));
+    crate::print_table(&summary_table(&plans));
+    if let [avalanche, snowball] = &plans[..] {
+        println!(
+            "{} {}",
+            tr("Less interest:").green().bold(),
+            crate::describe_cheaper(
+                tr(avalanche.order.get_name()),
+                tr(snowball.order.get_name()),
+                snowball.total_interest() - avalanche.total_interest()
+            )
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn payoff_tables_have_no_english_headings_in_spanish() {
+        crate::i18n::set_language(Some("es")).unwrap();
+        let debts = vec![
+            Debt {
+                name: "Visa".to_string(),
+                balance: dec!(5200),
+                apr: dec!(22.9),
+                minimum: dec!(150),
+            },
+            Debt {
+                name: "Store card".to_string(),
+                balance: dec!(900),
+                apr: dec!(27.5),
+                minimum: dec!(35),
+            },
+        ];
+        let plans: Vec<PayoffPlan> = PAYOFF_ORDERS
+            .iter()
+            .map(|order| revolving::plan_payoff(&debts, dec!(400), *order).unwrap())
+            .collect();
+
+        let mut printed = String::new();
+        for plan in &plans {
+            printed.push_str(tr(plan.order.get_description()));
+            printed.push_str(&plan_table(plan, &debts).to_string());
+        }
+        printed.push_str(tr("Avalanche vs. Snowball:"));
+        printed.push_str(&summary_table(&plans).to_string());
+        for english in [
+            "Order", "Debt", "Balance", "Paid Off", "Interest", "Strategy", "Debt-Free", "Month", "Avalanche",
+            "Snowball",
+        ] {
+            assert!(!printed.contains(english), "{:?} left in English:\n{}", english, printed);
+        }
+        assert!(printed.contains("Avalancha (primero la tasa más alta)"));
+    }
+}
@@ -0,0 +1,157 @@
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+
+use crate::schedule::PaymentStrategy;
+use crate::LoanCalculator;
+
+/// How fast a car loses value: the percent of its value lost in each year of
+/// its age, with the last year's percent repeating after that.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepreciationCurve {
+    yearly: Vec<Decimal>,
+}
+
+impl TryFrom<Vec<f64>> for DepreciationCurve {
+    type Error = String;
+
+    fn try_from(yearly: Vec<f64>) -> Result<Self, String> {
+        if yearly.is_empty() || yearly.iter().any(|rate| !(0.0..100.0).contains(rate)) {
+            return Err("Depreciation must list at least one yearly percent, each at least 0 and below 100".to_string());
+        }
+        Ok(DepreciationCurve {
+            yearly: yearly.into_iter().filter_map(Decimal::from_f64).collect(),
+        })
+    }
+}
+
+// About a fifth of a new car's value goes in its first year, and less each
+// year after
+impl Default for DepreciationCurve {
+    fn default() -> Self {
+        DepreciationCurve {
+            yearly: vec![dec!(20), dec!(15), dec!(13), dec!(12), dec!(11)],
+        }
+    }
+}
+
+impl DepreciationCurve {
+    // Percents separated by commas, like 20,15,13
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let yearly = input
+            .split(',')
+            .map(|rate| {
+                rate.trim()
+                    .trim_end_matches('%')
+                    .parse::<f64>()
+                    .map_err(|_| format!("'{}' is not a percent", rate.trim()))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+        DepreciationCurve::try_from(yearly)
+    }
+
+    pub fn yearly(&self) -> &[Decimal] {
+        &self.yearly
+    }
+
+    // The year's percent for a car `year` years old
+    fn rate_in(&self, year: usize) -> Decimal {
+        self.yearly.get(year).or(self.yearly.last()).copied().unwrap_or_default()
+    }
+
+    // A car loses each year's value evenly over its months
+    pub fn value_after(&self, value: Decimal, months: u32) -> Decimal {
+        let mut value = value;
+        for year in 0..(months / 12) as usize {
+            value *= dec!(1) - self.rate_in(year) / dec!(100);
+        }
+        let partial = Decimal::from(months % 12) / dec!(12);
+        value *= dec!(1) - self.rate_in((months / 12) as usize) / dec!(100) * partial;
+        value.round_dp(2)
+    }
+
+    // The percent lost each year, the last repeating
+    pub fn yearly_rates(&self) -> &[Decimal] {
+        &self.yearly
+    }
+
+    pub fn get_description(&self) -> String {
+        let rates: Vec<String> = self.yearly.iter().map(|rate| format!("{}%", rate.normalize())).collect();
+        match rates.split_last() {
+            Some((last, [])) => format!("{} a year", last),
+            Some((last, first)) => format!("{}, then {} a year", first.join(", "), last),
+            None => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EquityMonth {
+    pub month: u32,
+    pub value: Decimal,
+    pub balance: Decimal,
+}
+
+impl EquityMonth {
+    // Negative while the loan is underwater
+    pub fn equity(&self) -> Decimal {
+        self.value - self.balance
+    }
+}
+
+/// A car's value set against what is owed on it, from the day it is bought
+/// (month 0) to the last payment.
+#[derive(Debug, Clone)]
+pub struct EquityTimeline {
+    pub months: Vec<EquityMonth>,
+}
+
+impl EquityTimeline {
+    pub fn underwater_months(&self) -> u32 {
+        self.months.iter().filter(|month| month.equity() < Decimal::ZERO).count() as u32
+    }
+
+    // The first month from which the car stays worth more than what is owed;
+    // None when it never is underwater
+    pub fn positive_from(&self) -> Option<u32> {
+        let last = self.months.iter().rev().find(|month| month.equity() < Decimal::ZERO)?;
+        Some(last.month + 1)
+    }
+
+    // The most owed beyond the car's value, which is what GAP coverage would
+    // pay if the car were totaled
+    pub fn deepest(&self) -> Option<&EquityMonth> {
+        self.months
+            .iter()
+            .filter(|month| month.equity() < Decimal::ZERO)
+            .min_by_key(|month| month.equity())
+    }
+}
+
+impl LoanCalculator {
+    // The banks.yaml `depreciation`, or the typical curve
+    pub fn depreciation(&self) -> &DepreciationCurve {
+        &self.depreciation
+    }
+
+    pub fn equity_timeline(
+        &self,
+        loan_amount: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        vehicle_value: Decimal,
+        curve: &DepreciationCurve,
+    ) -> EquityTimeline {
+        let schedule = self.generate_schedule(loan_amount, annual_rate, num_payments, &PaymentStrategy::Standard);
+        let mut months = vec![EquityMonth {
+            month: 0,
+            value: vehicle_value,
+            balance: loan_amount,
+        }];
+        months.extend((1..=schedule.num_payments()).map(|month| EquityMonth {
+            month,
+            value: curve.value_after(vehicle_value, month),
+            balance: schedule.balance_after(month),
+        }));
+        EquityTimeline { months }
+    }
+}
@@ -0,0 +1,135 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::interest::InterestMethod;
+use crate::{LoanCalculator, LoanType};
+
+// The federal Truth in Lending box for one offer. The loan type's closing
+// costs, the origination fee and points are treated as prepaid finance charges, so they
+// come out of the amount financed and raise the APR above the note rate.
+#[derive(Debug, Clone)]
+pub struct Disclosure {
+    pub apr: Decimal,
+    pub finance_charge: Decimal,
+    pub amount_financed: Decimal,
+    pub total_of_payments: Decimal,
+    // Runs of equal payments: (count, amount, first payment number)
+    pub payment_schedule: Vec<(u32, Decimal, u32)>,
+}
+
+impl LoanCalculator {
+    pub fn prepaid_finance_charges(&self, bank_name: &str, loan_type: &LoanType, principal: Decimal) -> Decimal {
+        let Some(bank) = self.get_bank(bank_name) else {
+            return Decimal::ZERO;
+        };
+        bank.get_closing_costs(loan_type) + principal * (bank.origination_fee + bank.points) / dec!(100)
+    }
+
+    // Solves for the rate at which the amount financed, after prepaid
+    // charges, repays with the offer's monthly payment. None when the
+    // charges use up the whole loan, which leaves no rate to solve for.
+    pub fn calculate_apr(
+        &self,
+        method: &InterestMethod,
+        bank_name: &str,
+        loan_type: &LoanType,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> Option<Decimal> {
+        let cost = self.calculate_cost(method, principal, annual_rate, num_payments)?;
+        let amount_financed = principal - self.prepaid_finance_charges(bank_name, loan_type, principal);
+        if amount_financed <= Decimal::ZERO {
+            return None;
+        }
+        // The installment unrounded, so a loan without fees has its note rate as the APR
+        let payment = match method {
+            InterestMethod::Amortizing | InterestMethod::SimpleDaily { .. } => {
+                self.calculate_monthly_payment(principal, annual_rate, num_payments)
+            }
+            _ => cost.monthly_payment,
+        };
+        self.solve_rate_for_payment(amount_financed, payment, num_payments)
+    }
+
+    pub fn disclosure(
+        &self,
+        method: &InterestMethod,
+        bank_name: &str,
+        loan_type: &LoanType,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> Option<Disclosure> {
+        let cost = self.calculate_cost(method, principal, annual_rate, num_payments)?;
+        let amount_financed = principal - self.prepaid_finance_charges(bank_name, loan_type, principal);
+
+        // Amortizing loans end with a final payment that clears the balance
+        // left after cent rounding; other methods quote level installments
+        let payments: Vec<Decimal> = if *method == InterestMethod::Amortizing {
+            let amortization = crate::exact_amortization(principal, annual_rate, num_payments, self.rounding()).ok()?;
+            let mut payments = vec![amortization.payment; num_payments as usize - 1];
+            payments.push(amortization.final_payment);
+            payments
+        } else {
+            vec![self.rounding().round_cents(cost.monthly_payment); num_payments as usize]
+        };
+
+        let mut payment_schedule: Vec<(u32, Decimal, u32)> = Vec::new();
+        for (index, payment) in payments.iter().enumerate() {
+            match payment_schedule.last_mut() {
+                Some((count, amount, _)) if amount == payment => *count += 1,
+                _ => payment_schedule.push((1, *payment, index as u32 + 1)),
+            }
+        }
+
+        let total_of_payments: Decimal = payments.iter().sum();
+        Some(Disclosure {
+            apr: self.calculate_apr(method, bank_name, loan_type, principal, annual_rate, num_payments)?,
+            finance_charge: total_of_payments - amount_financed,
+            amount_financed,
+            total_of_payments,
+            payment_schedule,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BankConfig;
+
+    fn calculator(bank: BankConfig) -> LoanCalculator {
+        LoanCalculator::builder().bank(bank).build().unwrap()
+    }
+
+    #[test]
+    fn closing_costs_apply_to_their_loan_type() {
+        let mut bank = BankConfig::new("Bank")
+            .rates(&LoanType::Home, 6.0, 6.0)
+            .rates(&LoanType::Personal, 9.0, 9.0)
+            .rates(&LoanType::Car, 7.0, 7.0)
+            .closing_costs(6000.0);
+        bank.car_loan_range.closing_costs = Some(400.0);
+        let calculator = calculator(bank);
+        let principal = dec!(10000);
+        assert_eq!(calculator.prepaid_finance_charges("Bank", &LoanType::Home, principal), dec!(6000));
+        assert_eq!(calculator.prepaid_finance_charges("Bank", &LoanType::Car, principal), dec!(400));
+        assert_eq!(calculator.prepaid_finance_charges("Bank", &LoanType::Personal, principal), dec!(0));
+
+        // Without fees a personal loan's APR is its note rate
+        let apr = calculator
+            .calculate_apr(&InterestMethod::Amortizing, "Bank", &LoanType::Personal, principal, dec!(9), 36)
+            .unwrap();
+        assert!((apr - dec!(9)).abs() < dec!(0.01), "APR {}", apr);
+    }
+
+    #[test]
+    fn no_apr_when_the_charges_use_up_the_loan() {
+        let calculator = calculator(BankConfig::new("Bank").rates(&LoanType::Home, 6.0, 6.0).closing_costs(6000.0));
+        let apr = calculator.calculate_apr(&InterestMethod::Amortizing, "Bank", &LoanType::Home, dec!(5000), dec!(6), 60);
+        assert_eq!(apr, None);
+        let apr = calculator.calculate_apr(&InterestMethod::Amortizing, "Bank", &LoanType::Home, dec!(6000), dec!(6), 60);
+        assert_eq!(apr, None);
+    }
+}
@@ -0,0 +1,65 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::LoanCalculator;
+
+// What the borrower earns and already owes each month, before taxes
+#[derive(Debug, Clone)]
+pub struct Income {
+    pub gross_monthly: Decimal,
+    pub monthly_debts: Decimal,
+}
+
+// Debt-to-income ratios as percents of gross monthly income. The front-end
+// ratio counts only housing, so it is None for loans that are not a home.
+#[derive(Debug, Clone)]
+pub struct Dti {
+    pub front_end: Option<Decimal>,
+    pub back_end: Decimal,
+    pub max_front_end: Decimal,
+    pub max_back_end: Decimal,
+}
+
+impl Income {
+    fn percent_of(&self, amount: Decimal) -> Decimal {
+        (amount / self.gross_monthly * dec!(100)).round_dp(2)
+    }
+}
+
+impl Dti {
+    // Why the bank would turn the loan down on income, if it would
+    pub fn problem(&self) -> Option<String> {
+        match self.front_end {
+            Some(front_end) if front_end > self.max_front_end => Some(format!(
+                "front-end DTI of {:.1}% is above its {:.0}% limit",
+                front_end.round_dp(1), self.max_front_end.round_dp(0)
+            )),
+            _ if self.back_end > self.max_back_end => Some(format!(
+                "back-end DTI of {:.1}% is above its {:.0}% limit",
+                self.back_end.round_dp(1), self.max_back_end.round_dp(0)
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn fits(&self) -> bool {
+        self.problem().is_none()
+    }
+}
+
+impl LoanCalculator {
+    // `new_payment` is everything the loan adds each month; for a home that
+    // includes PMI, escrow and HOA dues
+    pub fn check_dti(&self, bank_name: &str, income: &Income, new_payment: Decimal, housing: bool) -> Option<Dti> {
+        if income.gross_monthly <= dec!(0) {
+            return None;
+        }
+        let bank = self.get_bank(bank_name)?;
+        Some(Dti {
+            front_end: housing.then(|| income.percent_of(new_payment)),
+            back_end: income.percent_of(new_payment + income.monthly_debts),
+            max_front_end: bank.max_front_end_dti,
+            max_back_end: bank.max_back_end_dti,
+        })
+    }
+}
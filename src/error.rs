@@ -0,0 +1,56 @@
+use std::fmt;
+
+// Everything the library can fail with. None of the public entry points
+// panic on bad input; they return one of these instead.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Yaml(serde_yaml::Error),
+    // A config file that does not parse, with the field at fault if known
+    Parse {
+        file: String,
+        field: Option<String>,
+        message: String,
+    },
+    // The bank data parsed but holds values the engine cannot use
+    Config(String),
+    // Loan terms no lender could quote, such as a term of zero months
+    InvalidLoan(String),
+    // An intermediate result too large for a Decimal
+    Overflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Yaml(e) => write!(f, "{}", e),
+            Error::Parse { file, field: Some(field), message } => write!(f, "{}: {}: {}", file, field, message),
+            Error::Parse { file, field: None, message } => write!(f, "{}: {}", file, message),
+            Error::Config(message) | Error::InvalidLoan(message) => write!(f, "{}", message),
+            Error::Overflow => write!(f, "the loan is too large to calculate"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Yaml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Self {
+        Error::Yaml(e)
+    }
+}
@@ -0,0 +1,27 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+// Ownership costs paid alongside a mortgage. Taxes and insurance are
+// yearly and collected monthly into escrow; HOA dues are monthly and paid
+// to the association directly.
+#[derive(Debug, Clone, Default)]
+pub struct HousingCosts {
+    pub property_tax: Decimal,
+    pub insurance: Decimal,
+    pub hoa_dues: Decimal,
+}
+
+impl HousingCosts {
+    pub fn monthly_escrow(&self) -> Decimal {
+        ((self.property_tax + self.insurance) / dec!(12)).round_dp(2)
+    }
+
+    pub fn monthly_total(&self) -> Decimal {
+        self.monthly_escrow() + self.hoa_dues
+    }
+
+    // Escrow and dues over the payments given, at today's amounts
+    pub fn total(&self, num_payments: u32) -> Decimal {
+        self.monthly_total() * Decimal::from(num_payments)
+    }
+}
@@ -0,0 +1,137 @@
+use rust_decimal::Decimal;
+
+use crate::{exact_period_amortization, LoanCalculator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaymentFrequency {
+    Weekly,
+    BiWeekly,
+    SemiMonthly,
+    Monthly,
+}
+
+pub const FREQUENCIES: [PaymentFrequency; 4] = [
+    PaymentFrequency::Monthly,
+    PaymentFrequency::SemiMonthly,
+    PaymentFrequency::BiWeekly,
+    PaymentFrequency::Weekly,
+];
+
+impl PaymentFrequency {
+    pub fn from_name(name: &str) -> Option<PaymentFrequency> {
+        match name.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "weekly" => Some(PaymentFrequency::Weekly),
+            "biweekly" => Some(PaymentFrequency::BiWeekly),
+            "semimonthly" => Some(PaymentFrequency::SemiMonthly),
+            "monthly" => Some(PaymentFrequency::Monthly),
+            _ => None,
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            PaymentFrequency::Weekly => "Weekly",
+            PaymentFrequency::BiWeekly => "Bi-weekly",
+            PaymentFrequency::SemiMonthly => "Semi-monthly",
+            PaymentFrequency::Monthly => "Monthly",
+        }
+    }
+
+    pub fn periods_per_year(&self) -> u32 {
+        match self {
+            PaymentFrequency::Weekly => 52,
+            PaymentFrequency::BiWeekly => 26,
+            PaymentFrequency::SemiMonthly => 24,
+            PaymentFrequency::Monthly => 12,
+        }
+    }
+
+    // Payments over a term given in months, rounded to the nearest whole
+    // payment (a 30-year bi-weekly loan has 780)
+    pub fn num_payments(&self, term_months: u32) -> u32 {
+        (term_months * self.periods_per_year() + 6) / 12
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FrequencyCost {
+    pub frequency: PaymentFrequency,
+    pub payment: Decimal,
+    pub num_payments: u32,
+    pub total_interest: Decimal,
+    // Compared with monthly payments on the same loan
+    pub interest_saved: Decimal,
+}
+
+impl LoanCalculator {
+    // An amortizing loan over the same term with interest accruing per
+    // payment period rather than per month
+    pub fn calculate_frequency_cost(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        term_months: u32,
+        frequency: PaymentFrequency,
+    ) -> Option<FrequencyCost> {
+        // Billed to the cent as the monthly Total Interest is, so the savings
+        // shown add up
+        let total_interest = |frequency: PaymentFrequency| {
+            let num_payments = frequency.num_payments(term_months);
+            let amortization = exact_period_amortization(
+                principal,
+                annual_rate,
+                frequency.periods_per_year(),
+                num_payments,
+                self.rounding(),
+            )
+            .ok()?;
+            Some((amortization.payment, num_payments, amortization.total_interest))
+        };
+        let (_, _, monthly_interest) = total_interest(PaymentFrequency::Monthly)?;
+        let (payment, num_payments, interest) = total_interest(frequency)?;
+        Some(FrequencyCost {
+            frequency,
+            payment,
+            num_payments,
+            total_interest: interest,
+            interest_saved: monthly_interest - interest,
+        })
+    }
+
+    // Monthly, bi-weekly and the chosen frequency, in that order
+    pub fn compare_frequencies(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        term_months: u32,
+        frequency: PaymentFrequency,
+    ) -> Vec<FrequencyCost> {
+        let mut frequencies = vec![PaymentFrequency::Monthly, PaymentFrequency::BiWeekly];
+        if !frequencies.contains(&frequency) {
+            frequencies.push(frequency);
+        }
+        frequencies
+            .into_iter()
+            .filter_map(|frequency| self.calculate_frequency_cost(principal, annual_rate, term_months, frequency))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_CONFIG;
+    use crate::interest::InterestMethod;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn monthly_row_matches_the_total_interest() {
+        let calculator = LoanCalculator::from_config(DEFAULT_CONFIG).unwrap();
+        let cost = calculator.calculate_cost(&InterestMethod::Amortizing, dec!(300000), dec!(6.5), 360).unwrap();
+        let frequencies = calculator.compare_frequencies(dec!(300000), dec!(6.5), 360, PaymentFrequency::Weekly);
+        assert_eq!(frequencies[0].frequency, PaymentFrequency::Monthly);
+        assert_eq!(frequencies[0].payment, cost.monthly_payment);
+        assert_eq!(frequencies[0].total_interest, cost.total_interest);
+        assert!(frequencies[1..].iter().all(|frequency| frequency.interest_saved > dec!(0)));
+    }
+}
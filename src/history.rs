@@ -0,0 +1,55 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config;
+use crate::Error;
+
+// One completed comparison: the `--output json` document, which carries the
+// inputs, every bank's results and the provenance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub recorded_at: String,
+    pub comparison: Value,
+}
+
+impl HistoryEntry {
+    pub fn new(comparison: Value) -> Self {
+        HistoryEntry {
+            recorded_at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            comparison,
+        }
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    config::user_data_dir().map(|dir| dir.join("history.jsonl"))
+}
+
+// One JSON document per line, so recording a run only ever appends
+pub fn append(path: &Path, entry: &HistoryEntry) -> Result<(), Error> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| Error::Config(e.to_string()))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+// Oldest first. Lines that do not parse, such as one cut short by a crash,
+// are skipped rather than hiding the rest of the history.
+pub fn read(path: &Path) -> Result<Vec<HistoryEntry>, Error> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
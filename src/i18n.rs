@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+// Translations of the prompts, headings and messages of a comparison, keyed
+// by their English text so anything a catalog leaves out stays in English.
+// A language is added by adding its file to locales/.
+const CATALOGS: &[(&str, &str)] = &[
+    ("es", include_str!("../locales/es.yaml")),
+    ("fr", include_str!("../locales/fr.yaml")),
+];
+
+static MESSAGES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+// The language of a POSIX locale name like fr_CA.UTF-8, or of a bare code
+fn language_of(locale: &str) -> String {
+    locale.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase()
+}
+
+// `--lang` over the language of LC_ALL, LC_MESSAGES or LANG. Only an unknown
+// `--lang` is an error; a locale without a catalog is shown in English.
+pub fn set_language(name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let language = match name {
+        Some(name) => {
+            let language = language_of(name);
+            if language != "en" && !CATALOGS.iter().any(|(code, _)| *code == language) {
+                let codes: Vec<&str> = CATALOGS.iter().map(|(code, _)| *code).collect();
+                return Err(format!("Unknown language '{}' (expected en, {})", name, codes.join(", ")).into());
+            }
+            language
+        }
+        None => ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map(|locale| language_of(&locale))
+            .unwrap_or_default(),
+    };
+    let messages = match CATALOGS.iter().find(|(code, _)| *code == language) {
+        Some((code, catalog)) => {
+            serde_yaml::from_str(catalog).map_err(|e| format!("locales/{}.yaml: {}", code, e))?
+        }
+        None => HashMap::new(),
+    };
+    let _ = MESSAGES.set(messages);
+    Ok(())
+}
+
+// Whether a catalog was loaded, for the few messages English builds from
+// pieces that do not translate one by one
+pub fn translating() -> bool {
+    MESSAGES.get().is_some_and(|messages| !messages.is_empty())
+}
+
+pub fn tr(text: &'static str) -> &'static str {
+    MESSAGES
+        .get()
+        .and_then(|messages| messages.get(text))
+        .map(String::as_str)
+        .unwrap_or(text)
+}
+
+// A translated message with each {} filled in turn
+pub fn trf(text: &'static str, args: &[&dyn Display]) -> String {
+    let mut pieces = tr(text).split("{}");
+    let mut message = pieces.next().unwrap_or_default().to_string();
+    for (index, piece) in pieces.enumerate() {
+        if let Some(arg) = args.get(index) {
+            message.push_str(&arg.to_string());
+        }
+        message.push_str(piece);
+    }
+    message
+}
+
+// `loancalc::format_months` in the chosen language
+pub fn format_months(months: u32) -> String {
+    match (months / 12, months % 12) {
+        (years, 0) => trf("{} yrs", &[&years]),
+        (0, months) => trf("{} mos", &[&months]),
+        (years, months) => trf("{} yrs {} mos", &[&years, &months]),
+    }
+}
+
+// `loancalc::format_rate` in the chosen language
+pub fn format_rate(rate: Decimal, apr: Option<Decimal>) -> String {
+    match apr {
+        Some(apr) if (apr - rate).abs() >= dec!(0.005) => trf(
+            "{}% ({}% APR)",
+            &[&format!("{:.2}", rate.round_dp(2)), &format!("{:.2}", apr.round_dp(2))],
+        ),
+        _ => format!("{:.2}%", rate.round_dp(2)),
+    }
+}
@@ -0,0 +1,366 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::approx;
+use crate::cache::QuoteKey;
+use crate::dates::Calendar;
+use crate::LoanCalculator;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InterestMethod {
+    Amortizing,
+    // Interest accrues over the actual days between due dates
+    SimpleDaily { days_early: u32, calendar: Calendar },
+    RuleOf78s { payoff_after: Option<u32> },
+    AddOn,
+    Discount,
+}
+
+impl InterestMethod {
+    pub fn get_description(&self) -> String {
+        match self {
+            InterestMethod::Amortizing => "Amortizing (monthly compounding)".to_string(),
+            InterestMethod::SimpleDaily { days_early: 0, .. } => {
+                "Simple interest (daily accrual)".to_string()
+            }
+            InterestMethod::SimpleDaily { days_early, .. } => {
+                format!("Simple interest (daily accrual, paid {} days early)", days_early)
+            }
+            InterestMethod::RuleOf78s { payoff_after: None } => {
+                "Precomputed interest (Rule of 78s)".to_string()
+            }
+            InterestMethod::RuleOf78s { payoff_after: Some(payments) } => {
+                format!("Precomputed interest (Rule of 78s, paid off after {} payments)", payments)
+            }
+            InterestMethod::AddOn => "Add-on interest (flat installments)".to_string(),
+            InterestMethod::Discount => "Discount (interest deducted upfront)".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoanCost {
+    pub monthly_payment: Decimal,
+    pub total_interest: Decimal,
+    pub total_payment: Decimal,
+    // Extra cost of paying off early compared to an actuarial loan at the same rate
+    pub early_payoff_penalty: Option<Decimal>,
+    // Actuarial rate equivalent to the quoted rate, for methods that understate it
+    pub effective_rate: Option<Decimal>,
+}
+
+impl LoanCalculator {
+    pub fn calculate_cost(
+        &self,
+        method: &InterestMethod,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> Option<LoanCost> {
+        let key = QuoteKey {
+            method: method.clone(),
+            principal,
+            annual_rate,
+            num_payments,
+        };
+        self.quote_cache.get_or_insert_with(key, || {
+            self.compute_cost(method, principal, annual_rate, num_payments)
+        })
+    }
+
+    pub(crate) fn compute_cost(
+        &self,
+        method: &InterestMethod,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> Option<LoanCost> {
+        // No loan has zero payments, and every formula below divides by them
+        if num_payments == 0 {
+            return None;
+        }
+        let cost = match method {
+            // Totals count the cents each payment is rounded to
+            InterestMethod::Amortizing => {
+                let payment = self.rounding().round_cents(self.checked_monthly_payment(principal, annual_rate, num_payments).ok()?);
+                let amortization =
+                    crate::amortize_payments(principal, payment, annual_rate / dec!(100) / dec!(12), num_payments, self.rounding());
+                LoanCost {
+                    monthly_payment: amortization.payment,
+                    total_interest: amortization.total_interest,
+                    total_payment: amortization.total_payment,
+                    early_payoff_penalty: None,
+                    effective_rate: None,
+                }
+            }
+            InterestMethod::SimpleDaily { days_early, calendar } => {
+                self.simple_interest_cost(principal, annual_rate, num_payments, *days_early, calendar)
+            }
+            InterestMethod::RuleOf78s { payoff_after } => {
+                self.rule_of_78s_cost(principal, annual_rate, num_payments, *payoff_after)
+            }
+            InterestMethod::AddOn => self.add_on_cost(principal, annual_rate, num_payments),
+            InterestMethod::Discount => return self.discount_cost(principal, annual_rate, num_payments),
+        };
+        Some(cost)
+    }
+
+    // The lender quotes the same installment as an amortizing loan, but each
+    // payment only covers interest accrued since the previous one. Paying
+    // early shortens the first period, and the final payment absorbs whatever
+    // balance is left.
+    fn simple_interest_cost(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        days_early: u32,
+        calendar: &Calendar,
+    ) -> LoanCost {
+        let rounding = self.rounding();
+        let monthly_payment = rounding.round_cents(self.calculate_monthly_payment(principal, annual_rate, num_payments));
+        let daily_rate = annual_rate / dec!(100) / dec!(365);
+
+        let mut balance = principal;
+        let mut total_interest = dec!(0);
+        let mut total_payment = dec!(0);
+
+        // Each due date is found once and carried over as the start of the
+        // next period
+        let mut period_start = calendar.payment_date(0);
+        for month in 0..num_payments {
+            let due_date = calendar.payment_date(month + 1);
+            let mut days = (due_date - period_start).num_days() as u32;
+            period_start = due_date;
+            if month == 0 {
+                days -= days_early.min(days - 1);
+            }
+
+            let interest = rounding.round_product(balance, daily_rate * Decimal::from(days));
+            let due = balance + interest;
+            let payment = if month == num_payments - 1 || due <= monthly_payment {
+                due
+            } else {
+                monthly_payment
+            };
+
+            balance = due - payment;
+            total_interest += interest;
+            total_payment += payment;
+
+            if balance.is_zero() {
+                break;
+            }
+        }
+
+        LoanCost {
+            monthly_payment,
+            total_interest,
+            total_payment,
+            early_payoff_penalty: None,
+            effective_rate: None,
+        }
+    }
+
+    // The finance charge is fixed upfront at what an amortizing loan would
+    // cost over the full term. On early payoff the lender rebates the
+    // unearned share using the sum of the digits of the remaining payments,
+    // which front-loads interest and rebates less than an actuarial payoff.
+    // Installments, the rebate and the payoff are all billed to the cent.
+    fn rule_of_78s_cost(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        payoff_after: Option<u32>,
+    ) -> LoanCost {
+        let rounding = self.rounding();
+        let principal = rounding.round_cents(principal);
+        let monthly_payment = rounding.round_cents(self.calculate_monthly_payment(principal, annual_rate, num_payments));
+        let finance_charge = monthly_payment * Decimal::from(num_payments) - principal;
+
+        let payments_made = match payoff_after {
+            Some(payments) if payments < num_payments => payments,
+            _ => {
+                return LoanCost {
+                    monthly_payment,
+                    total_interest: finance_charge,
+                    total_payment: principal + finance_charge,
+                    early_payoff_penalty: None,
+                    effective_rate: None,
+                };
+            }
+        };
+
+        let remaining = Decimal::from(num_payments - payments_made);
+        let digits = Decimal::from(num_payments) * Decimal::from(num_payments + 1);
+        let rebate = rounding.round_cents(finance_charge * remaining * (remaining + dec!(1)) / digits);
+        let payoff_amount = monthly_payment * remaining - rebate;
+
+        let actuarial_payoff = rounding.round_cents(self.calculate_remaining_balance(
+            principal,
+            annual_rate,
+            num_payments,
+            payments_made,
+        ));
+
+        let total_payment = monthly_payment * Decimal::from(payments_made) + payoff_amount;
+        LoanCost {
+            monthly_payment,
+            total_interest: total_payment - principal,
+            total_payment,
+            early_payoff_penalty: Some(payoff_amount - actuarial_payoff),
+            effective_rate: None,
+        }
+    }
+
+    // Add-on loans charge simple interest on the original principal for the
+    // whole term and spread principal plus interest into flat installments,
+    // so the borrower pays interest on money already repaid. The installment
+    // is billed to the cent, and the totals count what those cents add up to.
+    fn add_on_cost(&self, principal: Decimal, annual_rate: Decimal, num_payments: u32) -> LoanCost {
+        let interest =
+            principal * annual_rate / dec!(100) * Decimal::from(num_payments) / dec!(12);
+        let monthly_payment = self.rounding().round_cents((principal + interest) / Decimal::from(num_payments));
+        let total_payment = monthly_payment * Decimal::from(num_payments);
+
+        LoanCost {
+            monthly_payment,
+            total_interest: total_payment - principal,
+            total_payment,
+            early_payoff_penalty: None,
+            effective_rate: self.solve_rate_for_payment(principal, monthly_payment, num_payments),
+        }
+    }
+
+    // Discount loans withhold the interest from the disbursement and repay
+    // the face amount in equal installments. The face amount is grossed up so
+    // the borrower still nets `principal`, keeping the offer comparable with
+    // standard loans. No loan exists once the discount swallows the face amount.
+    fn discount_cost(&self, principal: Decimal, annual_rate: Decimal, num_payments: u32) -> Option<LoanCost> {
+        let discount_fraction = annual_rate / dec!(100) * Decimal::from(num_payments) / dec!(12);
+        if discount_fraction >= dec!(1) {
+            return None;
+        }
+
+        let rounding = self.rounding();
+        let principal = rounding.round_cents(principal);
+        let face_amount = rounding.round_cents(principal / (dec!(1) - discount_fraction));
+        let monthly_payment = rounding.round_cents(face_amount / Decimal::from(num_payments));
+        // The installments repay the face amount to within a few cents,
+        // and the totals count the cents billed
+        let total_payment = monthly_payment * Decimal::from(num_payments);
+
+        Some(LoanCost {
+            monthly_payment,
+            total_interest: total_payment - principal,
+            total_payment,
+            early_payoff_penalty: None,
+            effective_rate: self.solve_rate_for_payment(principal, monthly_payment, num_payments),
+        })
+    }
+
+    // Bisects for the annual rate at which an amortizing loan of `principal`
+    // has the given monthly payment. The approximate f64 math narrows the
+    // range first so only the last few steps use Decimal. None when no rate
+    // the math can reach is high enough.
+    pub fn solve_rate_for_payment(
+        &self,
+        principal: Decimal,
+        monthly_payment: Decimal,
+        num_payments: u32,
+    ) -> Option<Decimal> {
+        let (mut low, mut high) = approx::bracket_rate_for_payment(principal, monthly_payment, num_payments)?;
+
+        while high - low > dec!(0.000001) {
+            let mid = (low + high) / dec!(2);
+            // Only a rate far too high overflows the payment formula
+            let too_low = self
+                .checked_monthly_payment(principal, mid, num_payments)
+                .is_ok_and(|payment| payment < monthly_payment);
+            if too_low {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        Some((low + high) / dec!(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_CONFIG;
+
+    fn calculator() -> LoanCalculator {
+        LoanCalculator::from_config(DEFAULT_CONFIG).unwrap()
+    }
+
+    #[test]
+    fn solved_rate_reproduces_the_payment() {
+        let calculator = calculator();
+        for (num_payments, rate) in [(60, dec!(7.25)), (360, dec!(6.5)), (12, dec!(18.9)), (120, dec!(0.5))] {
+            let payment = calculator.calculate_monthly_payment(dec!(30000), rate, num_payments);
+            let solved = calculator.solve_rate_for_payment(dec!(30000), payment, num_payments).unwrap();
+            assert!((solved - rate).abs() < dec!(0.00001), "{} payments: {} against {}", num_payments, solved, rate);
+        }
+    }
+
+    #[test]
+    fn add_on_rate_is_nearly_double_the_quote() {
+        // A flat 6% on the original principal over five years costs about
+        // 10.85% actuarially
+        let cost = calculator().calculate_cost(&InterestMethod::AddOn, dec!(10000), dec!(6), 60).unwrap();
+        let effective = cost.effective_rate.unwrap();
+        assert!(effective > dec!(10.84) && effective < dec!(10.86), "{}", effective);
+    }
+
+    #[test]
+    fn add_on_totals_count_the_billed_installments() {
+        // 60% flat over five years is 30,000 on 10,000, billed as 60
+        // installments of 666.67
+        let cost = calculator().calculate_cost(&InterestMethod::AddOn, dec!(10000), dec!(60), 60).unwrap();
+        assert_eq!(cost.monthly_payment, dec!(666.67));
+        assert_eq!(cost.total_payment, dec!(40000.20));
+        assert_eq!(cost.total_interest, dec!(30000.20));
+        let effective = cost.effective_rate.unwrap();
+        let payment = calculator().calculate_monthly_payment(dec!(10000), effective, 60);
+        assert!((payment - cost.monthly_payment).abs() < dec!(0.0001), "{} at {}", payment, effective);
+    }
+
+    #[test]
+    fn deep_discount_rate_is_found_above_100_percent() {
+        // 20% a year over four years withholds 80% of the face amount, so the
+        // borrower nets 10,000 and repays 50,000 in 48 installments of
+        // 1,041.67, about 123.88% a year actuarially
+        let cost = calculator().calculate_cost(&InterestMethod::Discount, dec!(10000), dec!(20), 48).unwrap();
+        let effective = cost.effective_rate.unwrap();
+        assert!(effective > dec!(123.87) && effective < dec!(123.89), "{}", effective);
+        let payment = calculator().calculate_monthly_payment(dec!(10000), effective, 48);
+        assert!((payment - cost.monthly_payment).abs() < dec!(0.0001), "{} against {}", payment, cost.monthly_payment);
+    }
+
+    #[test]
+    fn rule_of_78s_rebates_by_the_sum_of_digits() {
+        let calculator = calculator();
+        let full = calculator
+            .calculate_cost(&InterestMethod::RuleOf78s { payoff_after: None }, dec!(12000), dec!(12), 12)
+            .unwrap();
+        let finance_charge = full.total_interest;
+
+        // After one of 12 payments, 11 + 10 + ... + 1 = 66 of the 78 digits
+        // are still unearned
+        let paid_off = calculator
+            .calculate_cost(&InterestMethod::RuleOf78s { payoff_after: Some(1) }, dec!(12000), dec!(12), 12)
+            .unwrap();
+        let rebate = (finance_charge * dec!(66) / dec!(78)).round_dp(2);
+        let expected = full.monthly_payment * dec!(12) - rebate;
+        assert_eq!(paid_off.total_payment, expected);
+
+        // Rule of 78s front-loads interest, so paying off early costs more
+        // than it would on an actuarial loan
+        assert!(paid_off.early_payoff_penalty.unwrap() > dec!(0));
+    }
+}
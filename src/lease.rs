@@ -0,0 +1,183 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::schedule::PaymentStrategy;
+use crate::{Error, LoanCalculator, LoanType};
+
+/// The miles a lease allows each year, the miles actually expected and what
+/// each one over the allowance costs at turn-in.
+#[derive(Debug, Clone)]
+pub struct Mileage {
+    pub allowed_per_year: u32,
+    pub expected_per_year: u32,
+    pub excess_fee: Decimal,
+}
+
+/// A car lease as a dealer quotes it.
+#[derive(Debug, Clone)]
+pub struct Lease {
+    // The negotiated price of the car, with any fees rolled in
+    pub cap_cost: Decimal,
+    // Paid at signing to bring the payment down
+    pub cap_cost_reduction: Decimal,
+    // What the lessor expects the car to be worth at turn-in
+    pub residual: Decimal,
+    pub money_factor: Decimal,
+    pub term_months: u32,
+    pub mileage: Mileage,
+}
+
+impl Lease {
+    pub fn new(
+        cap_cost: Decimal,
+        cap_cost_reduction: Decimal,
+        residual: Decimal,
+        money_factor: Decimal,
+        term_months: u32,
+        mileage: Mileage,
+    ) -> Result<Lease, Error> {
+        if cap_cost <= Decimal::ZERO {
+            return Err(Error::InvalidLoan("the capitalized cost must be greater than 0".to_string()));
+        }
+        if cap_cost_reduction < Decimal::ZERO || cap_cost_reduction >= cap_cost {
+            return Err(Error::InvalidLoan(
+                "the cap cost reduction must be at least 0 and less than the capitalized cost".to_string(),
+            ));
+        }
+        if residual <= Decimal::ZERO || residual >= cap_cost - cap_cost_reduction {
+            return Err(Error::InvalidLoan(
+                "the residual must be greater than 0 and less than the capitalized cost after the reduction"
+                    .to_string(),
+            ));
+        }
+        if money_factor < Decimal::ZERO || money_factor >= dec!(0.01) {
+            return Err(Error::InvalidLoan("the money factor must be at least 0 and below 0.01".to_string()));
+        }
+        if term_months == 0 {
+            return Err(Error::InvalidLoan("the lease term must be at least 1 month".to_string()));
+        }
+        Ok(Lease {
+            cap_cost,
+            cap_cost_reduction,
+            residual,
+            money_factor,
+            term_months,
+            mileage,
+        })
+    }
+
+    pub fn adjusted_cap_cost(&self) -> Decimal {
+        self.cap_cost - self.cap_cost_reduction
+    }
+
+    // The value the car loses over the lease, spread over its months
+    pub fn depreciation_fee(&self) -> Decimal {
+        ((self.adjusted_cap_cost() - self.residual) / Decimal::from(self.term_months)).round_dp(2)
+    }
+
+    // The lease's interest, charged on the adjusted cap cost and the residual
+    // together
+    pub fn rent_charge(&self) -> Decimal {
+        ((self.adjusted_cap_cost() + self.residual) * self.money_factor).round_dp(2)
+    }
+
+    pub fn monthly_payment(&self) -> Decimal {
+        self.depreciation_fee() + self.rent_charge()
+    }
+
+    // Dealers compare a money factor with a loan's rate by multiplying by 2400
+    pub fn equivalent_rate(&self) -> Decimal {
+        self.money_factor * dec!(2400)
+    }
+
+    pub fn excess_miles(&self) -> u32 {
+        let yearly = self.mileage.expected_per_year.saturating_sub(self.mileage.allowed_per_year);
+        yearly * self.term_months / 12
+    }
+
+    pub fn mileage_charge(&self) -> Decimal {
+        self.mileage.excess_fee * Decimal::from(self.excess_miles())
+    }
+
+    // Everything paid over the lease, with no car to show for it at the end
+    pub fn total_cost(&self) -> Decimal {
+        self.cap_cost_reduction + self.monthly_payment() * Decimal::from(self.term_months) + self.mileage_charge()
+    }
+}
+
+/// Financing the car instead, over the months of the lease.
+#[derive(Debug, Clone)]
+pub struct FinancedOverLease {
+    // Cash down, the car loan's closing costs and the payments made
+    pub paid: Decimal,
+    pub balance: Decimal,
+    // The car's value less what is still owed on it
+    pub equity: Decimal,
+}
+
+impl FinancedOverLease {
+    // What owning cost to the end of the lease, counting the car kept
+    pub fn net_cost(&self) -> Decimal {
+        self.paid - self.equity
+    }
+}
+
+impl LoanCalculator {
+    // The car is valued at the lease's residual, the lessor's own forecast of
+    // what it is worth at turn-in
+    pub fn finance_over_lease(
+        &self,
+        bank_name: &str,
+        loan_amount: Decimal,
+        annual_rate: Decimal,
+        loan_term: u32,
+        cash_down: Decimal,
+        lease: &Lease,
+    ) -> FinancedOverLease {
+        let schedule = self.generate_schedule(loan_amount, annual_rate, loan_term, &PaymentStrategy::Standard);
+        let months = lease.term_months.min(schedule.num_payments());
+        let payments: Decimal = (1..=months).map(|month| schedule.payment_in(month)).sum();
+        // Nothing is owed on a loan shorter than the lease
+        let balance = schedule.balance_after(lease.term_months);
+        FinancedOverLease {
+            paid: cash_down + self.get_closing_costs(bank_name, &LoanType::Car) + payments,
+            balance,
+            equity: lease.residual - balance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BankConfig;
+
+    fn lease() -> Lease {
+        let mileage = Mileage {
+            allowed_per_year: 12000,
+            expected_per_year: 12000,
+            excess_fee: dec!(0.25),
+        };
+        Lease::new(dec!(30000), dec!(2000), dec!(18000), dec!(0.0025), 36, mileage).unwrap()
+    }
+
+    #[test]
+    fn financing_pays_the_car_loans_closing_costs() {
+        let calculator = |car_closing_costs: Option<f64>| {
+            let mut bank = BankConfig::new("Bank")
+                .rates(&LoanType::Home, 6.0, 6.0)
+                .rates(&LoanType::Car, 7.0, 7.0)
+                .closing_costs(6000.0);
+            bank.car_loan_range.closing_costs = car_closing_costs;
+            LoanCalculator::builder().bank(bank).build().unwrap()
+        };
+        let financed = |calculator: LoanCalculator| {
+            calculator.finance_over_lease("Bank", dec!(28000), dec!(7), 60, dec!(2000), &lease())
+        };
+        let schedule = calculator(None).generate_schedule(dec!(28000), dec!(7), 60, &PaymentStrategy::Standard);
+        let payments: Decimal = (1..=36).map(|month| schedule.payment_in(month)).sum();
+        // The mortgage's closing costs are not part of a car loan
+        assert_eq!(financed(calculator(None)).paid, dec!(2000) + payments);
+        assert_eq!(financed(calculator(Some(400.0))).paid, dec!(2400) + payments);
+    }
+}
@@ -0,0 +1,1207 @@
+//! The loan math behind the `loancalc` command line tool.
+//!
+//! A [`LoanCalculator`] holds the banks it quotes for, loaded from a
+//! banks.yaml document with [`LoanCalculator::from_config`], from a YAML,
+//! TOML or JSON file with [`LoanCalculator::from_config_file`], or
+//! assembled in code with [`LoanCalculator::builder`]. The payment formulas are also
+//! available on their own as [`monthly_payment`], [`period_payment`] and
+//! [`remaining_balance`], and [`exact_amortization`] gives the totals a
+//! servicer charges once every payment is rounded to the cent.
+//!
+//! Rates are annual percentages (`6.5` for 6.5%), terms are in months and
+//! amounts are [`Decimal`]s. Fallible calls return [`Error`] rather than
+//! panicking on bad input.
+
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+pub mod affordability;
+pub mod application;
+mod approx;
+pub mod arm;
+mod cache;
+#[cfg(feature = "chart")]
+pub mod chart;
+pub mod collateral;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod combined;
+pub mod config;
+pub mod conventions;
+pub mod credit;
+pub mod dates;
+pub mod depreciation;
+pub mod disclosure;
+pub mod dti;
+mod error;
+pub mod escrow;
+pub mod frequency;
+pub mod history;
+pub mod interest;
+pub mod lease;
+pub mod money;
+pub mod payoff;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod plot;
+pub mod pmi;
+pub mod points;
+pub mod prepay;
+pub mod provenance;
+pub mod purchase;
+#[cfg(feature = "python")]
+mod python;
+pub mod quotes;
+pub mod ranking;
+pub mod rates;
+pub mod refinance;
+pub mod rentbuy;
+pub mod report;
+pub mod results;
+pub mod revolving;
+pub mod scenarios;
+pub mod schedule;
+pub mod spreadsheet;
+pub mod student;
+pub mod tax;
+pub mod vehicle;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::Error;
+pub use rust_decimal::Decimal;
+
+use cache::{PaymentFactors, QuoteCache};
+use points::{PointsConfig, PointsOption};
+use quotes::{HttpJsonProvider, HttpQuoteConfig, QuoteProvider, QuoteRequest};
+use config::{ConfigFormat, ConfigSource};
+use conventions::{CentRate, Compounding, InterestConventions, Rounding, RoundingMode};
+use credit::{CreditTierConfig, CreditTiers};
+use depreciation::DepreciationCurve;
+use money::Currency;
+use ranking::{RankingConfig, RankingWeights};
+use rates::{MarketRate, MarketRatesConfig};
+
+/// The kinds of loan a bank can quote, each with its own rate range.
+#[derive(Debug, Clone)]
+pub enum LoanType {
+    Home,
+    Car,
+    Personal,
+    Student,
+}
+
+impl LoanType {
+    pub fn get_default_amount(&self) -> f64 {
+        match self {
+            LoanType::Home => 300_000.0,
+            LoanType::Car => 25_000.0,
+            LoanType::Personal => 10_000.0,
+            LoanType::Student => 30_000.0,
+        }
+    }
+
+    pub fn get_max_amount(&self) -> f64 {
+        match self {
+            LoanType::Home => 10_000_000.0,
+            LoanType::Car => 150_000.0,
+            LoanType::Personal => 100_000.0,
+            LoanType::Student => 500_000.0,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LoanType> {
+        match name.to_lowercase().as_str() {
+            "home" => Some(LoanType::Home),
+            "car" => Some(LoanType::Car),
+            "personal" => Some(LoanType::Personal),
+            "student" => Some(LoanType::Student),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        match self {
+            LoanType::Home => "home",
+            LoanType::Car => "car",
+            LoanType::Personal => "personal",
+            LoanType::Student => "student",
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            LoanType::Home => "Home loans typically range from $100,000 to $10,000,000",
+            LoanType::Car => "Car loans typically range from $5,000 to $150,000",
+            LoanType::Personal => "Personal loans typically range from $1,000 to $100,000",
+            LoanType::Student => "Student loans typically range from $1,000 to $200,000",
+        }
+    }
+
+    // Terms are in months
+    pub fn get_max_term(&self) -> u32 {
+        match self {
+            LoanType::Home => 480,
+            LoanType::Car => 84,
+            LoanType::Personal => 84,
+            LoanType::Student => 300,
+        }
+    }
+
+    // Common terms to weigh against each other, in months
+    pub fn get_comparison_terms(&self) -> &[u32] {
+        match self {
+            LoanType::Home => &[180, 240, 360],
+            LoanType::Car => &[36, 48, 60, 72],
+            LoanType::Personal => &[24, 36, 48, 60],
+            LoanType::Student => &[120, 180, 240],
+        }
+    }
+
+    pub fn get_default_term(&self) -> u32 {
+        match self {
+            LoanType::Home => 360,
+            LoanType::Car => 60,
+            LoanType::Personal => 36,
+            LoanType::Student => 120,
+        }
+    }
+}
+
+/// Annual rates a bank quotes for one loan type; 0 to 0 means it does not
+/// offer that loan.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RateRange {
+    pub min: f64,
+    pub max: f64,
+    // Longest term the bank offers for this loan type
+    #[serde(default)]
+    pub max_term_months: Option<u32>,
+    // Purchase LTV percentage above which the offer carries a warning
+    #[serde(default)]
+    pub ltv_warning: Option<f64>,
+    // Flat fees paid at closing on this loan type; unset, home loans take
+    // the bank's closing_costs and other loans have none
+    #[serde(default)]
+    pub closing_costs: Option<f64>,
+}
+
+/// One bank as written in banks.yaml.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BankConfig {
+    pub name: String,
+    pub home_loan_range: RateRange,
+    pub car_loan_range: RateRange,
+    pub personal_loan_range: RateRange,
+    // Optional so configs written before student loans still load
+    #[serde(default)]
+    pub student_loan_range: RateRange,
+    pub min_credit_score: u16,
+    #[serde(default)]
+    pub max_ltv: Option<f64>,
+    #[serde(default = "default_allows_cross_collateral")]
+    pub allows_cross_collateral: bool,
+    #[serde(default)]
+    pub points_pricing: Vec<PointsConfig>,
+    // Flat fees paid at closing on a home loan
+    #[serde(default)]
+    pub closing_costs: f64,
+    // Both percent of the amount borrowed, paid at closing
+    #[serde(default)]
+    pub origination_fee: f64,
+    #[serde(default)]
+    pub points: f64,
+    #[serde(default)]
+    pub max_cash_out_ltv: Option<f64>,
+    // Annual mortgage insurance as a percent of the amount borrowed, charged
+    // on home purchases financed above 80% LTV
+    #[serde(default = "default_pmi_rate")]
+    pub pmi_rate: f64,
+    // Highest debt-to-income percents the bank lends at: housing alone
+    // (front-end), and housing plus other debts (back-end)
+    #[serde(default = "default_max_front_end_dti")]
+    pub max_front_end_dti: f64,
+    #[serde(default = "default_max_back_end_dti")]
+    pub max_back_end_dti: f64,
+    // Customer satisfaction and service scores out of 5
+    #[serde(default)]
+    pub satisfaction_rating: Option<f64>,
+    #[serde(default)]
+    pub service_rating: Option<f64>,
+    // Percent of the balance charged for paying off early
+    #[serde(default)]
+    pub prepayment_penalty: f64,
+    // Lender API asked for a soft quote before falling back to the ranges
+    #[serde(default)]
+    pub live_quote: Option<HttpQuoteConfig>,
+    // Replaces the global credit_tiers for this bank
+    #[serde(default)]
+    pub credit_tiers: Option<Vec<CreditTierConfig>>,
+    // How the bank's quoted rates compound, semi_annual for Canadian
+    // mortgages; unset follows --compounding
+    #[serde(default)]
+    pub compounding: Option<Compounding>,
+}
+
+fn default_allows_cross_collateral() -> bool {
+    true
+}
+
+fn default_pmi_rate() -> f64 {
+    0.5
+}
+
+fn default_max_front_end_dti() -> f64 {
+    28.0
+}
+
+fn default_max_back_end_dti() -> f64 {
+    36.0
+}
+
+impl BankConfig {
+    /// A bank that offers no loans yet and accepts any credit score; chain
+    /// [`BankConfig::rates`] for each loan type it quotes.
+    pub fn new(name: &str) -> Self {
+        BankConfig {
+            name: name.to_string(),
+            home_loan_range: RateRange::default(),
+            car_loan_range: RateRange::default(),
+            personal_loan_range: RateRange::default(),
+            student_loan_range: RateRange::default(),
+            min_credit_score: 300,
+            max_ltv: None,
+            allows_cross_collateral: default_allows_cross_collateral(),
+            points_pricing: Vec::new(),
+            closing_costs: 0.0,
+            origination_fee: 0.0,
+            points: 0.0,
+            max_cash_out_ltv: None,
+            pmi_rate: default_pmi_rate(),
+            max_front_end_dti: default_max_front_end_dti(),
+            max_back_end_dti: default_max_back_end_dti(),
+            satisfaction_rating: None,
+            service_rating: None,
+            prepayment_penalty: 0.0,
+            live_quote: None,
+            credit_tiers: None,
+            compounding: None,
+        }
+    }
+
+    fn range_mut(&mut self, loan_type: &LoanType) -> &mut RateRange {
+        match loan_type {
+            LoanType::Home => &mut self.home_loan_range,
+            LoanType::Car => &mut self.car_loan_range,
+            LoanType::Personal => &mut self.personal_loan_range,
+            LoanType::Student => &mut self.student_loan_range,
+        }
+    }
+
+    pub fn rates(mut self, loan_type: &LoanType, min: f64, max: f64) -> Self {
+        let range = self.range_mut(loan_type);
+        range.min = min;
+        range.max = max;
+        self
+    }
+
+    pub fn max_term_months(mut self, loan_type: &LoanType, months: u32) -> Self {
+        self.range_mut(loan_type).max_term_months = Some(months);
+        self
+    }
+
+    pub fn min_credit_score(mut self, score: u16) -> Self {
+        self.min_credit_score = score;
+        self
+    }
+
+    pub fn closing_costs(mut self, amount: f64) -> Self {
+        self.closing_costs = amount;
+        self
+    }
+
+    pub fn origination_fee(mut self, percent: f64) -> Self {
+        self.origination_fee = percent;
+        self
+    }
+
+    pub fn points(mut self, percent: f64) -> Self {
+        self.points = percent;
+        self
+    }
+
+    pub fn credit_tiers(mut self, tiers: Vec<CreditTierConfig>) -> Self {
+        self.credit_tiers = Some(tiers);
+        self
+    }
+
+    pub fn compounding(mut self, compounding: Compounding) -> Self {
+        self.compounding = Some(compounding);
+        self
+    }
+}
+
+/// A bank ready to quote, with amounts converted to [`Decimal`].
+#[derive(Debug, Clone)]
+pub struct Bank {
+    pub name: String,
+    pub home_loan_range: (Decimal, Decimal),
+    pub car_loan_range: (Decimal, Decimal),
+    pub personal_loan_range: (Decimal, Decimal),
+    pub student_loan_range: (Decimal, Decimal),
+    pub home_max_term: Option<u32>,
+    pub car_max_term: Option<u32>,
+    pub personal_max_term: Option<u32>,
+    pub student_max_term: Option<u32>,
+    pub home_ltv_warning: Option<Decimal>,
+    pub car_ltv_warning: Option<Decimal>,
+    pub personal_ltv_warning: Option<Decimal>,
+    pub student_ltv_warning: Option<Decimal>,
+    pub min_credit_score: u16,
+    pub max_ltv: Option<Decimal>,
+    pub allows_cross_collateral: bool,
+    pub points_pricing: Vec<PointsOption>,
+    pub home_closing_costs: Decimal,
+    pub car_closing_costs: Decimal,
+    pub personal_closing_costs: Decimal,
+    pub student_closing_costs: Decimal,
+    pub origination_fee: Decimal,
+    pub points: Decimal,
+    pub max_cash_out_ltv: Option<Decimal>,
+    pub pmi_rate: Decimal,
+    pub max_front_end_dti: Decimal,
+    pub max_back_end_dti: Decimal,
+    pub rating: Option<Decimal>,
+    pub prepayment_penalty: Decimal,
+    pub credit_tiers: Option<CreditTiers>,
+    pub compounding: Option<Compounding>,
+}
+
+// Rejects NaN and infinities, which YAML allows but Decimal cannot hold
+fn config_decimal(bank: &str, field: &str, value: f64) -> Result<Decimal, Error> {
+    Decimal::from_f64(value).ok_or_else(|| Error::Config(format!("{} {} of {} is not a usable number", bank, field, value)))
+}
+
+fn config_rates(bank: &str, field: &str, range: &RateRange) -> Result<(Decimal, Decimal), Error> {
+    Ok((
+        config_decimal(bank, field, range.min)?,
+        config_decimal(bank, field, range.max)?,
+    ))
+}
+
+fn config_ltv_warning(bank: &str, field: &str, range: &RateRange) -> Result<Option<Decimal>, Error> {
+    range
+        .ltv_warning
+        .map(|ltv| config_decimal(bank, &format!("{}.ltv_warning", field), ltv))
+        .transpose()
+}
+
+fn config_closing_costs(bank: &str, field: &str, range: &RateRange, default: Decimal) -> Result<Decimal, Error> {
+    match range.closing_costs {
+        Some(amount) => config_decimal(bank, &format!("{}.closing_costs", field), amount),
+        None => Ok(default),
+    }
+}
+
+impl TryFrom<BankConfig> for Bank {
+    type Error = Error;
+
+    fn try_from(config: BankConfig) -> Result<Self, Error> {
+        let name = config.name.as_str();
+        let closing_costs = config_decimal(name, "closing_costs", config.closing_costs)?;
+        Ok(Bank {
+            home_loan_range: config_rates(name, "home_loan_range", &config.home_loan_range)?,
+            car_loan_range: config_rates(name, "car_loan_range", &config.car_loan_range)?,
+            personal_loan_range: config_rates(name, "personal_loan_range", &config.personal_loan_range)?,
+            student_loan_range: config_rates(name, "student_loan_range", &config.student_loan_range)?,
+            home_max_term: config.home_loan_range.max_term_months,
+            car_max_term: config.car_loan_range.max_term_months,
+            personal_max_term: config.personal_loan_range.max_term_months,
+            student_max_term: config.student_loan_range.max_term_months,
+            home_ltv_warning: config_ltv_warning(name, "home_loan_range", &config.home_loan_range)?,
+            car_ltv_warning: config_ltv_warning(name, "car_loan_range", &config.car_loan_range)?,
+            personal_ltv_warning: config_ltv_warning(name, "personal_loan_range", &config.personal_loan_range)?,
+            student_ltv_warning: config_ltv_warning(name, "student_loan_range", &config.student_loan_range)?,
+            min_credit_score: config.min_credit_score,
+            max_ltv: config.max_ltv.map(|ltv| config_decimal(name, "max_ltv", ltv)).transpose()?,
+            allows_cross_collateral: config.allows_cross_collateral,
+            points_pricing: config
+                .points_pricing
+                .into_iter()
+                .map(PointsOption::try_from)
+                .collect::<Result<_, _>>()
+                .map_err(|e| Error::Config(format!("{} {}", name, e)))?,
+            home_closing_costs: config_closing_costs(name, "home_loan_range", &config.home_loan_range, closing_costs)?,
+            car_closing_costs: config_closing_costs(name, "car_loan_range", &config.car_loan_range, dec!(0))?,
+            personal_closing_costs: config_closing_costs(
+                name,
+                "personal_loan_range",
+                &config.personal_loan_range,
+                dec!(0),
+            )?,
+            student_closing_costs: config_closing_costs(
+                name,
+                "student_loan_range",
+                &config.student_loan_range,
+                dec!(0),
+            )?,
+            origination_fee: config_decimal(name, "origination_fee", config.origination_fee)?,
+            points: config_decimal(name, "points", config.points)?,
+            max_cash_out_ltv: config
+                .max_cash_out_ltv
+                .map(|ltv| config_decimal(name, "max_cash_out_ltv", ltv))
+                .transpose()?,
+            pmi_rate: config_decimal(name, "pmi_rate", config.pmi_rate)?,
+            max_front_end_dti: config_decimal(name, "max_front_end_dti", config.max_front_end_dti)?,
+            max_back_end_dti: config_decimal(name, "max_back_end_dti", config.max_back_end_dti)?,
+            // A bank's rating averages whichever scores it has
+            rating: match (config.satisfaction_rating, config.service_rating) {
+                (Some(satisfaction), Some(service)) => Decimal::from_f64((satisfaction + service) / 2.0),
+                (Some(rating), None) | (None, Some(rating)) => Decimal::from_f64(rating),
+                (None, None) => None,
+            },
+            prepayment_penalty: config_decimal(name, "prepayment_penalty", config.prepayment_penalty)?,
+            credit_tiers: config
+                .credit_tiers
+                .map(|tiers| CreditTiers::try_from(tiers).map_err(|e| Error::Config(format!("{} {}", name, e))))
+                .transpose()?,
+            compounding: config.compounding,
+            name: config.name,
+        })
+    }
+}
+
+/// Rates outside these bounds are almost always typos, such as 65 for 6.5.
+/// `market_bounds` in banks.yaml overrides the defaults per loan type.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MarketBounds {
+    #[serde(default)]
+    pub home: Option<RateRange>,
+    #[serde(default)]
+    pub car: Option<RateRange>,
+    #[serde(default)]
+    pub personal: Option<RateRange>,
+    #[serde(default)]
+    pub student: Option<RateRange>,
+}
+
+impl MarketBounds {
+    pub fn get(&self, loan_type: &LoanType) -> (f64, f64) {
+        let (configured, default) = match loan_type {
+            LoanType::Home => (&self.home, (2.0, 12.0)),
+            LoanType::Car => (&self.car, (1.0, 25.0)),
+            LoanType::Personal => (&self.personal, (4.0, 36.0)),
+            LoanType::Student => (&self.student, (2.0, 16.0)),
+        };
+        configured.as_ref().map(|range| (range.min, range.max)).unwrap_or(default)
+    }
+}
+
+/// How much of each bank's rate range the comparison shows: the midpoint
+/// quote alone, or the best and worst case beside it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateDisplay {
+    #[default]
+    Midpoint,
+    Range,
+}
+
+impl RateDisplay {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "midpoint" => Some(RateDisplay::Midpoint),
+            "range" => Some(RateDisplay::Range),
+            _ => None,
+        }
+    }
+}
+
+/// A bank's best-case, midpoint and worst-case rates for one borrower.
+#[derive(Debug, Clone, Copy)]
+pub struct RateQuotes {
+    pub best: Decimal,
+    pub midpoint: Decimal,
+    pub worst: Decimal,
+}
+
+/// The whole banks.yaml document.
+#[derive(Debug, Default, Deserialize)]
+pub struct BanksConfig {
+    pub banks: Vec<BankConfig>,
+    // Days other than weekends on which payments are not processed
+    #[serde(default)]
+    pub holidays: Vec<chrono::NaiveDate>,
+    // How the recommended offer is chosen
+    #[serde(default)]
+    pub ranking: RankingConfig,
+    // When the configured rate ranges were last checked
+    #[serde(default)]
+    pub rates_as_of: Option<chrono::NaiveDate>,
+    // Plausible rates per loan type, for `config lint`
+    #[serde(default)]
+    pub market_bounds: MarketBounds,
+    // Rate changes by credit score, for banks without their own
+    #[serde(default)]
+    pub credit_tiers: Option<Vec<CreditTierConfig>>,
+    // Whether comparisons show each bank's best and worst case too
+    #[serde(default)]
+    pub rate_display: RateDisplay,
+    // Where current average rates are fetched from, if anywhere
+    #[serde(default)]
+    pub market_rates: Option<MarketRatesConfig>,
+    // How payments and interest are rounded to the cent
+    #[serde(default)]
+    pub rounding: RoundingMode,
+    // How interest accrues between payments, per loan type
+    #[serde(default)]
+    pub interest_conventions: InterestConventions,
+    // ISO code of the currency amounts are in, US dollars when left out
+    #[serde(default)]
+    pub currency: Option<String>,
+    // Percent of a car's value lost in each year of its age, the last
+    // repeating; a typical curve when left out
+    #[serde(default)]
+    pub depreciation: Option<Vec<f64>>,
+}
+
+impl Bank {
+    pub fn get_rate_range(&self, loan_type: &LoanType) -> (Decimal, Decimal) {
+        match loan_type {
+            LoanType::Home => self.home_loan_range,
+            LoanType::Car => self.car_loan_range,
+            LoanType::Personal => self.personal_loan_range,
+            LoanType::Student => self.student_loan_range,
+        }
+    }
+
+    pub fn get_max_term(&self, loan_type: &LoanType) -> Option<u32> {
+        match loan_type {
+            LoanType::Home => self.home_max_term,
+            LoanType::Car => self.car_max_term,
+            LoanType::Personal => self.personal_max_term,
+            LoanType::Student => self.student_max_term,
+        }
+    }
+
+    pub fn get_closing_costs(&self, loan_type: &LoanType) -> Decimal {
+        match loan_type {
+            LoanType::Home => self.home_closing_costs,
+            LoanType::Car => self.car_closing_costs,
+            LoanType::Personal => self.personal_closing_costs,
+            LoanType::Student => self.student_closing_costs,
+        }
+    }
+
+    fn closing_costs_mut(&mut self, loan_type: &LoanType) -> &mut Decimal {
+        match loan_type {
+            LoanType::Home => &mut self.home_closing_costs,
+            LoanType::Car => &mut self.car_closing_costs,
+            LoanType::Personal => &mut self.personal_closing_costs,
+            LoanType::Student => &mut self.student_closing_costs,
+        }
+    }
+
+    // Reason the bank would decline the term, if any
+    pub fn check_term(&self, loan_type: &LoanType, months: u32) -> Option<String> {
+        match self.get_max_term(loan_type) {
+            Some(max_months) if months > max_months => Some(format!(
+                "offers terms up to {} for this loan type",
+                format_months(max_months)
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn checked_pow(base: Decimal, exp: u32) -> Option<Decimal> {
+    let mut result = dec!(1);
+    let mut base = base;
+    let mut exp = exp;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.checked_mul(base)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.checked_mul(base)?;
+        }
+    }
+
+    Some(result)
+}
+
+/// The level monthly payment that repays `principal` over `num_payments`
+/// months at `annual_rate` percent, compounded monthly.
+pub fn monthly_payment(principal: Decimal, annual_rate: Decimal, num_payments: u32) -> Result<Decimal, Error> {
+    period_payment(principal, annual_rate, 12, num_payments)
+}
+
+/// The level payment that repays `principal` over `num_payments` periods
+/// when `periods_per_year` payments are made, each accruing one period of
+/// interest at `annual_rate` percent. [`monthly_payment`] is the 12-period
+/// case.
+pub fn period_payment(
+    principal: Decimal,
+    annual_rate: Decimal,
+    periods_per_year: u32,
+    num_payments: u32,
+) -> Result<Decimal, Error> {
+    let factor = annuity_factor(annual_rate, periods_per_year, num_payments)?;
+    apply_annuity_factor(principal, num_payments, factor)
+}
+
+// The payment per unit of principal, or None when the rate is too small to
+// accrue anything at Decimal precision and the principal is split evenly.
+// It depends on the rate and term alone, which is what lets the calculator
+// work it out once per rate.
+pub(crate) fn annuity_factor(
+    annual_rate: Decimal,
+    periods_per_year: u32,
+    num_payments: u32,
+) -> Result<Option<Decimal>, Error> {
+    if num_payments == 0 {
+        return Err(Error::InvalidLoan("a loan needs at least one payment".to_string()));
+    }
+    let period_rate = annual_rate / dec!(100) / Decimal::from(periods_per_year);
+
+    let base_raised = checked_pow(dec!(1) + period_rate, num_payments).ok_or(Error::Overflow)?;
+    if base_raised == dec!(1) {
+        return Ok(None);
+    }
+
+    let numerator = period_rate.checked_mul(base_raised).ok_or(Error::Overflow)?;
+    let denominator = base_raised - dec!(1);
+    Ok(Some(numerator / denominator))
+}
+
+pub(crate) fn apply_annuity_factor(principal: Decimal, num_payments: u32, factor: Option<Decimal>) -> Result<Decimal, Error> {
+    match factor {
+        Some(factor) => principal.checked_mul(factor).ok_or(Error::Overflow),
+        None => Ok(principal / Decimal::from(num_payments)),
+    }
+}
+
+/// The balance still owed after `payments_made` of the payments given by
+/// [`monthly_payment`].
+pub fn remaining_balance(
+    principal: Decimal,
+    annual_rate: Decimal,
+    num_payments: u32,
+    payments_made: u32,
+) -> Result<Decimal, Error> {
+    let payment = monthly_payment(principal, annual_rate, num_payments)?;
+    let monthly_rate = annual_rate / dec!(100) / dec!(12);
+
+    if monthly_rate.is_zero() {
+        return Ok(principal - payment * Decimal::from(payments_made));
+    }
+
+    let growth = checked_pow(dec!(1) + monthly_rate, payments_made).ok_or(Error::Overflow)?;
+    let owed = principal.checked_mul(growth).ok_or(Error::Overflow)?;
+    let paid = payment.checked_mul(growth - dec!(1)).ok_or(Error::Overflow)? / monthly_rate;
+    Ok(owed - paid)
+}
+
+/// What an amortizing loan costs as a servicer bills it, to the cent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExactAmortization {
+    /// Every payment but the last
+    pub payment: Decimal,
+    /// Whatever clears the balance, a few cents off `payment`
+    pub final_payment: Decimal,
+    pub total_interest: Decimal,
+    pub total_payment: Decimal,
+}
+
+/// Walks the loan payment by payment the way a servicer does: the
+/// [`monthly_payment`] is rounded to the cent, each month's interest is
+/// rounded to the cent before the rest of the payment goes to principal,
+/// and the last payment settles the balance left.
+pub fn exact_amortization(
+    principal: Decimal,
+    annual_rate: Decimal,
+    num_payments: u32,
+    rounding: Rounding,
+) -> Result<ExactAmortization, Error> {
+    exact_period_amortization(principal, annual_rate, 12, num_payments, rounding)
+}
+
+/// [`exact_amortization`] for `periods_per_year` payments a year, each
+/// accruing one period of interest, as [`period_payment`] figures them.
+pub fn exact_period_amortization(
+    principal: Decimal,
+    annual_rate: Decimal,
+    periods_per_year: u32,
+    num_payments: u32,
+    rounding: Rounding,
+) -> Result<ExactAmortization, Error> {
+    let payment = rounding.round_cents(period_payment(principal, annual_rate, periods_per_year, num_payments)?);
+    let period_rate = annual_rate / dec!(100) / Decimal::from(periods_per_year);
+    Ok(amortize_payments(principal, payment, period_rate, num_payments, rounding))
+}
+
+// The walk behind `exact_period_amortization`, for a `payment` already
+// rounded to the cent
+pub(crate) fn amortize_payments(
+    principal: Decimal,
+    payment: Decimal,
+    period_rate: Decimal,
+    num_payments: u32,
+    rounding: Rounding,
+) -> ExactAmortization {
+    let cent_rate = CentRate::new(rounding, period_rate);
+    let principal = rounding.round_cents(principal);
+    let (mut total_interest, balance) = amortize_cents(&cent_rate, principal, payment, num_payments - 1)
+        .unwrap_or_else(|| {
+            let mut balance = principal;
+            let mut total_interest = dec!(0);
+            for _ in 1..num_payments {
+                let interest = cent_rate.interest(balance);
+                total_interest += interest;
+                balance -= payment - interest;
+            }
+            (total_interest, balance)
+        });
+    // Rounding leaves the last balance a few cents off what one payment
+    // clears, so the final payment is whatever it takes
+    let final_interest = cent_rate.interest(balance);
+    total_interest += final_interest;
+    let final_payment = balance + final_interest;
+
+    ExactAmortization {
+        payment,
+        final_payment,
+        total_interest,
+        total_payment: payment * Decimal::from(num_payments - 1) + final_payment,
+    }
+}
+
+// The interest of `payments` level payments and the balance they leave,
+// counted in whole cents. Gives up on balances that go negative or amounts
+// too large for the cent arithmetic, which the Decimal walk then covers.
+fn amortize_cents(rate: &CentRate, principal: Decimal, payment: Decimal, payments: u32) -> Option<(Decimal, Decimal)> {
+    let rounding = rate.rounding();
+    let payment = rounding.cents_of(payment)?;
+    let mut balance = rounding.cents_of(principal)?;
+    let mut total_interest = 0i128;
+    for _ in 0..payments {
+        let interest = i128::from(rate.interest_cents(u64::try_from(balance).ok()?)?);
+        total_interest += interest;
+        balance -= payment - interest;
+    }
+    let minor_units = rounding.minor_units;
+    Some((
+        Decimal::try_from_i128_with_scale(total_interest, minor_units).ok()?,
+        Decimal::try_from_i128_with_scale(balance, minor_units).ok()?,
+    ))
+}
+
+/// Quotes, schedules and comparisons for a set of banks.
+pub struct LoanCalculator {
+    banks: Vec<Bank>,
+    holidays: Arc<[chrono::NaiveDate]>,
+    quote_cache: QuoteCache,
+    payment_factors: PaymentFactors,
+    ranking: RankingWeights,
+    credit_tiers: CreditTiers,
+    // Live quote sources, by bank name
+    quote_providers: Vec<(String, Box<dyn QuoteProvider>)>,
+    config_sha256: String,
+    rates_as_of: Option<chrono::NaiveDate>,
+    rate_display: RateDisplay,
+    market_rates_config: Option<MarketRatesConfig>,
+    // Filled in by `use_market_rates`
+    market_rates: Vec<MarketRate>,
+    rounding: RoundingMode,
+    interest_conventions: InterestConventions,
+    // For banks without their own `compounding`
+    compounding: Compounding,
+    currency: Option<Currency>,
+    depreciation: DepreciationCurve,
+}
+
+/// Assembles a [`LoanCalculator`] without a banks.yaml file.
+#[derive(Debug, Default)]
+pub struct LoanCalculatorBuilder {
+    config: BanksConfig,
+}
+
+impl LoanCalculatorBuilder {
+    pub fn bank(mut self, bank: BankConfig) -> Self {
+        self.config.banks.push(bank);
+        self
+    }
+
+    pub fn holiday(mut self, date: chrono::NaiveDate) -> Self {
+        self.config.holidays.push(date);
+        self
+    }
+
+    pub fn ranking(mut self, ranking: RankingConfig) -> Self {
+        self.config.ranking = ranking;
+        self
+    }
+
+    pub fn rates_as_of(mut self, date: chrono::NaiveDate) -> Self {
+        self.config.rates_as_of = Some(date);
+        self
+    }
+
+    pub fn credit_tiers(mut self, tiers: Vec<CreditTierConfig>) -> Self {
+        self.config.credit_tiers = Some(tiers);
+        self
+    }
+
+    pub fn rate_display(mut self, rate_display: RateDisplay) -> Self {
+        self.config.rate_display = rate_display;
+        self
+    }
+
+    pub fn market_rates(mut self, market_rates: MarketRatesConfig) -> Self {
+        self.config.market_rates = Some(market_rates);
+        self
+    }
+
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.config.rounding = rounding;
+        self
+    }
+
+    pub fn interest_conventions(mut self, interest_conventions: InterestConventions) -> Self {
+        self.config.interest_conventions = interest_conventions;
+        self
+    }
+
+    pub fn currency(mut self, code: &str) -> Self {
+        self.config.currency = Some(code.to_string());
+        self
+    }
+
+    pub fn depreciation(mut self, yearly: Vec<f64>) -> Self {
+        self.config.depreciation = Some(yearly);
+        self
+    }
+
+    // There is no YAML to checksum, so provenance hashes the settings instead
+    pub fn build(self) -> Result<LoanCalculator, Error> {
+        let checksum = provenance::config_checksum(&format!("{:?}", self.config));
+        LoanCalculator::from_banks_config(self.config, checksum)
+    }
+}
+
+impl LoanCalculator {
+    /// Loads the bank config found by [`config::resolve_config`], falling
+    /// back to the built-in banks.
+    pub fn new() -> Result<Self, Error> {
+        Self::load(&config::resolve_config(None))
+    }
+
+    pub fn load(source: &ConfigSource) -> Result<Self, Error> {
+        match source {
+            ConfigSource::File(path) => Self::from_config_file(path),
+            ConfigSource::Builtin => Self::from_config(config::DEFAULT_CONFIG),
+        }
+    }
+
+    /// Loads a bank config, reading it as YAML, TOML or JSON by its extension.
+    pub fn from_config_file(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let format = ConfigFormat::from_path(path).ok_or_else(|| {
+            Error::Config(format!("{}: expected a .yaml, .yml, .toml or .json bank config", file))
+        })?;
+        let contents = fs::read_to_string(path).map_err(|e| {
+            Error::Io(std::io::Error::new(e.kind(), format!("{}: {}", file, e)))
+        })?;
+        let banks_config = config::parse_config(&contents, format, &file)?;
+        Self::from_banks_config(banks_config, provenance::config_checksum(&contents)).map_err(|e| match e {
+            Error::Config(message) => Error::Config(format!("{}: {}", file, message)),
+            e => e,
+        })
+    }
+
+    /// Parses a banks.yaml document.
+    pub fn from_config(config_str: &str) -> Result<Self, Error> {
+        let config: BanksConfig = serde_yaml::from_str(config_str)?;
+        Self::from_banks_config(config, provenance::config_checksum(config_str))
+    }
+
+    pub fn builder() -> LoanCalculatorBuilder {
+        LoanCalculatorBuilder::default()
+    }
+
+    fn from_banks_config(config: BanksConfig, config_sha256: String) -> Result<Self, Error> {
+        let ranking = RankingWeights::try_from(config.ranking).map_err(Error::Config)?;
+        let credit_tiers = CreditTiers::try_from(config.credit_tiers.unwrap_or_else(credit::default_credit_tiers))
+            .map_err(Error::Config)?;
+        if config
+            .market_rates
+            .as_ref()
+            .is_some_and(|market_rates| market_rates.source == rates::MarketRateSource::Json && market_rates.url.is_none())
+        {
+            return Err(Error::Config("market_rates needs a url for the json source".to_string()));
+        }
+        let mut quote_providers: Vec<(String, Box<dyn QuoteProvider>)> = Vec::new();
+        for bank in &config.banks {
+            if let Some(live_quote) = &bank.live_quote {
+                quote_providers.push((bank.name.clone(), Box::new(HttpJsonProvider::new(live_quote.clone()))));
+            }
+        }
+        let banks = config
+            .banks
+            .into_iter()
+            .map(Bank::try_from)
+            .collect::<Result<Vec<Bank>, Error>>()?;
+        if let Some(bank) = banks
+            .iter()
+            .find(|bank| bank.rating.is_some_and(|rating| rating < dec!(0) || rating > dec!(5)))
+        {
+            return Err(Error::Config(format!("{} ratings in banks.yaml must be between 0 and 5", bank.name)));
+        }
+        let currency = match &config.currency {
+            Some(code) => Some(Currency::from_code(code).ok_or_else(|| {
+                Error::Config(format!("unknown currency '{}' (expected one of {})", code, Currency::codes().join(", ")))
+            })?),
+            None => None,
+        };
+        let depreciation = match config.depreciation {
+            Some(yearly) => DepreciationCurve::try_from(yearly).map_err(Error::Config)?,
+            None => DepreciationCurve::default(),
+        };
+        Ok(Self {
+            banks,
+            holidays: config.holidays.into(),
+            quote_cache: QuoteCache::default(),
+            payment_factors: PaymentFactors::default(),
+            ranking,
+            credit_tiers,
+            quote_providers,
+            config_sha256,
+            rates_as_of: config.rates_as_of,
+            rate_display: config.rate_display,
+            market_rates_config: config.market_rates,
+            market_rates: Vec::new(),
+            rounding: config.rounding,
+            interest_conventions: config.interest_conventions,
+            compounding: Compounding::Monthly,
+            currency,
+            depreciation,
+        })
+    }
+
+    pub fn banks(&self) -> &[Bank] {
+        &self.banks
+    }
+
+    pub fn holidays(&self) -> Arc<[chrono::NaiveDate]> {
+        self.holidays.clone()
+    }
+
+    pub fn ranking(&self) -> &RankingWeights {
+        &self.ranking
+    }
+
+    pub fn rate_display(&self) -> RateDisplay {
+        self.rate_display
+    }
+
+    // Zero for a loan with no payments; `monthly_payment` says why
+    pub fn calculate_monthly_payment(&self, principal: Decimal, annual_rate: Decimal, num_payments: u32) -> Decimal {
+        self.checked_monthly_payment(principal, annual_rate, num_payments).unwrap_or_default()
+    }
+
+    // `monthly_payment`, with the annuity factor of each rate and term
+    // worked out once, since banks quote the same few over and over
+    pub(crate) fn checked_monthly_payment(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> Result<Decimal, Error> {
+        if num_payments == 0 {
+            return monthly_payment(principal, annual_rate, num_payments);
+        }
+        let factor = self
+            .payment_factors
+            .get_or_insert_with(annual_rate, num_payments, || annuity_factor(annual_rate, 12, num_payments).ok())
+            .ok_or(Error::Overflow)?;
+        apply_annuity_factor(principal, num_payments, factor)
+    }
+
+    pub fn calculate_remaining_balance(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        payments_made: u32,
+    ) -> Decimal {
+        remaining_balance(principal, annual_rate, num_payments, payments_made).unwrap_or_default()
+    }
+
+    // Uses the global credit_tiers, or the defaults when banks.yaml has none
+    pub fn adjust_rate_for_credit(&self, base_rate: Decimal, credit_score: u16) -> Decimal {
+        base_rate + self.credit_tiers.rate_change(credit_score)
+    }
+
+    pub fn adjust_bank_rate_for_credit(&self, bank: &Bank, base_rate: Decimal, credit_score: u16) -> Decimal {
+        let tiers = bank.credit_tiers.as_ref().unwrap_or(&self.credit_tiers);
+        base_rate + tiers.rate_change(credit_score)
+    }
+
+
+    // Asks each bank with a quote API for a live rate. Banks missing from the
+    // result use their configured ranges; None means the lender declined.
+    // Quoted closing costs replace the configured ones.
+    pub fn fetch_live_quotes(
+        &mut self,
+        request: &QuoteRequest,
+        warnings: &mut Vec<String>,
+    ) -> HashMap<String, Option<Decimal>> {
+        let mut quotes = HashMap::new();
+        for (name, provider) in &self.quote_providers {
+            match provider.fetch_quote(request) {
+                Ok(Some(quote)) => {
+                    if let (Some(closing_costs), Some(bank), Some(loan_type)) = (
+                        quote.closing_costs,
+                        self.banks.iter_mut().find(|bank| bank.name == *name),
+                        LoanType::from_name(request.loan_type),
+                    ) {
+                        *bank.closing_costs_mut(&loan_type) = closing_costs;
+                    }
+                    quotes.insert(name.clone(), Some(quote.rate));
+                }
+                Ok(None) => {
+                    quotes.insert(name.clone(), None);
+                }
+                Err(e) => warnings.push(format!(
+                    "{} live quote failed ({}); using configured rates",
+                    name, e
+                )),
+            }
+        }
+        quotes
+    }
+
+    // Where a bank's live quotes come from, if it has a quote API
+    pub fn quote_source(&self, bank_name: &str) -> Option<&str> {
+        self.quote_providers
+            .iter()
+            .find(|(name, _)| name == bank_name)
+            .map(|(_, provider)| provider.source())
+    }
+
+    // Banks price from the middle of their range, adjusted for credit and
+    // converted to compound monthly
+    pub fn quote_rate(&self, bank: &Bank, loan_type: &LoanType, credit_score: u16) -> Decimal {
+        let (min_rate, max_rate) = bank.get_rate_range(loan_type);
+        let quoted = self.adjust_bank_rate_for_credit(bank, (min_rate + max_rate) / dec!(2), credit_score);
+        self.monthly_rate(&bank.name, quoted)
+    }
+
+    // Each end of the bank's range, adjusted for credit the same way
+    pub fn quote_rate_range(&self, bank: &Bank, loan_type: &LoanType, credit_score: u16) -> RateQuotes {
+        let (min_rate, max_rate) = bank.get_rate_range(loan_type);
+        RateQuotes {
+            best: self.monthly_rate(&bank.name, self.adjust_bank_rate_for_credit(bank, min_rate, credit_score)),
+            midpoint: self.quote_rate(bank, loan_type, credit_score),
+            worst: self.monthly_rate(&bank.name, self.adjust_bank_rate_for_credit(bank, max_rate, credit_score)),
+        }
+    }
+
+    pub fn get_points_pricing(&self, bank_name: &str) -> Option<&[PointsOption]> {
+        self.banks
+            .iter()
+            .find(|bank| bank.name == bank_name && !bank.points_pricing.is_empty())
+            .map(|bank| bank.points_pricing.as_slice())
+    }
+
+    pub fn get_bank(&self, bank_name: &str) -> Option<&Bank> {
+        self.banks.iter().find(|bank| bank.name == bank_name)
+    }
+
+    pub fn get_closing_costs(&self, bank_name: &str, loan_type: &LoanType) -> Decimal {
+        self.get_bank(bank_name)
+            .map(|bank| bank.get_closing_costs(loan_type))
+            .unwrap_or(dec!(0))
+    }
+
+    pub fn get_rating(&self, bank_name: &str) -> Option<Decimal> {
+        self.get_bank(bank_name).and_then(|bank| bank.rating)
+    }
+
+    pub fn get_prepayment_penalty(&self, bank_name: &str) -> Decimal {
+        self.get_bank(bank_name)
+            .map(|bank| bank.prepayment_penalty)
+            .unwrap_or(dec!(0))
+    }
+
+    pub fn get_min_credit_score(&self) -> u16 {
+        self.banks.iter().map(|bank| bank.min_credit_score).min().unwrap_or(300)
+    }
+}
+
+// In the currency and locale given to `money::set_money_format`, US
+// dollars until then
+pub fn format_money(amount: Decimal) -> String {
+    money::money_format().format(amount)
+}
+
+pub fn format_months(months: u32) -> String {
+    match (months / 12, months % 12) {
+        (years, 0) => format!("{} yrs", years),
+        (0, months) => format!("{} mos", months),
+        (years, months) => format!("{} yrs {} mos", years, months),
+    }
+}
+
+// The note rate, with the APR beside it when fees or the interest method
+// make borrowing cost more than the note rate says
+pub fn format_rate(rate: Decimal, apr: Option<Decimal>) -> String {
+    match apr {
+        Some(apr) if (apr - rate).abs() >= dec!(0.005) => {
+            format!("{:.2}% ({:.2}% APR)", rate.round_dp(2), apr.round_dp(2))
+        }
+        _ => format!("{:.2}%", rate.round_dp(2)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_amortization_bills_the_rounded_payment() {
+        let amortization = exact_amortization(dec!(200000), dec!(6), 360, Rounding::cents(RoundingMode::HalfUp)).unwrap();
+        assert_eq!(amortization.payment, dec!(1199.10));
+        assert_eq!(
+            amortization.total_payment,
+            amortization.payment * dec!(359) + amortization.final_payment
+        );
+        assert_eq!(amortization.total_payment - amortization.total_interest, dec!(200000));
+    }
+
+    #[test]
+    fn final_payment_settles_what_rounding_leaves() {
+        // 333.33 twice leaves 333.34 for the last payment
+        let amortization = exact_amortization(dec!(1000), dec!(0), 3, Rounding::cents(RoundingMode::HalfUp)).unwrap();
+        assert_eq!(amortization.payment, dec!(333.33));
+        assert_eq!(amortization.final_payment, dec!(333.34));
+        assert_eq!(amortization.total_interest, dec!(0));
+
+        let amortization = exact_amortization(dec!(25000), dec!(7.25), 60, Rounding::cents(RoundingMode::HalfUp)).unwrap();
+        assert_ne!(amortization.final_payment, amortization.payment);
+        assert!((amortization.final_payment - amortization.payment).abs() < dec!(1));
+        assert_eq!(amortization.final_payment, amortization.final_payment.round_dp(2));
+    }
+
+    #[test]
+    fn exact_amortization_needs_a_payment() {
+        assert!(exact_amortization(dec!(1000), dec!(5), 0, Rounding::cents(RoundingMode::HalfUp)).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::fs;
+
+use colored::Colorize;
+use loancalc::config::{self, ConfigFormat, ConfigSource};
+use loancalc::credit::CreditTierConfig;
+use loancalc::{BankConfig, BanksConfig, LoanCalculator, LoanType, MarketBounds, RateRange};
+
+pub const LOAN_TYPES: [LoanType; 4] = [LoanType::Home, LoanType::Car, LoanType::Personal, LoanType::Student];
+
+// Rate data this old probably no longer matches the market
+const STALE_AFTER_DAYS: i64 = 180;
+
+struct Finding {
+    subject: String,
+    problem: String,
+    suggestion: String,
+}
+
+pub fn rate_range<'a>(bank: &'a BankConfig, loan_type: &LoanType) -> &'a RateRange {
+    match loan_type {
+        LoanType::Home => &bank.home_loan_range,
+        LoanType::Car => &bank.car_loan_range,
+        LoanType::Personal => &bank.personal_loan_range,
+        LoanType::Student => &bank.student_loan_range,
+    }
+}
+
+// The bank-wide closing_costs are a home loan's; other loan types charge
+// only what their own range sets
+pub fn closing_costs(bank: &BankConfig, loan_type: &LoanType) -> f64 {
+    match (rate_range(bank, loan_type).closing_costs, loan_type) {
+        (Some(amount), _) => amount,
+        (None, LoanType::Home) => bank.closing_costs,
+        (None, _) => 0.0,
+    }
+}
+
+pub fn offers(range: &RateRange) -> bool {
+    range.min != 0.0 || range.max != 0.0
+}
+
+// A better score should never cost more
+fn lint_credit_tiers(subject: &str, tiers: &[CreditTierConfig], findings: &mut Vec<Finding>) {
+    let mut sorted: Vec<&CreditTierConfig> = tiers.iter().collect();
+    sorted.sort_by_key(|tier| tier.min_score);
+    for pair in sorted.windows(2) {
+        let (lower, higher) = (pair[0], pair[1]);
+        if higher.rate_change > lower.rate_change {
+            findings.push(Finding {
+                subject: subject.to_string(),
+                problem: format!(
+                    "credit_tiers charge a score of {} more ({:+}%) than a score of {} ({:+}%)",
+                    higher.min_score, higher.rate_change, lower.min_score, lower.rate_change
+                ),
+                suggestion: "rate_change should fall as min_score rises".to_string(),
+            });
+        }
+    }
+}
+
+fn lint_bank(bank: &BankConfig, bounds: &MarketBounds, findings: &mut Vec<Finding>) {
+    let mut add = |problem: String, suggestion: String| {
+        findings.push(Finding {
+            subject: bank.name.clone(),
+            problem,
+            suggestion,
+        })
+    };
+
+    for loan_type in &LOAN_TYPES {
+        let name = loan_type.get_name();
+        let range = rate_range(bank, loan_type);
+        if !offers(range) {
+            continue;
+        }
+        if range.min > range.max {
+            add(
+                format!("{}_loan_range is inverted ({}% to {}%)", name, range.min, range.max),
+                format!("swap them: min: {}, max: {}", range.max, range.min),
+            );
+        }
+        let (low, high) = bounds.get(loan_type);
+        for (field, rate) in [("min", range.min), ("max", range.max)] {
+            if rate < low || rate > high {
+                let suggestion = if rate > high && rate / 10.0 >= low && rate / 10.0 <= high {
+                    format!("did you mean {}?", rate / 10.0)
+                } else {
+                    format!("check the rate, or widen market_bounds.{} if it is intended", name)
+                };
+                add(
+                    format!(
+                        "{}_loan_range {} of {}% is outside the usual {}%-{}% for {} loans",
+                        name, field, rate, low, high, name
+                    ),
+                    suggestion,
+                );
+            }
+        }
+        if let Some(amount) = range.closing_costs.filter(|amount| *amount < 0.0) {
+            add(
+                format!("{}_loan_range closing_costs of {} is negative", name, amount),
+                "record lender credits as negative points in points_pricing instead".to_string(),
+            );
+        }
+        if range.max_term_months == Some(0) {
+            add(
+                format!("{}_loan_range allows no term at all (max_term_months: 0)", name),
+                format!(
+                    "set max_term_months to the longest term offered, e.g. {}",
+                    loan_type.get_max_term()
+                ),
+            );
+        }
+    }
+
+    if bank.min_credit_score > 850 {
+        add(
+            format!("min_credit_score of {} is above the highest possible score", bank.min_credit_score),
+            "no one can qualify; use a score between 300 and 850".to_string(),
+        );
+    } else if bank.min_credit_score < 300 {
+        add(
+            format!("min_credit_score of {} is below the lowest possible score", bank.min_credit_score),
+            "use 300 to accept every score".to_string(),
+        );
+    }
+    if let Some(max_ltv) = bank.max_ltv.filter(|ltv| *ltv <= 0.0) {
+        add(
+            format!("max_ltv of {}% rejects every secured loan", max_ltv),
+            "remove max_ltv, or set the highest loan-to-value accepted, e.g. 80".to_string(),
+        );
+    }
+    if bank.closing_costs < 0.0 {
+        add(
+            format!("closing_costs of {} is negative", bank.closing_costs),
+            "record lender credits as negative points in points_pricing instead".to_string(),
+        );
+    }
+    if bank.max_back_end_dti < bank.max_front_end_dti {
+        add(
+            format!(
+                "max_back_end_dti of {}% is below max_front_end_dti of {}%",
+                bank.max_back_end_dti, bank.max_front_end_dti
+            ),
+            "the back-end limit counts housing plus other debts, so it should be the higher one".to_string(),
+        );
+    }
+    if !bank.points_pricing.is_empty() && bank.points_pricing.iter().all(|option| option.points != 0.0) {
+        add(
+            "points_pricing has no 0-point row for the par rate".to_string(),
+            "add `- points: 0` with `rate_change: 0`".to_string(),
+        );
+    }
+    if let Some(tiers) = &bank.credit_tiers {
+        lint_credit_tiers(&bank.name, tiers, findings);
+    }
+}
+
+fn lint(config: &BanksConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if config.banks.is_empty() {
+        findings.push(Finding {
+            subject: "banks".to_string(),
+            problem: "no banks are configured".to_string(),
+            suggestion: "add at least one bank".to_string(),
+        });
+    }
+
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for bank in &config.banks {
+        let key = bank.name.trim().to_lowercase();
+        if let Some(first) = seen.insert(key, &bank.name) {
+            findings.push(Finding {
+                subject: bank.name.clone(),
+                problem: format!("duplicates the bank name '{}'", first),
+                suggestion: "merge the entries or give each a distinct name".to_string(),
+            });
+        }
+        lint_bank(bank, &config.market_bounds, &mut findings);
+    }
+
+    if let Some(tiers) = &config.credit_tiers {
+        lint_credit_tiers("credit_tiers", tiers, &mut findings);
+    }
+
+    for loan_type in &LOAN_TYPES {
+        if !config.banks.iter().any(|bank| offers(rate_range(bank, loan_type))) {
+            findings.push(Finding {
+                subject: format!("{} loans", loan_type.get_name()),
+                problem: "no bank offers this loan type (every range is 0%-0%)".to_string(),
+                suggestion: format!("add a {}_loan_range to at least one bank", loan_type.get_name()),
+            });
+        }
+    }
+
+    match config.rates_as_of {
+        None => findings.push(Finding {
+            subject: "rates_as_of".to_string(),
+            problem: "the rate data is undated".to_string(),
+            suggestion: "add `rates_as_of: YYYY-MM-DD` so quotes can be traced".to_string(),
+        }),
+        Some(date) => {
+            let age = (chrono::Local::now().date_naive() - date).num_days();
+            if age > STALE_AFTER_DAYS {
+                findings.push(Finding {
+                    subject: "rates_as_of".to_string(),
+                    problem: format!("the rates were last checked {} days ago ({})", age, date),
+                    suggestion: "refresh the ranges against current lender rates".to_string(),
+                });
+            }
+        }
+    }
+    findings
+}
+
+// Reads the config, exiting with the error when it does not parse or load
+pub fn load(source: &ConfigSource) -> Result<BanksConfig, Box<dyn std::error::Error>> {
+    let path = source.get_description();
+    let (format, config_str) = match source {
+        ConfigSource::File(file) => {
+            let format = ConfigFormat::from_path(file)
+                .ok_or_else(|| format!("{}: expected a .yaml, .yml, .toml or .json bank config", path))?;
+            (format, fs::read_to_string(file)?)
+        }
+        ConfigSource::Builtin => (ConfigFormat::Yaml, config::DEFAULT_CONFIG.to_string()),
+    };
+    let config: BanksConfig = match config::parse_config(&config_str, format, &path) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("{} {}", "error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = LoanCalculator::load(source) {
+        println!("{} {}", "error:".red().bold(), e);
+        std::process::exit(1);
+    }
+    Ok(config)
+}
+
+// Prints what lint finds, exiting non-zero if it finds anything
+pub fn report(config: &BanksConfig, path: &str) {
+    let findings = lint(config);
+    if findings.is_empty() {
+        println!("No problems found in {}", path);
+        return;
+    }
+    for finding in &findings {
+        println!("{} {}: {}", "warning:".yellow().bold(), finding.subject, finding.problem);
+        println!("  {} {}", "suggestion:".cyan(), finding.suggestion);
+    }
+    println!("\n{} problem(s) found in {}", findings.len(), path);
+    std::process::exit(1);
+}
+
+// `config lint [path]` checks banks.yaml for entries that load but look wrong.
+// Hard errors, such as YAML that does not parse, are reported first.
+pub fn run(source: &ConfigSource) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load(source)?;
+    report(&config, &source.get_description());
+    Ok(())
+}
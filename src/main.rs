@@ -1,356 +1,5677 @@
-use dialoguer::{Select, Input};
+use clap::Parser;
+use colored::Colorize;
+use dialoguer::{Select, Input, MultiSelect};
+use prettytable::format::FormatBuilder;
 use prettytable::{Table, row};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use rust_decimal::prelude::*;
-use serde::Deserialize;
 use std::fs;
+use std::io::{IsTerminal, Read};
+use std::sync::OnceLock;
 
-#[derive(Debug, Clone)]
-enum LoanType {
-    Home,
-    Car,
-    Personal,
+mod banks;
+mod batch;
+mod cli;
+mod debts;
+mod i18n;
+mod lint;
+mod packs;
+mod rent;
+#[cfg(feature = "serve")]
+mod serve;
+
+use i18n::{format_months, format_rate, tr, trf};
+
+use loancalc::dates::{Calendar, Closing, DateFormat, PaymentDay, StubInterest};
+use loancalc::depreciation::{DepreciationCurve, EquityTimeline};
+use loancalc::arm::{ArmScenario, ArmTerms};
+use loancalc::collateral::{self, Collateral};
+use loancalc::combined::CombinedSchedule;
+use loancalc::config::{self, ConfigSource};
+use loancalc::conventions::{Compounding, InterestConvention};
+use loancalc::dti::{Dti, Income};
+use loancalc::escrow::HousingCosts;
+use loancalc::history::{self, HistoryEntry};
+use loancalc::frequency::{PaymentFrequency, FREQUENCIES};
+use loancalc::interest::{InterestMethod, LoanCost};
+use loancalc::lease::{Lease, Mileage};
+use loancalc::money::{self, Currency, MoneyFormat};
+use loancalc::payoff::PayoffProjection;
+use loancalc::rates::MarketRate;
+use loancalc::tax::{self, TaxSavings};
+use loancalc::vehicle::VehicleDeal;
+use loancalc::provenance::Provenance;
+use loancalc::purchase::{DownPayment, Purchase};
+use loancalc::quotes::QuoteRequest;
+use loancalc::ranking::{RankingConfig, RankingWeights, OBJECTIVES};
+use loancalc::refinance::{self, ExistingLoan};
+use loancalc::scenarios::{Scenario, ScenarioPrepayment, ScenarioStore};
+use loancalc::schedule::{PaymentStrategy, Prepayment, Schedule};
+use loancalc::student::{Deferment, DEFAULT_GRACE_MONTHS};
+use loancalc::report::{self, ReportFormat};
+use loancalc::{affordability, application, results, spreadsheet};
+#[cfg(feature = "chart")]
+use loancalc::chart;
+#[cfg(feature = "arrow")]
+use loancalc::columnar;
+use loancalc::{format_money, LoanCalculator, LoanType, RateDisplay, RateQuotes};
+
+static DATE_FORMAT: OnceLock<DateFormat> = OnceLock::new();
+
+fn format_date(date: chrono::NaiveDate) -> String {
+    DATE_FORMAT.get().copied().unwrap_or(DateFormat::Iso).format(date)
 }
 
-impl LoanType {
-    fn get_default_amount(&self) -> f64 {
-        match self {
-            LoanType::Home => 300_000.0,
-            LoanType::Car => 25_000.0,
-            LoanType::Personal => 10_000.0,
+
+// Terms read as years unless marked in months: "30", "30y" and "66m"
+fn parse_term(input: &str) -> Option<u32> {
+    let input = input.trim().to_lowercase();
+    if let Some(months) = input.strip_suffix('m') {
+        months.trim().parse().ok()
+    } else {
+        let years: u32 = input.strip_suffix('y').unwrap_or(&input).trim().parse().ok()?;
+        years.checked_mul(12)
+    }
+}
+
+fn format_term_input(months: u32) -> String {
+    match months % 12 {
+        0 => format!("{}y", months / 12),
+        _ => format!("{}m", months),
+    }
+}
+
+// Fits a table to the terminal: the full grid when it fits, then a
+// borderless grid, then one block per row. Output that is not going to a
+// terminal always gets the full grid.
+fn print_table(table: &Table) {
+    let Some((rows, columns)) = console::Term::stdout().size_checked().filter(|(_, columns)| *columns > 0)
+    else {
+        table.printstd();
+        return;
+    };
+    let width = columns as usize;
+
+    let text = if table_width(table) <= width {
+        table.to_string()
+    } else {
+        let mut compact = table.clone();
+        compact.set_format(FormatBuilder::new().padding(0, 2).build());
+        if table_width(&compact) <= width {
+            compact.to_string()
+        } else {
+            stacked_table(table)
+        }
+    };
+
+    let paged = text.lines().count() >= rows as usize
+        && USE_PAGER.get().copied().unwrap_or(false)
+        && page(&text).is_ok();
+    if !paged {
+        print!("{}", text);
+    }
+}
+
+// One block per row, labeled by the header row
+fn stacked_table(table: &Table) -> String {
+    let mut rows = table.row_iter();
+    let Some(header) = rows.next() else {
+        return String::new();
+    };
+    let labels: Vec<String> = header.iter().map(|cell| cell.get_content()).collect();
+    let mut text = String::new();
+    for row in rows {
+        let mut cells = row.iter().map(|cell| cell.get_content());
+        text.push_str(&format!("{}\n", cells.next().unwrap_or_default()));
+        for (label, value) in labels.iter().skip(1).zip(cells) {
+            text.push_str(&format!("  {}: {}\n", label, value));
         }
     }
+    text
+}
 
-    fn get_max_amount(&self) -> f64 {
-        match self {
-            LoanType::Home => 10_000_000.0,
-            LoanType::Car => 150_000.0,
-            LoanType::Personal => 100_000.0,
+static USE_PAGER: OnceLock<bool> = OnceLock::new();
+
+// Shows text through $PAGER, or less, and waits for the user to close it
+fn page(text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().ok_or("PAGER is empty")?;
+    let mut child = std::process::Command::new(program)
+        .args(parts)
+        .env("LESS", std::env::var("LESS").unwrap_or_else(|_| "FRX".to_string()))
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        // The user may quit before reading everything
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+fn table_width(table: &Table) -> usize {
+    table
+        .to_string()
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Rate,
+    Apr,
+    Payment,
+    TotalInterest,
+    TotalPayment,
+    Fees,
+    Rating,
+    Balance,
+}
+
+const ALL_COLUMNS: [Column; 8] = [
+    Column::Rate,
+    Column::Apr,
+    Column::Payment,
+    Column::TotalInterest,
+    Column::TotalPayment,
+    Column::Fees,
+    Column::Rating,
+    Column::Balance,
+];
+
+const DEFAULT_COLUMNS: [Column; 5] = [
+    Column::Rate,
+    Column::Payment,
+    Column::TotalInterest,
+    Column::TotalPayment,
+    Column::Balance,
+];
+
+impl Column {
+    fn from_name(name: &str) -> Option<Column> {
+        match name.trim() {
+            "rate" => Some(Column::Rate),
+            "apr" => Some(Column::Apr),
+            "payment" => Some(Column::Payment),
+            "total_interest" => Some(Column::TotalInterest),
+            "total_payment" | "total_cost" => Some(Column::TotalPayment),
+            "fees" => Some(Column::Fees),
+            "rating" => Some(Column::Rating),
+            "balance" => Some(Column::Balance),
+            _ => None,
         }
     }
 
     fn get_description(&self) -> &str {
         match self {
-            LoanType::Home => "Home loans typically range from $100,000 to $10,000,000",
-            LoanType::Car => "Car loans typically range from $5,000 to $150,000",
-            LoanType::Personal => "Personal loans typically range from $1,000 to $100,000",
+            Column::Rate => tr("Interest Rate"),
+            Column::Apr => tr("APR"),
+            Column::Payment => tr("Monthly Payment"),
+            Column::TotalInterest => tr("Total Interest"),
+            Column::TotalPayment => tr("Total Payment"),
+            Column::Fees => tr("Fees & Points"),
+            Column::Rating => tr("Rating"),
+            Column::Balance => tr("Balance"),
         }
     }
+}
 
-    fn get_default_term(&self) -> u32 {
-        match self {
-            LoanType::Home => 30,
-            LoanType::Car => 5,
-            LoanType::Personal => 3,
+// Columns the comparison gains from choices made outside the column picker
+struct TableExtras<'a> {
+    prepayments: &'a [Prepayment],
+    // A home purchase that may need mortgage insurance
+    pmi_purchase: Option<&'a Purchase>,
+    housing: Option<&'a HousingCosts>,
+    income: Option<&'a Income>,
+    loan_type: &'a LoanType,
+}
+
+impl TableExtras<'_> {
+    // PMI is figured from the amortizing balance, so other methods go without
+    fn pmi(
+        &self,
+        calculator: &LoanCalculator,
+        interest_method: &InterestMethod,
+        bank_name: &str,
+        rate: Decimal,
+        loan_term: u32,
+    ) -> Option<loancalc::pmi::Pmi> {
+        if *interest_method != InterestMethod::Amortizing {
+            return None;
         }
+        calculator.calculate_pmi(bank_name, self.pmi_purchase?, rate, loan_term)
+    }
+
+    // Everything the loan adds each month: the payment, plus PMI, escrow and
+    // HOA dues on a home
+    fn monthly_cost(
+        &self,
+        calculator: &LoanCalculator,
+        interest_method: &InterestMethod,
+        bank_name: &str,
+        rate: Decimal,
+        loan_term: u32,
+        payment: Decimal,
+    ) -> Decimal {
+        let pmi_monthly = self
+            .pmi(calculator, interest_method, bank_name, rate, loan_term)
+            .map(|pmi| pmi.monthly)
+            .unwrap_or_default();
+        payment + pmi_monthly + self.housing.map(HousingCosts::monthly_total).unwrap_or_default()
+    }
+
+    fn dti(
+        &self,
+        calculator: &LoanCalculator,
+        interest_method: &InterestMethod,
+        bank_name: &str,
+        rate: Decimal,
+        loan_term: u32,
+        payment: Decimal,
+    ) -> Option<Dti> {
+        let new_payment = self.monthly_cost(calculator, interest_method, bank_name, rate, loan_term, payment);
+        // Home loans count toward the front-end DTI as well as the back-end
+        let housing_loan = matches!(self.loan_type, LoanType::Home);
+        calculator.check_dti(bank_name, self.income?, new_payment, housing_loan)
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct RateRange {
-    min: f64,
-    max: f64,
+// The order the comparison lists offers in and the limits that leave some
+// out, from --sort, --max-payment, --min-rate and --max-rate or the menu
+#[derive(Debug, Clone, Default)]
+struct OfferView {
+    sort: Option<cli::SortBy>,
+    max_payment: Option<Decimal>,
+    min_rate: Option<Decimal>,
+    max_rate: Option<Decimal>,
 }
 
-impl RateRange {
-    fn to_decimal_tuple(&self) -> (Decimal, Decimal) {
-        (
-            Decimal::from_f64(self.min).unwrap(),
-            Decimal::from_f64(self.max).unwrap(),
-        )
+impl OfferView {
+    fn from_cli(cli: &cli::Cli) -> Result<Self, Box<dyn std::error::Error>> {
+        let limit = |value: Option<f64>, flag: &str| -> Result<Option<Decimal>, Box<dyn std::error::Error>> {
+            match value {
+                Some(value) if value > 0.0 => Ok(Decimal::from_f64(value)),
+                Some(_) => Err(format!("{} must be above zero", flag).into()),
+                None => Ok(None),
+            }
+        };
+        let view = OfferView {
+            sort: cli.sort,
+            max_payment: limit(cli.max_payment, "--max-payment")?,
+            min_rate: limit(cli.min_rate, "--min-rate")?,
+            max_rate: limit(cli.max_rate, "--max-rate")?,
+        };
+        if let (Some(min), Some(max)) = (view.min_rate, view.max_rate) {
+            if min > max {
+                return Err(format!("--min-rate {}% is above --max-rate {}%", min, max).into());
+            }
+        }
+        Ok(view)
     }
+
+    // Returns the offers to show, adding those left out to `hidden` with the
+    // reason. Sorting keeps the config order between equal offers and puts
+    // any that cannot be priced last.
+    fn apply(
+        &self,
+        calculator: &LoanCalculator,
+        interest_method: &InterestMethod,
+        offers: &[(String, Decimal)],
+        loan_amount: Decimal,
+        loan_term: u32,
+        hidden: &mut Vec<(String, String)>,
+    ) -> Vec<(String, Decimal)> {
+        let mut shown = Vec::new();
+        for (name, rate) in offers {
+            let cost = calculator.calculate_cost(interest_method, loan_amount, *rate, loan_term);
+            let reason = if let Some(min) = self.min_rate.filter(|min| rate < min) {
+                Some(trf(
+                    "is priced at {}%, below the {}% minimum",
+                    &[&format!("{:.2}", rate.round_dp(2)), &format!("{:.2}", min.round_dp(2))],
+                ))
+            } else if let Some(max) = self.max_rate.filter(|max| rate > max) {
+                Some(trf(
+                    "is priced at {}%, above the {}% maximum",
+                    &[&format!("{:.2}", rate.round_dp(2)), &format!("{:.2}", max.round_dp(2))],
+                ))
+            } else {
+                match (self.max_payment, cost) {
+                    (Some(ceiling), Some(cost)) if cost.monthly_payment > ceiling => Some(trf(
+                        "has a monthly payment of {}, above the {} ceiling",
+                        &[&format_money(cost.monthly_payment), &format_money(ceiling)],
+                    )),
+                    _ => None,
+                }
+            };
+            match reason {
+                Some(reason) => hidden.push((name.clone(), reason)),
+                None => shown.push((name.clone(), *rate)),
+            }
+        }
+
+        if let Some(sort) = self.sort {
+            shown.sort_by_cached_key(|(_, rate)| {
+                let cost = calculator.calculate_cost(interest_method, loan_amount, *rate, loan_term);
+                let key = match sort {
+                    cli::SortBy::Payment => cost.map(|cost| cost.monthly_payment),
+                    cli::SortBy::Interest => cost.map(|cost| cost.total_interest),
+                    cli::SortBy::Rate => Some(*rate),
+                };
+                (key.is_none(), key)
+            });
+        }
+        shown
+    }
+}
+
+fn optional_limit(prompt: &str, current: Option<Decimal>) -> Result<Option<Decimal>, Box<dyn std::error::Error>> {
+    let input: String = Input::new()
+        .with_prompt(trf("{} (blank for none)", &[&prompt]))
+        .with_initial_text(current.map(|value| value.to_string()).unwrap_or_default())
+        .allow_empty(true)
+        .validate_with(|input: &String| {
+            let input = input.trim().trim_start_matches('$').trim_end_matches('%').replace(',', "");
+            if input.is_empty() || input.parse::<Decimal>().is_ok_and(|value| value > Decimal::ZERO) {
+                Ok(())
+            } else {
+                Err(tr("Enter an amount above zero, or leave it blank"))
+            }
+        })
+        .interact_text()?;
+    let input = input.trim().trim_start_matches('$').trim_end_matches('%').replace(',', "");
+    Ok(input.parse::<Decimal>().ok())
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct BankConfig {
-    name: String,
-    home_loan_range: RateRange,
-    car_loan_range: RateRange,
-    personal_loan_range: RateRange,
-    min_credit_score: u16,
+fn pick_offer_view(current: &OfferView) -> Result<OfferView, Box<dyn std::error::Error>> {
+    let sorts = [
+        (None, "Config order"),
+        (Some(cli::SortBy::Payment), "Monthly payment, lowest first"),
+        (Some(cli::SortBy::Interest), "Total interest, lowest first"),
+        (Some(cli::SortBy::Rate), "Interest rate, lowest first"),
+    ];
+    let labels: Vec<&str> = sorts.iter().map(|(_, label)| tr(label)).collect();
+    let selection = Select::new()
+        .with_prompt(tr("Sort the comparison by"))
+        .items(&labels)
+        .default(sorts.iter().position(|(sort, _)| *sort == current.sort).unwrap_or(0))
+        .interact()?;
+    Ok(OfferView {
+        sort: sorts[selection].0,
+        max_payment: optional_limit(tr("Highest monthly payment to show"), current.max_payment)?,
+        min_rate: optional_limit(tr("Lowest interest rate to show (%)"), current.min_rate)?,
+        max_rate: optional_limit(tr("Highest interest rate to show (%)"), current.max_rate)?,
+    })
 }
 
-#[derive(Debug, Clone)]
-struct Bank {
-    name: String,
-    home_loan_range: (Decimal, Decimal),
-    car_loan_range: (Decimal, Decimal),
-    personal_loan_range: (Decimal, Decimal),
-    min_credit_score: u16,
+fn print_hidden_offers(hidden: &[(String, String)]) {
+    for (name, reason) in hidden {
+        println!("{} {} {}", tr("Not shown:").cyan().bold(), name, reason);
+    }
 }
 
-impl From<BankConfig> for Bank {
-    fn from(config: BankConfig) -> Self {
-        Bank {
-            name: config.name,
-            home_loan_range: config.home_loan_range.to_decimal_tuple(),
-            car_loan_range: config.car_loan_range.to_decimal_tuple(),
-            personal_loan_range: config.personal_loan_range.to_decimal_tuple(),
-            min_credit_score: config.min_credit_score,
+// Rates compound monthly inside the calculator; a bank that quotes
+// semi-annually is shown at the rate it quotes
+fn format_offer_rate(calculator: &LoanCalculator, name: &str, rate: Decimal, apr: Option<Decimal>) -> String {
+    match calculator.compounding(name) {
+        Compounding::Monthly => format_rate(rate, apr),
+        compounding => {
+            let quoted = format!(
+                "{:.2}% {}",
+                calculator.quoted_rate(name, rate).round_dp(2),
+                compounding.get_description()
+            );
+            match apr {
+                Some(apr) => trf("{} ({}% APR)", &[&quoted, &format!("{:.2}", apr.round_dp(2))]),
+                None => quoted,
+            }
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct BanksConfig {
-    banks: Vec<BankConfig>,
-}
+fn comparison_table(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    columns: &[Column],
+    extras: &TableExtras,
+) -> Table {
+    let prepayments = extras.prepayments;
+    let pmi_purchase = extras
+        .pmi_purchase
+        .filter(|purchase| *interest_method == InterestMethod::Amortizing && purchase.requires_pmi());
+
+    let mut table = Table::new();
+    let mut header = vec![tr("Bank")];
+    header.extend(columns.iter().map(Column::get_description));
+    if !prepayments.is_empty() {
+        header.extend([tr("Interest With Prepayment"), tr("Payoff With Prepayment")]);
+    }
+    if pmi_purchase.is_some() {
+        header.extend([tr("Payment With PMI"), tr("PMI Lasts"), tr("Total PMI")]);
+    }
+    if extras.housing.is_some() {
+        header.push(tr("Monthly PITI"));
+    }
+    if extras.income.is_some() {
+        header.extend([tr("DTI (Front / Back)"), tr("Within DTI Limits")]);
+    }
+    table.add_row(header.into());
 
-impl Bank {
-    fn get_rate_range(&self, loan_type: &LoanType) -> (Decimal, Decimal) {
-        match loan_type {
-            LoanType::Home => self.home_loan_range,
-            LoanType::Car => self.car_loan_range,
-            LoanType::Personal => self.personal_loan_range,
+    for (name, rate) in offers {
+        let Some(cost) = calculator.calculate_cost(interest_method, loan_amount, *rate, loan_term) else {
+            continue;
+        };
+        let apr = calculator.calculate_apr(interest_method, name, extras.loan_type, loan_amount, *rate, loan_term);
+        let mut cells = vec![name.clone()];
+        cells.extend(columns.iter().map(|column| match column {
+            // The quoted rate carries the APR alongside it unless APR has its own column
+            Column::Rate if columns.contains(&Column::Apr) => format_offer_rate(calculator, name, *rate, None),
+            Column::Rate => format_offer_rate(calculator, name, *rate, apr),
+            Column::Apr => format!("{:.2}%", apr.unwrap_or(*rate).round_dp(2)),
+            Column::Payment => format_money(cost.monthly_payment),
+            Column::TotalInterest => format_money(cost.total_interest),
+            Column::TotalPayment => format_money(cost.total_payment),
+            Column::Fees => format_money(calculator.prepaid_finance_charges(name, extras.loan_type, loan_amount)),
+            Column::Rating => calculator
+                .get_rating(name)
+                .map(|rating| format!("{:.1}/5", rating.round_dp(1)))
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Balance => balance_sparkline(calculator, interest_method, loan_amount, *rate, loan_term),
+        }));
+        // Prepayments only shorten an amortizing balance
+        if !prepayments.is_empty() {
+            if *interest_method == InterestMethod::Amortizing {
+                let schedule = calculator.generate_prepaid_schedule(loan_amount, *rate, loan_term, prepayments);
+                cells.push(format_money(schedule.total_interest()));
+                cells.push(format_months(schedule.num_payments()));
+            } else {
+                cells.extend(["-".to_string(), "-".to_string()]);
+            }
+        }
+        let pmi = extras.pmi(calculator, interest_method, name, *rate, loan_term);
+        if pmi_purchase.is_some() {
+            match &pmi {
+                Some(pmi) => {
+                    cells.push(format_money(cost.monthly_payment + pmi.monthly));
+                    cells.push(format_months(pmi.last_month));
+                    cells.push(format_money(pmi.total));
+                }
+                None => cells.extend(["-".to_string(), "-".to_string(), "-".to_string()]),
+            }
+        }
+        if let Some(housing) = extras.housing {
+            let pmi_monthly = pmi.map(|pmi| pmi.monthly).unwrap_or_default();
+            cells.push(format_money(cost.monthly_payment + pmi_monthly + housing.monthly_total()));
+        }
+        if extras.income.is_some() {
+            match extras.dti(calculator, interest_method, name, *rate, loan_term, cost.monthly_payment) {
+                Some(dti) => {
+                    let front_end = dti
+                        .front_end
+                        .map(|front_end| format!("{:.1}%", front_end.round_dp(1)))
+                        .unwrap_or_else(|| "-".to_string());
+                    cells.push(format!("{} / {:.1}%", front_end, dti.back_end.round_dp(1)));
+                    cells.push(tr(if dti.fits() { "Yes" } else { "No" }).to_string());
+                }
+                None => cells.extend(["-".to_string(), "-".to_string()]),
+            }
         }
+        table.add_row(cells.into());
     }
+    table
 }
 
-struct LoanCalculator {
-    banks: Vec<Bank>,
+// What owning the home costs each month and over the term, with the loan
+// itself kept apart from escrow and dues
+fn print_housing_costs(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    extras: &TableExtras,
+) {
+    let Some(housing) = extras.housing else {
+        return;
+    };
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Principal & Interest"),
+        tr("PMI"),
+        tr("Taxes & Insurance"),
+        tr("HOA"),
+        tr("Monthly Total"),
+        tr("Loan Cost"),
+        tr("Escrow & HOA")
+    ]);
+    for (name, rate) in offers {
+        let Some(cost) = calculator.calculate_cost(interest_method, loan_amount, *rate, loan_term) else {
+            continue;
+        };
+        let pmi = extras.pmi(calculator, interest_method, name, *rate, loan_term);
+        let (pmi_monthly, pmi_total) = pmi.map(|pmi| (pmi.monthly, pmi.total)).unwrap_or_default();
+        table.add_row(row![
+            name,
+            format_money(cost.monthly_payment),
+            format_money(pmi_monthly),
+            format_money(housing.monthly_escrow()),
+            format_money(housing.hoa_dues),
+            format_money(cost.monthly_payment + pmi_monthly + housing.monthly_total()),
+            format_money(cost.total_payment + pmi_total),
+            format_money(housing.total(loan_term))
+        ]);
+    }
+
+    println!("\n{}", tr("Full Monthly Cost (PITI and HOA):"));
+    print_table(&table);
 }
 
-impl LoanCalculator {
-    fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let config_str = fs::read_to_string("banks.yaml")?;
-        let config: BanksConfig = serde_yaml::from_str(&config_str)?;
-        let banks = config.banks.into_iter().map(Bank::from).collect();
-        Ok(Self { banks })
+// Interest accrued before repayment is capitalized, so repayment amortizes
+// a larger principal than was borrowed
+fn print_student_repayment(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    deferment: &Deferment,
+    calendar: &Calendar,
+) {
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Accrued Interest"),
+        tr("Principal at Repayment"),
+        tr("Monthly Payment"),
+        tr("Without Capitalization"),
+        tr("Total Interest")
+    ]);
+    for (name, rate) in offers {
+        let Some(repayment) =
+            calculator.calculate_student_repayment(interest_method, loan_amount, *rate, loan_term, deferment)
+        else {
+            continue;
+        };
+        table.add_row(row![
+            name,
+            format_money(repayment.accrued_interest),
+            format_money(repayment.capitalized_principal),
+            format_money(repayment.monthly_payment),
+            format_money(repayment.uncapitalized_payment),
+            format_money(repayment.total_interest)
+        ]);
     }
 
-    fn decimal_pow(&self, base: Decimal, exp: u32) -> Decimal {
-        let mut result = dec!(1);
-        let mut base = base;
-        let mut exp = exp;
+    println!(
+        "\n{}",
+        trf(
+            "Deferred {} in school and {} of grace, repayment from about {}:",
+            &[
+                &format_months(deferment.in_school_months),
+                &format_months(deferment.grace_months),
+                &format_date(calendar.payment_date(deferment.months() + 1)),
+            ],
+        )
+    );
+    print_table(&table);
+}
 
-        while exp > 0 {
-            if exp & 1 == 1 {
-                result *= base;
-            }
-            base *= base;
-            exp >>= 1;
+// Each offer's payoff on the calendar, and the interest due in each calendar
+// year side by side, as a tax year counts it
+fn print_payoff_projection(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    extras: &TableExtras,
+    calendar: &Calendar,
+) {
+    if *interest_method != InterestMethod::Amortizing || offers.is_empty() {
+        return;
+    }
+    let projections: Vec<(&str, PayoffProjection)> = offers
+        .iter()
+        .map(|(name, rate)| {
+            let pmi = extras.pmi(calculator, interest_method, name, *rate, loan_term);
+            (
+                name.as_str(),
+                calculator.project_payoff(loan_amount, *rate, loan_term, calendar, pmi.as_ref()),
+            )
+        })
+        .collect();
+
+    let mut table = Table::new();
+    let mut header = vec![tr("Bank"), tr("Payoff Date")];
+    if extras.pmi_purchase.is_some() {
+        header.push(tr("PMI Drops"));
+    }
+    table.add_row(header.into());
+    for (name, projection) in &projections {
+        let mut cells = vec![name.to_string(), format_date(projection.payoff_date)];
+        if extras.pmi_purchase.is_some() {
+            // PMI that never drops is paid until the loan is
+            cells.push(projection.pmi_drops.map(format_date).unwrap_or_else(|| tr("At payoff").to_string()));
         }
+        table.add_row(cells.into());
+    }
+    println!(
+        "\n{}",
+        trf("Payoff Projection (first payment due {}):", &[&format_date(calendar.payment_date(1))])
+    );
+    print_table(&table);
 
-        result
+    let mut years = Table::new();
+    let mut header = vec![tr("Year").to_string()];
+    header.extend(projections.iter().map(|(name, _)| name.to_string()));
+    years.add_row(header.into());
+    for (year, _) in &projections[0].1.interest_by_year {
+        let mut cells = vec![year.to_string()];
+        cells.extend(projections.iter().map(|(_, projection)| {
+            projection
+                .interest_by_year
+                .iter()
+                .find(|(offer_year, _)| offer_year == year)
+                .map(|(_, interest)| format_money(*interest))
+                .unwrap_or_else(|| "-".to_string())
+        }));
+        years.add_row(cells.into());
     }
+    println!("\n{}", tr("Interest by Calendar Year:"));
+    print_table(&years);
+}
 
-    fn calculate_monthly_payment(&self, principal: Decimal, annual_rate: Decimal, years: u32) -> Decimal {
-        let monthly_rate = annual_rate / dec!(100) / dec!(12);
-        let num_payments = years * 12;
-        
-        let base = dec!(1) + monthly_rate;
-        let base_raised = self.decimal_pow(base, num_payments);
-        
-        if base_raised == dec!(1) {
-            return principal / Decimal::from(num_payments);
-        }
-        
-        let numerator = monthly_rate * base_raised;
-        let denominator = base_raised - dec!(1);
-        
-        principal * (numerator / denominator)
+// What each mortgage costs after the interest deduction, for a borrower who
+// itemizes, and the interest each year's Form 1098 should show for the
+// cheapest of them
+fn print_tax_savings(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+    tax_rate: Decimal,
+) {
+    if *interest_method != InterestMethod::Amortizing {
+        return;
     }
+    let savings: Vec<(&str, PayoffProjection, TaxSavings)> = offers
+        .iter()
+        .map(|(name, rate)| {
+            let projection = calculator.project_payoff(loan_amount, *rate, loan_term, calendar, None);
+            let savings = tax::tax_savings(loan_amount, *rate, &projection, tax_rate);
+            (name.as_str(), projection, savings)
+        })
+        .collect();
+    let Some((cheapest, projection, best)) = savings.iter().min_by_key(|(_, _, savings)| savings.after_tax_rate)
+    else {
+        return;
+    };
 
-    fn adjust_rate_for_credit(&self, base_rate: Decimal, credit_score: u16) -> Decimal {
-        match credit_score {
-            score if score >= 800 => base_rate - dec!(0.5),
-            score if score >= 750 => base_rate - dec!(0.25),
-            score if score >= 700 => base_rate,
-            score if score >= 650 => base_rate + dec!(0.5),
-            score if score >= 600 => base_rate + dec!(1.0),
-            _ => base_rate + dec!(2.0),
-        }
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Deductible Interest"),
+        tr("Tax Saved"),
+        tr("After-Tax Interest"),
+        tr("After-Tax Rate")
+    ]);
+    for (name, _, savings) in &savings {
+        table.add_row(row![
+            name,
+            format_money(savings.deductible_interest),
+            format_money(savings.tax_saved),
+            format_money(savings.after_tax_interest),
+            format!("{:.2}%", savings.after_tax_rate.round_dp(2))
+        ]);
+    }
+    println!(
+        "\n{}",
+        trf("Mortgage Interest Deduction at a {}% marginal rate (if you itemize):", &[&tax_rate])
+    );
+    print_table(&table);
+    if loan_amount > tax::DEDUCTIBLE_DEBT_LIMIT {
+        println!(
+            "{}",
+            trf(
+                "Only the interest on {} of the {} borrowed is deductible.",
+                &[&format_money(tax::DEDUCTIBLE_DEBT_LIMIT), &format_money(loan_amount)],
+            )
+        );
     }
+    println!(
+        "{}",
+        trf(
+            "Debt whose interest is not deductible costs less than {} only at rates below {}%.",
+            &[cheapest, &format!("{:.2}", best.after_tax_rate.round_dp(2))],
+        )
+    );
 
-    fn get_min_credit_score(&self) -> u16 {
-        self.banks.iter().map(|bank| bank.min_credit_score).min().unwrap_or(300)
+    let mut years = Table::new();
+    years.add_row(row![tr("Year"), tr("Interest"), tr("Tax Saved"), tr("After Tax")]);
+    for ((year, interest), (_, saved)) in projection.interest_by_year.iter().zip(&best.by_year) {
+        years.add_row(row![year, format_money(*interest), format_money(*saved), format_money(interest - saved)]);
     }
+    println!("\n{}", trf("Year-End Interest with {}:", &[cheapest]));
+    print_table(&years);
 }
 
-fn format_money(amount: Decimal) -> String {
-    let mut str_amount = format!("{:.2}", amount);
-    let decimal_pos = str_amount.find('.').unwrap_or(str_amount.len());
-    let mut pos = decimal_pos;
-    while pos > 3 {
-        pos -= 3;
-        str_amount.insert(pos, ',');
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const SPARKLINE_WIDTH: u32 = 12;
+
+// Samples the balance evenly across the term, scaled to the amount borrowed
+// so offers share one scale. Only amortizing loans have a schedule to sample.
+fn balance_sparkline(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    loan_amount: Decimal,
+    rate: Decimal,
+    loan_term: u32,
+) -> String {
+    if *interest_method != InterestMethod::Amortizing {
+        return "-".to_string();
     }
-    format!("${}", str_amount)
+    let schedule = calculator.generate_schedule(loan_amount, rate, loan_term, &PaymentStrategy::Standard);
+    (0..SPARKLINE_WIDTH)
+        .map(|index| {
+            let month = index * loan_term / (SPARKLINE_WIDTH - 1);
+            let balance = if month == 0 { loan_amount } else { schedule.balance_after(month) };
+            let level = (balance / loan_amount * dec!(7)).round().to_usize().unwrap_or(0);
+            SPARKLINE_BARS[level.min(7)]
+        })
+        .collect()
 }
 
-fn get_valid_credit_score() -> Result<u16, Box<dyn std::error::Error>> {
-    loop {
-        let score: u16 = Input::new()
-            .with_prompt("Enter your credit score (300-850)")
-            .validate_with(|input: &u16| {
-                if *input >= 300 && *input <= 850 {
-                    Ok(())
+// `RankingWeights::get_description` with the objectives translated
+fn describe_ranking(ranking: &RankingWeights) -> String {
+    ranking
+        .shares()
+        .iter()
+        .filter(|(_, share)| !share.is_zero())
+        .map(|(objective, share)| {
+            format!("{} {:.0}%", tr(objective.get_description()), (share * dec!(100)).round_dp(0))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn print_recommendation(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    loan_type: &LoanType,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) {
+    let ranked =
+        calculator.rank_offers(interest_method, loan_type, offers, loan_amount, loan_term, calculator.ranking());
+    let Some(best) = ranked.first() else {
+        return;
+    };
+    println!(
+        "\n{} {}",
+        tr("Recommended:").green().bold(),
+        trf("{} (weighing {})", &[&best.name, &describe_ranking(calculator.ranking())])
+    );
+}
+
+// One Truth in Lending box and payment schedule per offer
+fn disclosure_tables(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    loan_type: &LoanType,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) -> Vec<(String, Table, Table)> {
+    offers
+        .iter()
+        .filter_map(|(name, rate)| {
+            let disclosure = calculator.disclosure(interest_method, name, loan_type, loan_amount, *rate, loan_term)?;
+
+            let mut summary = Table::new();
+            summary.add_row(row![
+                tr("ANNUAL PERCENTAGE RATE"),
+                tr("FINANCE CHARGE"),
+                tr("Amount Financed"),
+                tr("Total of Payments")
+            ]);
+            summary.add_row(row![
+                tr("The cost of your credit\nas a yearly rate"),
+                tr("The dollar amount the\ncredit will cost you"),
+                tr("The amount of credit\nprovided to you or\non your behalf"),
+                tr("The amount you will have\npaid after making all\npayments as scheduled")
+            ]);
+            summary.add_row(row![
+                format!("{:.3}%", disclosure.apr.round_dp(3)),
+                format_money(disclosure.finance_charge),
+                format_money(disclosure.amount_financed),
+                format_money(disclosure.total_of_payments)
+            ]);
+
+            let mut schedule = Table::new();
+            schedule.add_row(row![tr("Number of Payments"), tr("Amount of Payments"), tr("When Payments Are Due")]);
+            for (count, amount, first) in &disclosure.payment_schedule {
+                let due = if *count == 1 {
+                    format_date(calendar.payment_date(*first))
                 } else {
-                    Err("Credit score must be between 300 and 850")
-                }
-            })
-            .interact_text()?;
-        return Ok(score);
+                    trf("Monthly beginning {}", &[&format_date(calendar.payment_date(*first))])
+                };
+                schedule.add_row(row![count, format_money(*amount), due]);
+            }
+
+            Some((name.clone(), summary, schedule))
+        })
+        .collect()
+}
+
+fn print_disclosures(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    loan_type: &LoanType,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) {
+    for (name, summary, schedule) in
+        disclosure_tables(calculator, interest_method, loan_type, offers, loan_amount, loan_term, calendar)
+    {
+        println!("\n{}", trf("Truth in Lending Disclosure: {}", &[&name]));
+        print_table(&summary);
+        println!("{}", tr("Your payment schedule will be:"));
+        print_table(&schedule);
     }
+    println!("{}", tr("Closing costs are counted as prepaid finance charges."));
 }
 
-fn get_valid_loan_amount(loan_type: &LoanType) -> Result<Decimal, Box<dyn std::error::Error>> {
-    println!("\n{}", loan_type.get_description());
-    loop {
-        let amount: f64 = Input::new()
-            .with_prompt("Enter loan amount ($)")
-            .with_initial_text(&format!("{}", loan_type.get_default_amount()))
-            .validate_with(move |input: &f64| -> Result<(), &str> {
-                if *input <= 0.0 {
-                    Err("Loan amount must be greater than 0")
-                } else if *input > loan_type.get_max_amount() {
-                    Err("Loan amount exceeds maximum allowed")
-                } else {
-                    Ok(())
-                }
-            })
-            .interact_text()?;
-        return Ok(Decimal::from_f64(amount).unwrap());
+fn write_disclosures(
+    disclosures: Vec<(String, Table, Table)>,
+    provenance: &Provenance,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut text = String::new();
+    for (name, summary, schedule) in disclosures {
+        text.push_str(&format!("{}\n{}", trf("Truth in Lending Disclosure: {}", &[&name]), summary));
+        text.push_str(&format!("{}\n{}\n", tr("Your payment schedule will be:"), schedule));
+    }
+    text.push_str(&format!(
+        "{}\n\n{}\n",
+        tr("Closing costs are counted as prepaid finance charges."),
+        tr("Provenance:")
+    ));
+    for (name, value) in provenance.entries() {
+        text.push_str(&format!("  {}: {}\n", name, value));
     }
+    fs::write(path, text)?;
+    Ok(())
 }
 
-fn get_valid_loan_term(loan_type: &LoanType) -> Result<u32, Box<dyn std::error::Error>> {
-    loop {
-        let term: u32 = Input::new()
-            .with_prompt("Enter loan term (1-30 years)")
-            .with_initial_text(&format!("{}", loan_type.get_default_term()))
-            .validate_with(|input: &u32| {
-                if *input >= 1 && *input <= 30 {
+fn print_offer_ranking(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    loan_type: &LoanType,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let current = calculator.ranking();
+    let total: Decimal = current.weights.iter().map(|(_, weight)| *weight).sum();
+    let mut weights = Vec::new();
+    for objective in OBJECTIVES {
+        let weight: f64 = Input::new()
+            .with_prompt(trf("Weight for {} (0-100)", &[&tr(objective.get_description()).to_lowercase()]))
+            .with_initial_text(format!("{}", (current.get(objective) / total * dec!(100)).round()))
+            .validate_with(|input: &f64| {
+                if *input >= 0.0 && *input <= 100.0 {
                     Ok(())
                 } else {
-                    Err("Loan term must be between 1 and 30 years")
+                    Err(tr("Weight must be between 0 and 100"))
                 }
             })
             .interact_text()?;
-        return Ok(term);
+        weights.push(weight);
+    }
+    let max_horizon = (loan_term / 12).max(1);
+    let horizon = get_years_input(
+        &trf("Measure total cost over how many years? (1-{})", &[&max_horizon]),
+        &format!("{}", current.horizon_years.unwrap_or(max_horizon).min(max_horizon)),
+        max_horizon,
+    )?;
+
+    let ranking = match RankingWeights::try_from(RankingConfig {
+        payment: weights[0],
+        total_cost: weights[1],
+        fees: weights[2],
+        flexibility: weights[3],
+        rating: weights[4],
+        horizon_years: Some(horizon),
+    }) {
+        Ok(ranking) => ranking,
+        Err(reason) => {
+            println!("\n{}", reason);
+            return Ok(());
+        }
+    };
+
+    let shares: Vec<_> = ranking.shares().into_iter().filter(|(_, share)| !share.is_zero()).collect();
+    let mut table = Table::new();
+    let mut header = vec![tr("Rank").to_string(), tr("Bank").to_string(), tr("Score").to_string()];
+    header.extend(
+        shares
+            .iter()
+            .map(|(objective, share)| {
+                format!("{} ({:.0}%)", tr(objective.get_description()), (share * dec!(100)).round_dp(0))
+            }),
+    );
+    table.add_row(header.into());
+    let ranked = calculator.rank_offers(interest_method, loan_type, offers, loan_amount, loan_term, &ranking);
+    for (index, offer) in ranked.iter().enumerate() {
+        let mut cells = vec![format!("{}", index + 1), offer.name.clone(), format!("{:.3}", offer.score.round_dp(3))];
+        cells.extend(shares.iter().map(|(objective, _)| {
+            offer
+                .breakdown
+                .iter()
+                .find(|(candidate, _)| candidate == objective)
+                .map(|(_, contribution)| format!("{:.3}", contribution.round_dp(3)))
+                .unwrap_or_else(|| "-".to_string())
+        }));
+        table.add_row(cells.into());
     }
+
+    println!(
+        "\n{}",
+        trf(
+            "Offers ranked by {} (total cost over {}):",
+            &[&describe_ranking(&ranking), &format_months(horizon * 12)],
+        )
+    );
+    print_table(&table);
+    println!(
+        "{}",
+        tr("Each column is that objective's share of the score; the best offer on an objective earns its full weight.")
+    );
+    Ok(())
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let calculator = LoanCalculator::new()?;
-    
-    // Select loan type
-    let loan_types = vec!["Home Loan", "Car Loan", "Personal Loan"];
-    let loan_type_selection = Select::new()
-        .with_prompt("Select loan type")
-        .items(&loan_types)
-        .default(0)
-        .interact()?;
-    
-    let loan_type = match loan_type_selection {
-        0 => LoanType::Home,
-        1 => LoanType::Car,
-        2 => LoanType::Personal,
-        _ => unreachable!(),
+fn add_offer_warnings(warnings: &mut Vec<String>, name: &str, rate: Decimal, cost: Option<LoanCost>) {
+    let Some(cost) = cost else {
+        warnings.push(trf(
+            "{}: interest at {}% would exceed the amount borrowed, so no loan is possible",
+            &[&name, &format!("{:.2}", rate.round_dp(2))],
+        ));
+        return;
     };
 
-    // Get loan details with validation
-    let loan_amount = get_valid_loan_amount(&loan_type)?;
-    let loan_term = get_valid_loan_term(&loan_type)?;
-    let credit_score = get_valid_credit_score()?;
+    if let Some(penalty) = cost.early_payoff_penalty {
+        warnings.push(trf(
+            "{}: Rule of 78s payoff costs {} more than an actuarial loan at the same rate",
+            &[&name, &format_money(penalty)],
+        ));
+    }
+}
 
-    // Create results table
-    let mut table = Table::new();
-    table.add_row(row![
-        "Bank",
-        "Interest Rate",
-        "Monthly Payment",
-        "Total Interest",
-        "Total Payment"
-    ]);
+fn get_valid_credit_score() -> Result<u16, Box<dyn std::error::Error>> {
+    let score: u16 = Input::new()
+        .with_prompt(tr("Enter your credit score (300-850)"))
+        .validate_with(|input: &u16| {
+            if *input >= 300 && *input <= 850 {
+                Ok(())
+            } else {
+                Err(tr("Credit score must be between 300 and 850"))
+            }
+        })
+        .interact_text()?;
+    Ok(score)
+}
 
-    let mut has_qualifying_banks = false;
+fn get_valid_loan_amount(loan_type: &LoanType) -> Result<Decimal, Box<dyn std::error::Error>> {
+    eprintln!("\n{}", tr(loan_type.get_description()));
+    let amount: f64 = Input::new()
+        .with_prompt(money_prompt(tr("Enter loan amount ($)")))
+        .with_initial_text(format!("{}", loan_type.get_default_amount()))
+        .validate_with(move |input: &f64| check_loan_amount(loan_type, *input))
+        .interact_text()?;
+    Ok(Decimal::from_f64(amount).unwrap())
+}
 
-    for bank in &calculator.banks {
-        let (min_rate, max_rate) = bank.get_rate_range(&loan_type);
-        
-        // Skip if credit score is too low
+// How the loan is described: by the amount borrowed, by a purchase to
+// finance, or by the payment to find the largest loan for
+enum LoanEntry {
+    Amount,
+    Purchase(Purchase),
+    Payment(Decimal),
+}
+
+fn get_loan_entry(loan_type: &LoanType) -> Result<LoanEntry, Box<dyn std::error::Error>> {
+    let mut options = vec![tr("Loan amount")];
+    // Home and car loans can start from the price instead of the amount borrowed
+    let finances_purchases = matches!(loan_type, LoanType::Home | LoanType::Car);
+    if finances_purchases {
+        options.push(tr("Purchase price and down payment"));
+    }
+    options.push(tr("Monthly payment I can afford"));
+    let selection = Select::new()
+        .with_prompt(tr("Enter the loan as"))
+        .items(&options)
+        .default(0)
+        .interact()?;
+    if selection == 0 {
+        return Ok(LoanEntry::Amount);
+    }
+    if selection == options.len() - 1 {
+        let payment: f64 = Input::new()
+            .with_prompt(money_prompt(tr("Monthly payment you can afford ($)")))
+            .validate_with(|input: &f64| check_payment(*input))
+            .interact_text()?;
+        return Ok(LoanEntry::Payment(Decimal::from_f64(payment).unwrap()));
+    }
+
+    eprintln!("\n{}", tr(loan_type.get_description()));
+    let price: f64 = Input::new()
+        .with_prompt(money_prompt(tr("Enter purchase price ($)")))
+        .with_initial_text(format!("{}", loan_type.get_default_amount() * 1.25))
+        .validate_with(|input: &f64| {
+            if *input > 0.0 {
+                Ok(())
+            } else {
+                Err(tr("Purchase price must be greater than 0"))
+            }
+        })
+        .interact_text()?;
+    Ok(LoanEntry::Purchase(get_down_payment(loan_type, Decimal::from_f64(price).unwrap())?))
+}
+
+fn check_payment(payment: f64) -> Result<(), &'static str> {
+    if payment > 0.0 {
+        Ok(())
+    } else {
+        Err(tr("Payment must be greater than 0"))
+    }
+}
+
+// Each qualifying bank's rate for an amortizing loan given up front, from its
+// live quote or its configured range, adding the banks that turn the loan
+// down to `declined`. For `serve` and `batch`, which have no one to ask
+// about collateral or the closing date.
+fn quote_banks(
+    calculator: &mut LoanCalculator,
+    loan_type: &LoanType,
+    amount: Decimal,
+    term_months: u32,
+    credit_score: u16,
+    declined: &mut Vec<(String, String)>,
+    warnings: &mut Vec<String>,
+) -> Vec<(String, Decimal)> {
+    let live_quotes = calculator.fetch_live_quotes(
+        &QuoteRequest {
+            loan_type: loan_type.get_name(),
+            amount,
+            term_months,
+            credit_score,
+        },
+        warnings,
+    );
+    let mut offers = Vec::new();
+    for bank in calculator.banks() {
         if credit_score < bank.min_credit_score {
+            declined.push((
+                bank.name.clone(),
+                trf("requires a credit score of at least {}", &[&bank.min_credit_score]),
+            ));
             continue;
         }
+        if let Some(reason) = bank.check_term(loan_type, term_months) {
+            declined.push((bank.name.clone(), reason));
+            continue;
+        }
+        let rate = match live_quotes.get(&bank.name) {
+            Some(Some(rate)) => calculator.monthly_rate(&bank.name, *rate),
+            Some(None) => {
+                declined.push((bank.name.clone(), tr("declined to quote this loan").to_string()));
+                continue;
+            }
+            None => calculator.quote_rate(bank, loan_type, credit_score),
+        };
+        let cost = calculator.calculate_cost(&InterestMethod::Amortizing, amount, rate, term_months);
+        add_offer_warnings(warnings, &bank.name, rate, cost);
+        offers.push((bank.name.clone(), rate));
+    }
+    offers
+}
 
-        has_qualifying_banks = true;
+// Solves for the most each bank would lend at its quoted rate, rather than
+// pricing one amount
+fn print_affordability(
+    calculator: &LoanCalculator,
+    loan_type: &LoanType,
+    payment: Decimal,
+    loan_term: u32,
+    credit_score: u16,
+    json_output: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let interest_method = InterestMethod::Amortizing;
+    let mut offers = Vec::new();
+    let mut declined = Vec::new();
+    for bank in calculator.banks() {
+        if credit_score < bank.min_credit_score {
+            declined.push((
+                bank.name.clone(),
+                trf("requires a credit score of at least {}", &[&bank.min_credit_score]),
+            ));
+            continue;
+        }
+        if let Some(reason) = bank.check_term(loan_type, loan_term) {
+            declined.push((bank.name.clone(), reason));
+            continue;
+        }
+        offers.push((bank.name.clone(), calculator.quote_rate(bank, loan_type, credit_score)));
+    }
 
-        // Calculate adjusted rate based on credit score
-        let base_rate = (min_rate + max_rate) / dec!(2);
-        let adjusted_rate = calculator.adjust_rate_for_credit(base_rate, credit_score);
-        
-        let monthly_payment = calculator.calculate_monthly_payment(
-            loan_amount,
-            adjusted_rate,
-            loan_term,
-        );
-        
-        let total_payment = monthly_payment * Decimal::from(loan_term * 12);
-        let total_interest = total_payment - loan_amount;
+    if json_output {
+        let provenance = calculator.provenance(vec![
+            ("loan_type", loan_type.get_name().to_string()),
+            ("payment", format!("{:.2}", payment.round_dp(2))),
+            ("term_months", loan_term.to_string()),
+            ("credit_score", credit_score.to_string()),
+            ("interest_method", interest_method.get_description()),
+        ]);
+        let document = calculator.affordability_document(&affordability::AffordabilityResults {
+            loan_type: loan_type.get_name(),
+            payment,
+            term_months: loan_term,
+            credit_score,
+            interest_method: &interest_method,
+            offers: &offers,
+            declined: &declined,
+            provenance: &provenance,
+        });
+        println!("{}", serde_json::to_string_pretty(&document)?);
+        return Ok(());
+    }
 
+    let mut table = Table::new();
+    table.add_row(row![tr("Bank"), tr("Interest Rate"), tr("Maximum Loan"), tr("Total Interest"), tr("Total Payment")]);
+    for (name, rate) in &offers {
+        let Some(principal) = calculator.max_principal(&interest_method, payment, *rate, loan_term) else {
+            continue;
+        };
+        let Some(cost) = calculator.calculate_cost(&interest_method, principal, *rate, loan_term) else {
+            continue;
+        };
         table.add_row(row![
-            bank.name,
-            format!("{:.2}%", adjusted_rate),
-            format_money(monthly_payment),
-            format_money(total_interest),
-            format_money(total_payment)
+            name,
+            format_rate(*rate, None),
+            format_money(principal),
+            format_money(cost.total_interest),
+            format_money(cost.total_payment)
         ]);
     }
 
-    if !has_qualifying_banks {
-        println!("\nNo banks available for credit score {}.", credit_score);
-        println!("Minimum required credit score is {}.", calculator.get_min_credit_score());
-        println!("Consider using a custom interest rate to estimate payments.");
+    println!(
+        "\n{}",
+        trf(
+            "Most each bank would lend for {} a month over {} (credit score {}):",
+            &[&format_money(payment), &format_months(loan_term), &credit_score],
+        )
+    );
+    print_table(&table);
+    for (name, reason) in &declined {
+        println!("{} {} {}", tr("Declined:").yellow().bold(), name, reason);
     }
+    Ok(())
+}
 
-    // Option for custom rate
-    println!("\nWould you like to calculate with a custom interest rate?");
-    let custom_rate_options = vec!["Yes", "No"];
-    let custom_rate_selection = Select::new()
-        .items(&custom_rate_options)
-        .default(1)
+fn get_down_payment(loan_type: &LoanType, price: Decimal) -> Result<Purchase, Box<dyn std::error::Error>> {
+    let down_payment: String = Input::new()
+        .with_prompt(money_prompt(tr("Enter down payment ($ or %)")))
+        .with_initial_text("20%")
+        .validate_with(|input: &String| {
+            let down_payment = DownPayment::parse(input)
+                .ok_or_else(|| tr("Enter an amount like 60000 or a percent like 20%").to_string())?;
+            check_purchase(loan_type, price, down_payment).map(|_| ())
+        })
+        .interact_text()?;
+    let down_payment = DownPayment::parse(&down_payment).ok_or(tr("Down payment must be an amount or a percent"))?;
+    Ok(check_purchase(loan_type, price, down_payment)?)
+}
+
+fn check_purchase(loan_type: &LoanType, price: Decimal, down_payment: DownPayment) -> Result<Purchase, String> {
+    let purchase = Purchase::new(price, down_payment).map_err(|e| e.to_string())?;
+    check_loan_amount(loan_type, purchase.financed_amount().to_f64().unwrap_or_default())
+        .map_err(|e| trf("{} after the down payment", &[&e]))?;
+    Ok(purchase)
+}
+
+fn check_loan_amount(loan_type: &LoanType, amount: f64) -> Result<(), &'static str> {
+    if amount <= 0.0 {
+        Err(tr("Loan amount must be greater than 0"))
+    } else if amount > loan_type.get_max_amount() {
+        Err(tr("Loan amount exceeds maximum allowed"))
+    } else {
+        Ok(())
+    }
+}
+
+fn get_valid_loan_term(loan_type: &LoanType) -> Result<u32, Box<dyn std::error::Error>> {
+    let max_term = loan_type.get_max_term();
+    let term: String = Input::new()
+        .with_prompt(trf(
+            "Enter loan term (years, or months like 66m; up to {})",
+            &[&format_months(max_term)],
+        ))
+        .with_initial_text(format_term_input(loan_type.get_default_term()))
+        .validate_with(|input: &String| check_loan_term(loan_type, input).map(|_| ()))
+        .interact_text()?;
+    Ok(check_loan_term(loan_type, &term)?)
+}
+
+fn check_loan_term(loan_type: &LoanType, input: &str) -> Result<u32, String> {
+    let max_term = loan_type.get_max_term();
+    match parse_term(input) {
+        Some(months) if months >= 1 && months <= max_term => Ok(months),
+        Some(_) => Err(trf("Loan term must be between 1 month and {}", &[&format_months(max_term)])),
+        None => Err(tr("Enter a number of years like 30 or months like 66m").to_string()),
+    }
+}
+
+fn get_payment_frequency() -> Result<PaymentFrequency, Box<dyn std::error::Error>> {
+    let labels: Vec<&str> = FREQUENCIES.iter().map(|frequency| tr(frequency.get_description())).collect();
+    let selection = Select::new()
+        .with_prompt(tr("How often will you make payments?"))
+        .items(&labels)
+        .default(0)
         .interact()?;
+    Ok(FREQUENCIES[selection])
+}
 
-    if custom_rate_selection == 0 {
-        let custom_rate: f64 = Input::new()
-            .with_prompt("Enter custom interest rate (%)")
-            .validate_with(|input: &f64| {
-                if *input > 0.0 && *input < 100.0 {
-                    Ok(())
-                } else {
-                    Err("Interest rate must be between 0 and 100")
-                }
-            })
-            .interact_text()?;
-        let custom_rate = Decimal::from_f64(custom_rate).unwrap();
-        
-        let monthly_payment = calculator.calculate_monthly_payment(
-            loan_amount,
-            custom_rate,
-            loan_term,
-        );
-        
-        let total_payment = monthly_payment * Decimal::from(loan_term * 12);
-        let total_interest = total_payment - loan_amount;
+fn get_interest_method(
+    loan_term: u32,
+    calendar: &Calendar,
+    convention: InterestConvention,
+) -> Result<InterestMethod, Box<dyn std::error::Error>> {
+    let methods = vec![
+        tr("Amortizing (monthly compounding)"),
+        tr("Simple interest (daily accrual)"),
+        tr("Precomputed interest (Rule of 78s)"),
+        tr("Add-on interest (flat installments)"),
+        tr("Discount (interest deducted upfront)"),
+    ];
+    // Daily simple interest first when banks.yaml says the loan type accrues that way
+    let selection = Select::new()
+        .with_prompt(tr("Select how the lender charges interest"))
+        .items(&methods)
+        .default(if convention == InterestConvention::Actual365 { 1 } else { 0 })
+        .interact()?;
 
-        table.add_row(row![
-            "Custom Rate",
-            format!("{:.2}%", custom_rate),
-            format_money(monthly_payment),
-            format_money(total_interest),
-            format_money(total_payment)
-        ]);
+    match selection {
+        0 => Ok(InterestMethod::Amortizing),
+        1 => {
+            let days_early: u32 = Input::new()
+                .with_prompt(tr("How many days before the due date do you usually pay? (0-27)"))
+                .with_initial_text("0")
+                .validate_with(|input: &u32| {
+                    if *input <= 27 {
+                        Ok(())
+                    } else {
+                        Err(tr("Days early must be between 0 and 27"))
+                    }
+                })
+                .interact_text()?;
+            Ok(InterestMethod::SimpleDaily {
+                days_early,
+                calendar: calendar.clone(),
+            })
+        }
+        2 => {
+            let num_payments = loan_term;
+            let payoff_after: u32 = Input::new()
+                .with_prompt(trf(
+                    "Pay off early after how many payments? (0 to run the full {} payments)",
+                    &[&num_payments],
+                ))
+                .with_initial_text("0")
+                .validate_with(move |input: &u32| {
+                    if *input < num_payments {
+                        Ok(())
+                    } else {
+                        Err(tr("Early payoff must come before the final payment"))
+                    }
+                })
+                .interact_text()?;
+            let payoff_after = if payoff_after == 0 { None } else { Some(payoff_after) };
+            Ok(InterestMethod::RuleOf78s { payoff_after })
+        }
+        3 => Ok(InterestMethod::AddOn),
+        4 => Ok(InterestMethod::Discount),
+        _ => unreachable!(),
     }
+}
+
+fn get_closing(calculator: &LoanCalculator) -> Result<Closing, Box<dyn std::error::Error>> {
+    let today = chrono::Local::now().date_naive();
+    let date: String = Input::new()
+        .with_prompt(tr("Closing date (YYYY-MM-DD)"))
+        .with_initial_text(today.format("%Y-%m-%d").to_string())
+        .validate_with(|input: &String| {
+            chrono::NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d")
+                .map(|_| ())
+                .map_err(|_| tr("Enter a date like 2024-03-15"))
+        })
+        .interact_text()?;
+    let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")?;
 
-    // Print loan details
-    println!("\nLoan Details:");
-    println!("Amount: {}", format_money(loan_amount));
-    println!("Term: {} years", loan_term);
-    println!("Credit Score: {}", credit_score);
-    println!("\nComparison of Options:");
-    table.printstd();
+    let days = vec![tr("1st"), tr("15th"), tr("Last day of the month"), tr("Another day")];
+    let selection = Select::new()
+        .with_prompt(tr("Payment due day"))
+        .items(&days)
+        .default(0)
+        .interact()?;
+    let payment_day = match selection {
+        0 => PaymentDay::Day(1),
+        1 => PaymentDay::Day(15),
+        2 => PaymentDay::LastDay,
+        3 => {
+            let day: u32 = Input::new()
+                .with_prompt(tr("Day of the month (1-31, later than the month's end means its last day)"))
+                .with_initial_text("1")
+                .validate_with(|input: &u32| {
+                    if *input >= 1 && *input <= 31 {
+                        Ok(())
+                    } else {
+                        Err(tr("Day must be between 1 and 31"))
+                    }
+                })
+                .interact_text()?;
+            PaymentDay::Day(day)
+        }
+        _ => unreachable!(),
+    };
+
+    let options = vec![tr("Yes"), tr("No")];
+    let selection = Select::new()
+        .with_prompt(tr("Move due dates on weekends and holidays to the next business day?"))
+        .items(&options)
+        .default(1)
+        .interact()?;
+    let holidays = match selection {
+        0 => Some(calculator.holidays()),
+        _ => None,
+    };
+
+    let mut closing = Closing {
+        date,
+        payment_day,
+        holidays,
+        stub_interest: StubInterest::Prepaid,
+    };
+    if closing.stub_days() > 0 {
+        let options = vec![
+            tr("Collected at closing (prepaid interest)"),
+            tr("Added to the first payment"),
+        ];
+        let selection = Select::new()
+            .with_prompt(trf(
+                "How is interest for the {} days before {} paid?",
+                &[&closing.stub_days(), &format_date(closing.period_start())],
+            ))
+            .items(&options)
+            .default(0)
+            .interact()?;
+        if selection == 1 {
+            closing.stub_interest = StubInterest::OddFirstPayment;
+        }
+    }
+    Ok(closing)
+}
+
+fn print_stub_interest(
+    calculator: &LoanCalculator,
+    loan_type: &LoanType,
+    offers: &[(String, Decimal)],
+    interest_method: &InterestMethod,
+    loan_amount: Decimal,
+    loan_term: u32,
+    closing: &Closing,
+) {
+    let (stub_days, days_in_year) = calculator.interest_convention(loan_type).stub_days(closing);
+    let mut table = Table::new();
+    table.add_row(row![tr("Bank"), tr("Per Diem"), tr("Interest Before First Period"), tr("First Payment")]);
+    for (name, rate) in offers {
+        let Some(cost) = calculator.calculate_cost(interest_method, loan_amount, *rate, loan_term) else {
+            continue;
+        };
+        let stub_interest = calculator.calculate_stub_interest(loan_type, loan_amount, *rate, closing);
+        let first_payment = match closing.stub_interest {
+            StubInterest::Prepaid => cost.monthly_payment,
+            StubInterest::OddFirstPayment => cost.monthly_payment + stub_interest,
+        };
+        table.add_row(row![
+            name,
+            format_money(loan_amount * *rate / dec!(100) / days_in_year),
+            format_money(stub_interest),
+            format_money(first_payment)
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Interest from closing to {} ({} days of a {}-day year, {}):",
+            &[
+                &format_date(closing.period_start()),
+                &stub_days,
+                &days_in_year,
+                &tr(closing.stub_interest.get_description()),
+            ],
+        )
+    );
+    print_table(&table);
+}
+
+fn get_collateral() -> Result<Vec<Collateral>, Box<dyn std::error::Error>> {
+    let mut collateral = Vec::new();
+    eprintln!("\n{}", tr("Is the loan secured by collateral (vehicles, property, savings)?"));
+    let options = vec![tr("Yes"), tr("No")];
+    let selection = Select::new().items(&options).default(1).interact()?;
+    if selection == 1 {
+        return Ok(collateral);
+    }
+
+    loop {
+        let description: String = Input::new()
+            .with_prompt(tr("Collateral description"))
+            .interact_text()?;
+        let value: f64 = Input::new()
+            .with_prompt(money_prompt(tr("Collateral value ($)")))
+            .validate_with(|input: &f64| {
+                if *input > 0.0 {
+                    Ok(())
+                } else {
+                    Err(tr("Collateral value must be greater than 0"))
+                }
+            })
+            .interact_text()?;
+        collateral.push(Collateral {
+            description,
+            value: Decimal::from_f64(value).unwrap(),
+        });
+
+        let more = Select::new()
+            .with_prompt(tr("Add another collateral item?"))
+            .items(&options)
+            .default(1)
+            .interact()?;
+        if more == 1 {
+            return Ok(collateral);
+        }
+    }
+}
+
+fn get_housing_costs() -> Result<Option<HousingCosts>, Box<dyn std::error::Error>> {
+    eprintln!("\n{}", tr("Include property tax, homeowner's insurance and HOA dues in the monthly cost?"));
+    let options = vec![tr("Yes"), tr("No")];
+    let selection = Select::new().items(&options).default(1).interact()?;
+    if selection == 1 {
+        return Ok(None);
+    }
+    Ok(Some(HousingCosts {
+        property_tax: get_money_input(tr("Yearly property tax ($)"), "3600")?,
+        insurance: get_money_input(tr("Yearly homeowner's insurance ($)"), "1500")?,
+        hoa_dues: get_money_input(tr("Monthly HOA dues ($)"), "0")?,
+    }))
+}
+
+fn get_vehicle_deal() -> Result<Option<VehicleDeal>, Box<dyn std::error::Error>> {
+    eprintln!("\n{}", tr("Include sales tax, a trade-in and dealer fees in the amount financed?"));
+    let options = vec![tr("Yes"), tr("No")];
+    let selection = Select::new().items(&options).default(1).interact()?;
+    if selection == 1 {
+        return Ok(None);
+    }
+    let sales_tax_rate: f64 = Input::new()
+        .with_prompt(tr("Sales tax rate (%)"))
+        .with_initial_text("6")
+        .validate_with(|input: &f64| check_sales_tax(*input))
+        .interact_text()?;
+    let trade_in_value = get_money_input(tr("Trade-in value ($)"), "0")?;
+    let trade_in_payoff = if trade_in_value > Decimal::ZERO {
+        get_money_input(tr("Still owed on the trade-in ($)"), "0")?
+    } else {
+        Decimal::ZERO
+    };
+    Ok(Some(VehicleDeal {
+        sales_tax_rate: Decimal::from_f64(sales_tax_rate).unwrap(),
+        trade_in_value,
+        trade_in_payoff,
+        dealer_fees: get_money_input(tr("Dealer, documentation and registration fees ($)"), "500")?,
+    }))
+}
+
+fn check_sales_tax(rate: f64) -> Result<(), &'static str> {
+    if (0.0..100.0).contains(&rate) {
+        Ok(())
+    } else {
+        Err(tr("Sales tax rate must be at least 0 and below 100"))
+    }
+}
+
+// Student loans can start repayment after school and a grace period
+fn get_deferment() -> Result<Option<Deferment>, Box<dyn std::error::Error>> {
+    eprintln!("\n{}", tr("Is repayment deferred while you're in school?"));
+    let options = vec![tr("Yes"), tr("No")];
+    let selection = Select::new().items(&options).default(1).interact()?;
+    if selection == 1 {
+        return Ok(None);
+    }
+    Ok(Some(Deferment {
+        in_school_months: get_months_input(tr("Months left in school"), "24", 96)?,
+        grace_months: get_months_input(
+            tr("Grace period after school (months)"),
+            &DEFAULT_GRACE_MONTHS.to_string(),
+            12,
+        )?,
+    }))
+}
+
+fn get_income() -> Result<Option<Income>, Box<dyn std::error::Error>> {
+    eprintln!("\n{}", tr("Check the offers against each bank's debt-to-income limits?"));
+    let options = vec![tr("Yes"), tr("No")];
+    let selection = Select::new().items(&options).default(1).interact()?;
+    if selection == 1 {
+        return Ok(None);
+    }
+    let gross_monthly: f64 = Input::new()
+        .with_prompt(money_prompt(tr("Gross monthly income ($)")))
+        .validate_with(|input: &f64| {
+            if *input > 0.0 {
+                Ok(())
+            } else {
+                Err(tr("Income must be greater than 0"))
+            }
+        })
+        .interact_text()?;
+    Ok(Some(Income {
+        gross_monthly: Decimal::from_f64(gross_monthly).unwrap(),
+        monthly_debts: get_money_input(tr("Existing monthly debt payments ($)"), "0")?,
+    }))
+}
+
+fn get_months_input(prompt: &str, initial: &str, max_months: u32) -> Result<u32, Box<dyn std::error::Error>> {
+    let months: u32 = Input::new()
+        .with_prompt(prompt)
+        .with_initial_text(initial)
+        .validate_with(move |input: &u32| {
+            if *input <= max_months {
+                Ok(())
+            } else {
+                Err(trf("Must be at most {} months", &[&max_months]))
+            }
+        })
+        .interact_text()?;
+    Ok(months)
+}
+
+fn flag_amount(value: Option<f64>, flag: &str) -> Result<Decimal, String> {
+    match value {
+        None => Ok(Decimal::ZERO),
+        Some(amount) if amount >= 0.0 => Decimal::from_f64(amount).ok_or_else(|| format!("{} must be a number", flag)),
+        Some(_) => Err(format!("{} cannot be negative", flag)),
+    }
+}
+
+// English ordinals like "the 1st" do not translate, so other languages name
+// the day by its number
+fn describe_payment_day(payment_day: &PaymentDay) -> String {
+    match payment_day {
+        _ if !i18n::translating() => payment_day.get_description(),
+        PaymentDay::Day(day) => trf("day {} of the month", &[day]),
+        PaymentDay::LastDay => tr("the last day of the month").to_string(),
+    }
+}
+
+// `MarketRate::get_description` in the chosen language
+fn describe_market_rate(market_rate: &MarketRate) -> String {
+    let rate = format!("{:.2}", market_rate.rate);
+    match market_rate.as_of {
+        Some(date) => trf("{}% ({}, as of {})", &[&rate, &market_rate.source, &format_date(date)]),
+        None => format!("{}% ({})", rate, market_rate.source),
+    }
+}
+
+// `InterestMethod::get_description` in the chosen language
+fn describe_interest_method(method: &InterestMethod) -> String {
+    match method {
+        InterestMethod::Amortizing => tr("Amortizing (monthly compounding)").to_string(),
+        InterestMethod::SimpleDaily { days_early: 0, .. } => tr("Simple interest (daily accrual)").to_string(),
+        InterestMethod::SimpleDaily { days_early, .. } => {
+            trf("Simple interest (daily accrual, paid {} days early)", &[days_early])
+        }
+        InterestMethod::RuleOf78s { payoff_after: None } => tr("Precomputed interest (Rule of 78s)").to_string(),
+        InterestMethod::RuleOf78s { payoff_after: Some(payments) } => {
+            trf("Precomputed interest (Rule of 78s, paid off after {} payments)", &[payments])
+        }
+        InterestMethod::AddOn => tr("Add-on interest (flat installments)").to_string(),
+        InterestMethod::Discount => tr("Discount (interest deducted upfront)").to_string(),
+    }
+}
+
+// `PaymentStrategy::get_description` in the chosen language
+fn describe_strategy(strategy: &PaymentStrategy) -> String {
+    match strategy {
+        PaymentStrategy::Standard => tr("Standard").to_string(),
+        PaymentStrategy::RoundUp { increment } => {
+            trf("Round up to {}", &[&money::money_format().with_symbol(&increment.to_string())])
+        }
+        PaymentStrategy::AnnualExtra { month } => trf("13th payment (month {} of each year)", &[month]),
+        PaymentStrategy::Offset { initial_balance, monthly_deposit } => trf(
+            "Offset account ({} + {}/mo)",
+            &[&format_money(*initial_balance), &format_money(*monthly_deposit)],
+        ),
+        PaymentStrategy::LumpSum { amount, month } => {
+            trf("{} prepaid at month {}", &[&format_money(*amount), month])
+        }
+        PaymentStrategy::ExtraMonthly { amount } => trf("{} extra each month", &[&format_money(*amount)]),
+    }
+}
+
+// `Prepayment::get_description` in the chosen language
+fn describe_prepayment(prepayment: &Prepayment) -> String {
+    trf("{} at month {}", &[&format_money(prepayment.amount), &prepayment.month])
+}
+
+// Prompts are written with ($) and show the configured currency's symbol
+fn money_prompt(prompt: &str) -> String {
+    prompt.replace('$', money::money_format().currency.symbol)
+}
+
+fn get_money_input(prompt: &str, initial: &str) -> Result<Decimal, Box<dyn std::error::Error>> {
+    let amount: f64 = Input::new()
+        .with_prompt(money_prompt(prompt))
+        .with_initial_text(initial)
+        .validate_with(|input: &f64| {
+            if *input >= 0.0 {
+                Ok(())
+            } else {
+                Err(tr("Amount cannot be negative"))
+            }
+        })
+        .interact_text()?;
+    Ok(Decimal::from_f64(amount).unwrap())
+}
+
+fn get_payment_strategies(
+    loan_type: &LoanType,
+) -> Result<Vec<PaymentStrategy>, Box<dyn std::error::Error>> {
+    let mut strategy_options = vec![
+        tr("Round up each payment"),
+        tr("One extra payment per year"),
+        tr("Extra principal each month"),
+    ];
+    if let LoanType::Home = loan_type {
+        strategy_options.push(tr("Offset savings account"));
+    }
+    let selections = MultiSelect::new()
+        .with_prompt(tr("Select strategies to compare against standard payments (space to toggle)"))
+        .items(&strategy_options)
+        .interact()?;
+
+    let mut strategies = vec![PaymentStrategy::Standard];
+    for selection in selections {
+        match selection {
+            0 => {
+                let increments: Vec<String> =
+                    ["10", "50", "100"].iter().map(|amount| money::money_format().with_symbol(amount)).collect();
+                let increment_selection = Select::new()
+                    .with_prompt(tr("Round each payment up to the nearest"))
+                    .items(&increments)
+                    .default(1)
+                    .interact()?;
+
+                let increment = match increment_selection {
+                    0 => dec!(10),
+                    1 => dec!(50),
+                    2 => dec!(100),
+                    _ => unreachable!(),
+                };
+                strategies.push(PaymentStrategy::RoundUp { increment });
+            }
+            1 => {
+                let month: u32 = Input::new()
+                    .with_prompt(tr("Make the extra payment with which payment of each loan year? (1-12)"))
+                    .with_initial_text("12")
+                    .validate_with(|input: &u32| {
+                        if *input >= 1 && *input <= 12 {
+                            Ok(())
+                        } else {
+                            Err(tr("Month must be between 1 and 12"))
+                        }
+                    })
+                    .interact_text()?;
+                strategies.push(PaymentStrategy::AnnualExtra { month });
+            }
+            2 => {
+                let amount = get_money_input(tr("Extra principal with each payment ($)"), "100")?;
+                strategies.push(PaymentStrategy::ExtraMonthly { amount });
+            }
+            3 => {
+                let initial_balance = get_money_input(tr("Starting offset account balance ($)"), "0")?;
+                let monthly_deposit = get_money_input(tr("Monthly deposit into the offset account ($)"), "0")?;
+                strategies.push(PaymentStrategy::Offset {
+                    initial_balance,
+                    monthly_deposit,
+                });
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(strategies)
+}
+
+fn print_strategy_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    strategies: &[PaymentStrategy],
+) {
+    let mut table = Table::new();
+    let mut notes = Vec::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Strategy"),
+        tr("Monthly Payment"),
+        tr("Payoff"),
+        tr("Total Interest"),
+        tr("Interest Saved")
+    ]);
+
+    for (name, rate) in offers {
+        let baseline = calculator.generate_schedule(
+            loan_amount,
+            *rate,
+            loan_term,
+            &PaymentStrategy::Standard,
+        );
+
+        for strategy in strategies {
+            let schedule = calculator.generate_schedule(loan_amount, *rate, loan_term, strategy);
+            table.add_row(row![
+                name,
+                describe_strategy(strategy),
+                format_money(schedule.rows[0].payment),
+                format_months(schedule.num_payments()),
+                format_money(schedule.total_interest()),
+                format_money(baseline.total_interest() - schedule.total_interest())
+            ]);
+
+            if let PaymentStrategy::Offset { .. } = strategy {
+                let last_row = &schedule.rows[schedule.rows.len() - 1];
+                notes.push(trf(
+                    "{}: offset account holds {} when the loan is paid off",
+                    &[name, &format_money(last_row.offset_balance)],
+                ));
+            }
+        }
+    }
+
+    println!("\n{}", tr("Payment Strategy Comparison:"));
+    print_table(&table);
+    for note in &notes {
+        println!("{}", note);
+    }
+}
+
+// Paying more often than monthly means each payment carries less accrued
+// interest over the same term
+fn print_frequency_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    frequency: PaymentFrequency,
+) {
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Frequency"),
+        tr("Payment"),
+        tr("Payments"),
+        tr("Total Interest"),
+        tr("Interest Saved")
+    ]);
+    for (name, rate) in offers {
+        for cost in calculator.compare_frequencies(loan_amount, *rate, loan_term, frequency) {
+            table.add_row(row![
+                name,
+                tr(cost.frequency.get_description()),
+                format_money(cost.payment),
+                cost.num_payments,
+                format_money(cost.total_interest),
+                format_money(cost.interest_saved)
+            ]);
+        }
+    }
+
+    println!("\n{}", tr("Payment Frequency:"));
+    print_table(&table);
+}
+
+// Each lump sum on its own, then all of them together when there are several
+fn print_prepayment_effects(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+    prepayments: &[Prepayment],
+) {
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Prepayment"),
+        tr("Payoff Date"),
+        tr("Payoff"),
+        tr("Total Interest"),
+        tr("Interest Saved")
+    ]);
+
+    let mut scenarios: Vec<(String, Vec<Prepayment>)> = prepayments
+        .iter()
+        .map(|prepayment| (describe_prepayment(prepayment), vec![*prepayment]))
+        .collect();
+    if prepayments.len() > 1 {
+        scenarios.push((tr("All prepayments").to_string(), prepayments.to_vec()));
+    }
+
+    for (name, rate) in offers {
+        let baseline = calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+        table.add_row(row![
+            name,
+            tr("None"),
+            format_date(calendar.payment_date(baseline.num_payments())),
+            format_months(baseline.num_payments()),
+            format_money(baseline.total_interest()),
+            format_money(dec!(0))
+        ]);
+        for (description, scenario) in &scenarios {
+            let schedule = calculator.generate_prepaid_schedule(loan_amount, *rate, loan_term, scenario);
+            table.add_row(row![
+                name,
+                description,
+                format_date(calendar.payment_date(schedule.num_payments())),
+                format_months(schedule.num_payments()),
+                format_money(schedule.total_interest()),
+                format_money(baseline.total_interest() - schedule.total_interest())
+            ]);
+        }
+    }
+
+    println!("\n{}", tr("Lump-Sum Prepayments:"));
+    print_table(&table);
+}
+
+fn get_prepayments(loan_term: u32) -> Result<Vec<Prepayment>, Box<dyn std::error::Error>> {
+    let mut prepayments = Vec::new();
+    let options = vec![tr("Yes"), tr("No")];
+    loop {
+        let amount = get_money_input(tr("Prepayment amount ($)"), "10000")?;
+        let month: u32 = Input::new()
+            .with_prompt(trf("Made with which payment? (1-{})", &[&loan_term]))
+            .with_initial_text("24")
+            .validate_with(|input: &u32| {
+                if *input >= 1 && *input <= loan_term {
+                    Ok(())
+                } else {
+                    Err(tr("The prepayment must fall within the loan term"))
+                }
+            })
+            .interact_text()?;
+        prepayments.push(Prepayment { amount, month });
+
+        let more = Select::new()
+            .with_prompt(tr("Add another prepayment?"))
+            .items(&options)
+            .default(1)
+            .interact()?;
+        if more == 1 {
+            return Ok(prepayments);
+        }
+    }
+}
+
+fn print_prepay_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let lump_sum = get_money_input(tr("Lump sum available ($)"), "10000")?;
+    let savings_rate: f64 = Input::new()
+        .with_prompt(tr("Savings account yield (%)"))
+        .with_initial_text("4.0")
+        .validate_with(|input: &f64| {
+            if *input >= 0.0 && *input < 100.0 {
+                Ok(())
+            } else {
+                Err(tr("Yield must be between 0 and 100"))
+            }
+        })
+        .interact_text()?;
+    let savings_rate = Decimal::from_f64(savings_rate).unwrap();
+    let horizon: u32 = Input::new()
+        .with_prompt(trf("Horizon in months (1-{})", &[&loan_term]))
+        .with_initial_text(format!("{}", loan_term.min(120)))
+        .validate_with(move |input: &u32| {
+            if *input >= 1 && *input <= loan_term {
+                Ok(())
+            } else {
+                Err(tr("Horizon must fall within the loan term"))
+            }
+        })
+        .interact_text()?;
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Interest Saved"),
+        tr("Net at Horizon (Prepay)"),
+        tr("Net at Horizon (Save)"),
+        tr("Better Option"),
+        tr("Crossover Yield")
+    ]);
+
+    for (name, rate) in offers {
+        let comparison = calculator.compare_prepay_vs_save(
+            loan_amount,
+            *rate,
+            loan_term,
+            lump_sum,
+            savings_rate,
+            horizon,
+        );
+        let better = if comparison.prepay_net >= comparison.save_net {
+            tr("Prepay")
+        } else {
+            tr("Save")
+        };
+
+        table.add_row(row![
+            name,
+            format_money(comparison.interest_saved),
+            format_money(comparison.prepay_net),
+            format_money(comparison.save_net),
+            better,
+            comparison
+                .crossover_yield
+                .map(|crossover| format!("{:.2}%", crossover.round_dp(2)))
+                .unwrap_or_else(|| "-".to_string())
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Prepay vs. Save ({} at {}% over {} to {}, net = savings minus loan balance):",
+            &[
+                &format_money(lump_sum),
+                &format!("{:.2}", savings_rate.round_dp(2)),
+                &format_months(horizon),
+                &format_date(calendar.payment_date(horizon)),
+            ],
+        )
+    );
+    print_table(&table);
+    Ok(())
+}
+
+fn get_years_input(
+    prompt: &str,
+    initial: &str,
+    max_years: u32,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let years: u32 = Input::new()
+        .with_prompt(prompt)
+        .with_initial_text(initial)
+        .validate_with(move |input: &u32| {
+            if *input >= 1 && *input <= max_years {
+                Ok(())
+            } else {
+                Err(trf("Must be between 1 and {} years", &[&max_years]))
+            }
+        })
+        .interact_text()?;
+    Ok(years)
+}
+
+fn get_rate_input(prompt: &str, initial: &str) -> Result<Decimal, Box<dyn std::error::Error>> {
+    let rate: f64 = Input::new()
+        .with_prompt(prompt)
+        .with_initial_text(initial)
+        .validate_with(|input: &f64| {
+            if *input >= 0.0 && *input < 100.0 {
+                Ok(())
+            } else {
+                Err(tr("Interest rate must be between 0 and 100"))
+            }
+        })
+        .interact_text()?;
+    Ok(Decimal::from_f64(rate).unwrap())
+}
+
+// Interest-only years, leaving at least one year to amortize
+fn get_interest_only_months(loan_term: u32) -> Result<u32, Box<dyn std::error::Error>> {
+    let max_years = (loan_term / 12 - 1).min(15);
+    let io_years = get_years_input(
+        &trf("Interest-only period (1-{} years)", &[&max_years]),
+        &format!("{}", max_years.min(10)),
+        max_years,
+    )?;
+    Ok(io_years * 12)
+}
+
+// Where in its range a bank lands depends on more than credit, so the
+// midpoint quote is only the likeliest case
+fn print_rate_quotes(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    rate_quotes: &[(String, RateQuotes)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) {
+    let payment = |rate: Decimal| {
+        calculator
+            .calculate_cost(interest_method, loan_amount, rate, loan_term)
+            .map(|cost| format_money(cost.monthly_payment))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Best Rate"),
+        tr("Best Payment"),
+        tr("Midpoint Rate"),
+        tr("Midpoint Payment"),
+        tr("Worst Rate"),
+        tr("Worst Payment")
+    ]);
+    for (name, quotes) in rate_quotes {
+        table.add_row(row![
+            name,
+            format!("{:.2}%", quotes.best.round_dp(2)),
+            payment(quotes.best),
+            format!("{:.2}%", quotes.midpoint.round_dp(2)),
+            payment(quotes.midpoint),
+            format!("{:.2}%", quotes.worst.round_dp(2)),
+            payment(quotes.worst)
+        ]);
+    }
+
+    println!("\n{}", tr("Across Each Bank's Rate Range:"));
+    print_table(&table);
+}
+
+// Percentage points the quoted rates might move before they are locked
+const RATE_OFFSETS: [Decimal; 5] = [dec!(-0.5), dec!(-0.25), dec!(0.25), dec!(0.5), dec!(1.0)];
+
+fn signed_money(amount: Decimal) -> String {
+    if amount > dec!(0) {
+        format!("+{}", format_money(amount))
+    } else {
+        format_money(amount)
+    }
+}
+
+fn print_rate_sensitivity(
+    calculator: &LoanCalculator,
+    interest_method: &InterestMethod,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) {
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Rate Move"),
+        tr("Interest Rate"),
+        tr("Monthly Payment"),
+        tr("Payment Change"),
+        tr("Total Interest"),
+        tr("Interest Change")
+    ]);
+    for (name, rate) in offers {
+        let Some(quoted) = calculator.calculate_cost(interest_method, loan_amount, *rate, loan_term) else {
+            continue;
+        };
+        for offset in RATE_OFFSETS {
+            let moved_rate = *rate + offset;
+            if moved_rate <= dec!(0) {
+                continue;
+            }
+            let Some(cost) = calculator.calculate_cost(interest_method, loan_amount, moved_rate, loan_term) else {
+                continue;
+            };
+            table.add_row(row![
+                name,
+                format!("{:+.2}%", offset),
+                format!("{:.2}%", moved_rate.round_dp(2)),
+                format_money(cost.monthly_payment),
+                signed_money(cost.monthly_payment - quoted.monthly_payment),
+                format_money(cost.total_interest),
+                signed_money(cost.total_interest - quoted.total_interest)
+            ]);
+        }
+    }
+
+    println!("\n{}", tr("If Rates Move Before You Lock:"));
+    print_table(&table);
+}
+
+// The same amount at each bank over the usual terms for the loan type, and
+// the term chosen
+fn print_term_matrix(
+    calculator: &LoanCalculator,
+    loan_type: &LoanType,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) {
+    let mut terms: Vec<u32> = loan_type
+        .get_comparison_terms()
+        .iter()
+        .copied()
+        .chain([loan_term])
+        .collect();
+    terms.sort_unstable();
+    terms.dedup();
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Term"),
+        tr("Monthly Payment"),
+        tr("Total Interest"),
+        tr("Interest vs. Chosen Term"),
+        tr("Paid Off")
+    ]);
+    for (name, rate) in offers {
+        let Some(chosen) = calculator.calculate_cost(&InterestMethod::Amortizing, loan_amount, *rate, loan_term) else {
+            continue;
+        };
+        for term in &terms {
+            let label = if *term == loan_term {
+                trf("{} (chosen)", &[&format_months(*term)])
+            } else {
+                format_months(*term)
+            };
+            let declined = calculator
+                .get_bank(name)
+                .and_then(|bank| bank.check_term(loan_type, *term));
+            let cost = calculator.calculate_cost(&InterestMethod::Amortizing, loan_amount, *rate, *term);
+            match (declined, cost) {
+                (None, Some(cost)) => {
+                    table.add_row(row![
+                        name,
+                        label,
+                        format_money(cost.monthly_payment),
+                        format_money(cost.total_interest),
+                        signed_money(cost.total_interest - chosen.total_interest),
+                        format_date(calendar.payment_date(*term))
+                    ]);
+                }
+                _ => {
+                    table.add_row(row![name, label, tr("Not offered"), "-", "-", "-"]);
+                }
+            }
+        }
+    }
+
+    println!("\n{}", trf("Term Comparison for {}:", &[&format_money(loan_amount)]));
+    print_table(&table);
+}
+
+// Maturity for a balloon, at least a year before the amortization ends
+fn get_balloon_month(loan_term: u32) -> Result<u32, Box<dyn std::error::Error>> {
+    let max_years = loan_term / 12 - 1;
+    let years = get_years_input(
+        &trf("Balloon due after (1-{} years)", &[&max_years]),
+        &format!("{}", max_years.min(7)),
+        max_years,
+    )?;
+    Ok(years * 12)
+}
+
+// Payments amortize over the full term, but the balance left at maturity
+// is due at once
+fn print_balloon_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let balloon_month = get_balloon_month(loan_term)?;
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Monthly Payment"),
+        tr("Balloon Amount"),
+        tr("Interest Before Balloon"),
+        tr("Total Paid")
+    ]);
+    for (name, rate) in offers {
+        let schedule = calculator.generate_balloon_schedule(loan_amount, *rate, loan_term, balloon_month);
+        let total_paid: Decimal = schedule.rows.iter().map(|row| row.payment).sum();
+        table.add_row(row![
+            name,
+            format_money(schedule.payment_in(1)),
+            format_money(schedule.payment_in(balloon_month) - schedule.payment_in(1)),
+            format_money(schedule.total_interest()),
+            format_money(total_paid)
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Balloon due with payment {} ({}), amortized over {}:",
+            &[&balloon_month, &format_date(calendar.payment_date(balloon_month)), &format_months(loan_term)],
+        )
+    );
+    print_table(&table);
+    Ok(())
+}
+
+fn print_interest_only_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let io_months = get_interest_only_months(loan_term)?;
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Interest-Only Payment"),
+        tr("Amortizing Payment After"),
+        tr("Total Interest"),
+        tr("Extra Interest vs. Amortizing")
+    ]);
+    for (name, rate) in offers {
+        let schedule =
+            calculator.generate_interest_only_schedule(loan_amount, *rate, io_months, *rate, loan_term - io_months);
+        let baseline = calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+        table.add_row(row![
+            name,
+            format_money(schedule.payment_in(1)),
+            format_money(schedule.payment_in(io_months + 1)),
+            format_money(schedule.total_interest()),
+            format_money(schedule.total_interest() - baseline.total_interest())
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Interest only for {}, then amortizing over the remaining {}:",
+            &[&format_months(io_months), &format_months(loan_term - io_months)],
+        )
+    );
+    print_table(&table);
+    Ok(())
+}
+
+const ARM_STRUCTURES: [(&str, u32); 4] = [("3/1", 3), ("5/1", 5), ("7/1", 7), ("10/1", 10)];
+
+// Payments under an ARM if the index falls away, holds, or climbs to the
+// caps, against the cheapest fixed-rate offer
+fn print_arm_paths(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let structures: Vec<&str> = ARM_STRUCTURES
+        .iter()
+        .filter(|(_, years)| years * 12 < loan_term)
+        .map(|(name, _)| *name)
+        .collect();
+    if structures.is_empty() {
+        println!("\n{}", tr("The loan term is too short for an adjustable rate."));
+        return Ok(());
+    }
+    let selection = Select::new()
+        .with_prompt(tr("ARM structure (fixed years / years between adjustments)"))
+        .items(&structures)
+        .default(structures.len().min(2) - 1)
+        .interact()?;
+    let (structure, fixed_years) = ARM_STRUCTURES[selection];
+
+    let (fixed_name, fixed_rate) = offers
+        .iter()
+        .min_by_key(|(_, rate)| *rate)
+        .ok_or(tr("No fixed-rate offers to compare against"))?;
+    let terms = ArmTerms {
+        initial_rate: get_rate_input(
+            tr("Initial ARM rate (%)"),
+            &format!("{:.3}", (fixed_rate - dec!(0.5)).max(dec!(0)).round_dp(3)),
+        )?,
+        fixed_months: fixed_years * 12,
+        adjustment_months: 12,
+        margin: get_rate_input(tr("Margin over the index (%)"), "2.75")?,
+        initial_cap: get_rate_input(tr("Initial adjustment cap (%)"), "2")?,
+        periodic_cap: get_rate_input(tr("Periodic adjustment cap (%)"), "2")?,
+        lifetime_cap: get_rate_input(tr("Lifetime cap over the initial rate (%)"), "5")?,
+    };
+    let index = get_rate_input(tr("Index rate today (%)"), "4.0")?;
+    let scenarios = [ArmScenario::BestCase, ArmScenario::Expected { index }, ArmScenario::WorstCase];
+
+    let paths: Vec<Vec<(u32, Decimal)>> =
+        scenarios.iter().map(|scenario| terms.rate_path(scenario, loan_term)).collect();
+    let schedules: Vec<Schedule> = scenarios
+        .iter()
+        .map(|scenario| calculator.generate_arm_schedule(loan_amount, loan_term, &terms, scenario))
+        .collect();
+
+    // One row per stretch of payments over which no path changes rate
+    let mut starts: Vec<u32> = paths.iter().flatten().map(|(start, _)| *start).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    let rate_at = |path: &[(u32, Decimal)], month: u32| {
+        path.iter().rev().find(|(start, _)| *start <= month).map(|(_, rate)| *rate).unwrap_or_default()
+    };
+    starts.dedup_by(|month, previous| paths.iter().all(|path| rate_at(path, *month) == rate_at(path, *previous)));
+
+    let mut table = Table::new();
+    let mut header = vec![tr("Payments").to_string()];
+    header.extend(scenarios.iter().map(|scenario| tr(scenario.get_description()).to_string()));
+    table.add_row(header.into());
+    for (position, start) in starts.iter().enumerate() {
+        let end = starts.get(position + 1).map(|next| next - 1).unwrap_or(loan_term);
+        let mut cells = vec![format!("{}-{}", start, end)];
+        cells.extend(paths.iter().zip(&schedules).map(|(path, schedule)| {
+            format!("{:.3}% / {}", rate_at(path, *start).round_dp(3), format_money(schedule.payment_in(*start)))
+        }));
+        table.add_row(cells.into());
+    }
+    let mut totals = vec![tr("Total interest").to_string()];
+    totals.extend(schedules.iter().map(|schedule| format_money(schedule.total_interest())));
+    table.add_row(totals.into());
+
+    let fixed = calculator.generate_schedule(loan_amount, *fixed_rate, loan_term, &PaymentStrategy::Standard);
+    println!(
+        "\n{}",
+        trf(
+            "{} ARM at {}%, margin {}%, caps {}/{}/{} (rate and payment):",
+            &[
+                &structure,
+                &format!("{:.3}", terms.initial_rate.round_dp(3)),
+                &format!("{:.2}", terms.margin.round_dp(2)),
+                &terms.initial_cap,
+                &terms.periodic_cap,
+                &terms.lifetime_cap,
+            ],
+        )
+    );
+    print_table(&table);
+    println!(
+        "{}",
+        trf(
+            "Fixed at {}% with {}: {} a month, {} in interest.",
+            &[
+                &format!("{:.2}", fixed_rate.round_dp(2)),
+                fixed_name,
+                &format_money(fixed.payment_in(1)),
+                &format_money(fixed.total_interest()),
+            ],
+        )
+    );
+    Ok(())
+}
+
+fn print_blended_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let second_amount = get_money_input(tr("Second loan balance ($)"), "50000")?;
+    let second_rate = get_rate_input(tr("Second loan interest rate (%)"), "8.0")?;
+    let second_term = get_years_input(tr("Second loan term (1-30 years)"), "15", 30)?;
+    let second_schedule = calculator.generate_schedule(
+        second_amount,
+        second_rate,
+        second_term * 12,
+        &PaymentStrategy::Standard,
+    );
+
+    let combined: Vec<CombinedSchedule> = offers
+        .iter()
+        .map(|(_, rate)| {
+            let mut combined = CombinedSchedule::default();
+            combined.add(
+                loan_amount,
+                *rate,
+                calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard),
+            );
+            combined.add(second_amount, second_rate, second_schedule.clone());
+            combined
+        })
+        .collect();
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("First Loan Rate"),
+        tr("Blended Rate"),
+        tr("Combined Payment"),
+        tr("Combined Interest"),
+        tr("Last Payoff")
+    ]);
+    for ((name, rate), combined) in offers.iter().zip(&combined) {
+        table.add_row(row![
+            name,
+            format!("{:.2}%", rate.round_dp(2)),
+            format!("{:.2}%", combined.blended_rate().round_dp(2)),
+            format_money(combined.payment_in(1)),
+            format_money(combined.total_interest()),
+            format_months(combined.num_payments())
+        ]);
+    }
+    println!(
+        "\n{}",
+        trf(
+            "Combined with a {} loan at {}% over {} years:",
+            &[&format_money(second_amount), &format!("{:.2}", second_rate.round_dp(2)), &second_term],
+        )
+    );
+    print_table(&table);
+
+    print_combined_profile(offers, &combined, calendar)
+}
+
+fn print_combined_profile(
+    offers: &[(String, Decimal)],
+    combined: &[CombinedSchedule],
+    calendar: &Calendar,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let names: Vec<&str> = offers.iter().map(|(name, _)| name.as_str()).collect();
+    let selection = Select::new()
+        .with_prompt(tr("Show the combined amortization profile for"))
+        .items(&names)
+        .default(0)
+        .interact()?;
+    let combined = &combined[selection];
+
+    let mut profile = Table::new();
+    profile.add_row(row![tr("Year"), tr("Monthly Payment"), tr("Interest Paid"), tr("Combined Balance")]);
+    for (year, months) in calendar.payment_years(combined.num_payments()) {
+        let interest: Decimal = months.clone().map(|month| combined.interest_in(month)).sum();
+        profile.add_row(row![
+            year,
+            format_money(combined.payment_in(*months.start())),
+            format_money(interest),
+            format_money(combined.balance_after(*months.end()))
+        ]);
+    }
+    println!("\n{}", trf("Combined Amortization Profile ({}):", &[&names[selection]]));
+    print_table(&profile);
+    Ok(())
+}
+
+fn print_heloc_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let heloc_balance = get_money_input(tr("HELOC balance drawn ($)"), "50000")?;
+    let draw_rate = get_rate_input(tr("HELOC rate during the draw period (%)"), "8.5")?;
+    let draw_years = get_years_input(tr("Interest-only draw period (1-20 years)"), "10", 20)?;
+    let repayment_rate = get_rate_input(
+        tr("Expected HELOC rate when repayment starts (%, variable)"),
+        "9.5",
+    )?;
+    let repayment_years = get_years_input(tr("HELOC repayment period (1-30 years)"), "20", 30)?;
+
+    let heloc_schedule = calculator.generate_interest_only_schedule(
+        heloc_balance,
+        draw_rate,
+        draw_years * 12,
+        repayment_rate,
+        repayment_years * 12,
+    );
+
+    let combined: Vec<CombinedSchedule> = offers
+        .iter()
+        .map(|(_, rate)| {
+            let mut combined = CombinedSchedule::default();
+            combined.add(
+                loan_amount,
+                *rate,
+                calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard),
+            );
+            combined.add(heloc_balance, draw_rate, heloc_schedule.clone());
+            combined
+        })
+        .collect();
+
+    let repayment_start = draw_years * 12 + 1;
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Mortgage Payment"),
+        tr("Total (Draw Period)"),
+        tr("Total (HELOC Repayment)"),
+        tr("Peak Monthly"),
+        tr("Combined Interest")
+    ]);
+    for ((name, rate), combined) in offers.iter().zip(&combined) {
+        let mortgage_payment = calculator.calculate_monthly_payment(loan_amount, *rate, loan_term);
+        let peak = (1..=combined.num_payments())
+            .map(|month| combined.payment_in(month))
+            .max()
+            .unwrap_or(dec!(0));
+        table.add_row(row![
+            name,
+            format_money(mortgage_payment),
+            format_money(combined.payment_in(1)),
+            format_money(combined.payment_in(repayment_start)),
+            format_money(peak),
+            format_money(combined.total_interest())
+        ]);
+    }
+    println!(
+        "\n{}",
+        trf(
+            "Mortgage plus {} HELOC ({} years interest-only at {}%, then {} years at {}%):",
+            &[
+                &format_money(heloc_balance),
+                &draw_years,
+                &format!("{:.2}", draw_rate.round_dp(2)),
+                &repayment_years,
+                &format!("{:.2}", repayment_rate.round_dp(2)),
+            ],
+        )
+    );
+    print_table(&table);
+
+    print_combined_profile(offers, &combined, calendar)
+}
+
+fn print_seller_financing_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let seller_rate = get_rate_input(tr("Seller's interest rate (%)"), "7.0")?;
+    let amortization_years = get_years_input(tr("Seller's amortization period (1-40 years)"), "30", 40)?;
+    let balloon_years = get_years_input(
+        &trf("Balloon due after how many years? (1-{})", &[&amortization_years]),
+        &format!("{}", amortization_years.min(5)),
+        amortization_years,
+    )?;
+    let down_payment = get_money_input(
+        tr("Extra down payment the seller requires beyond bank financing ($)"),
+        "0",
+    )?;
+    if down_payment >= loan_amount {
+        println!("{}", tr("The down payment covers the whole amount, so there is nothing to finance."));
+        return Ok(());
+    }
+
+    let balloon_month = balloon_years * 12;
+    let seller_principal = loan_amount - down_payment;
+    let seller_schedule = calculator.generate_balloon_schedule(
+        seller_principal,
+        seller_rate,
+        amortization_years * 12,
+        balloon_month,
+    );
+    let balloon_amount = calculator
+        .generate_schedule(
+            seller_principal,
+            seller_rate,
+            amortization_years * 12,
+            &PaymentStrategy::Standard,
+        )
+        .balance_after(balloon_month);
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Offer"),
+        tr("Rate"),
+        tr("Cash Up Front"),
+        tr("Monthly Payment"),
+        trf("Interest to Year {}", &[&balloon_years]),
+        trf("Owed at Year {}", &[&balloon_years]),
+        trf("Cost to Year {}", &[&balloon_years])
+    ]);
+
+    // Cost to the balloon date counts cash up front and interest paid, since
+    // principal repaid stays with the borrower as equity
+    table.add_row(row![
+        tr("Seller Financing"),
+        format!("{:.2}%", seller_rate.round_dp(2)),
+        format_money(down_payment),
+        format_money(seller_schedule.payment_in(1)),
+        format_money(seller_schedule.interest_through(balloon_month)),
+        format_money(balloon_amount),
+        format_money(down_payment + seller_schedule.interest_through(balloon_month))
+    ]);
+
+    for (name, rate) in offers {
+        let schedule =
+            calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+        table.add_row(row![
+            name,
+            format!("{:.2}%", rate.round_dp(2)),
+            format_money(dec!(0)),
+            format_money(schedule.payment_in(1)),
+            format_money(schedule.interest_through(balloon_month)),
+            format_money(schedule.balance_after(balloon_month)),
+            format_money(schedule.interest_through(balloon_month))
+        ]);
+    }
+
+    println!("\n{}", tr("Seller Financing vs. Bank Offers:"));
+    print_table(&table);
+
+    // The balloon has to be refinanced at whatever rates exist then
+    let remaining_years = amortization_years - balloon_years;
+    if balloon_amount > dec!(0) && remaining_years > 0 {
+        let best_rate = offers.iter().map(|(_, rate)| *rate).min().unwrap_or(seller_rate);
+        let refinance_payment =
+            calculator.calculate_monthly_payment(balloon_amount, best_rate, remaining_years * 12);
+        let stressed_payment =
+            calculator.calculate_monthly_payment(
+                balloon_amount,
+                best_rate + dec!(2),
+                remaining_years * 12,
+            );
+        println!(
+            "{} {}",
+            tr("Warning:").yellow().bold(),
+            trf(
+                "A balloon of {} is due in year {}. Refinancing it over the remaining {} years \
+                 costs {}/month at today's best rate ({}%), or {}/month if rates rise 2 points.",
+                &[
+                    &format_money(balloon_amount),
+                    &balloon_years,
+                    &remaining_years,
+                    &format_money(refinance_payment),
+                    &format!("{:.2}", best_rate.round_dp(2)),
+                    &format_money(stressed_payment),
+                ],
+            )
+        );
+    }
+
+    Ok(())
+}
+
+// When each loan leaves the borrower owing more than the car is worth, to
+// weigh GAP coverage, which pays the difference if the car is totaled
+fn print_equity_timeline(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    vehicle_value: Decimal,
+    curve: &DepreciationCurve,
+    calendar: &Calendar,
+) {
+    let timelines: Vec<(&str, Decimal, EquityTimeline)> = offers
+        .iter()
+        .map(|(name, rate)| {
+            (
+                name.as_str(),
+                *rate,
+                calculator.equity_timeline(loan_amount, *rate, loan_term, vehicle_value, curve),
+            )
+        })
+        .collect();
+    let Some((cheapest, _, best)) = timelines.iter().min_by_key(|(_, rate, _)| *rate) else {
+        return;
+    };
+
+    let mut table = Table::new();
+    table.add_row(row![tr("Bank"), tr("Underwater For"), tr("Most Underwater"), tr("Positive Equity From")]);
+    for (name, _, timeline) in &timelines {
+        table.add_row(row![
+            name,
+            match timeline.underwater_months() {
+                0 => tr("Never").to_string(),
+                months => format_months(months),
+            },
+            timeline
+                .deepest()
+                .map(|month| trf("{} at month {}", &[&format_money(-month.equity()), &month.month]))
+                .unwrap_or_else(|| "-".to_string()),
+            match timeline.positive_from() {
+                Some(month) => trf("Month {} ({})", &[&month, &format_date(calendar.payment_date(month))]),
+                None => tr("The start").to_string(),
+            }
+        ]);
+    }
+    println!(
+        "\n{}",
+        trf(
+            "Negative Equity on a {} car losing {}:",
+            &[&format_money(vehicle_value), &describe_depreciation(curve)],
+        )
+    );
+    print_table(&table);
+
+    let mut years = Table::new();
+    years.add_row(row![tr("Year"), tr("Car Value"), tr("Owed"), tr("Equity")]);
+    for month in best.months.iter().filter(|month| month.month > 0 && month.month % 12 == 0) {
+        years.add_row(row![
+            month.month / 12,
+            format_money(month.value),
+            format_money(month.balance),
+            signed_money(month.equity())
+        ]);
+    }
+    println!("\n{}", trf("Year-End Equity with {}:", &[cheapest]));
+    print_table(&years);
+
+    match (best.deepest(), best.positive_from()) {
+        (Some(deepest), Some(month)) => println!(
+            "{}",
+            trf(
+                "GAP coverage would pay up to {} with {} if the car were totaled before month {}; \
+                 after that it pays nothing.",
+                &[&format_money(-deepest.equity()), cheapest, &month],
+            )
+        ),
+        _ => println!(
+            "{}",
+            trf(
+                "With {} the car is worth more than is owed throughout, so GAP coverage would pay nothing.",
+                &[cheapest],
+            )
+        ),
+    }
+}
+
+// `DepreciationCurve::get_description` in the chosen language
+fn describe_depreciation(curve: &DepreciationCurve) -> String {
+    let rates: Vec<String> = curve.yearly_rates().iter().map(|rate| format!("{}%", rate.normalize())).collect();
+    match rates.split_last() {
+        Some((last, [])) => trf("{} a year", &[last]),
+        Some((last, first)) => trf("{}, then {} a year", &[&first.join(", "), last]),
+        None => String::new(),
+    }
+}
+
+// None, after saying why, when the terms do not make a lease
+fn get_lease(cap_cost: Decimal) -> Result<Option<Lease>, Box<dyn std::error::Error>> {
+    let cap_cost = get_money_input(tr("Capitalized cost, the negotiated price with fees ($)"), &cap_cost.to_string())?;
+    let cap_cost_reduction = get_money_input(tr("Cap cost reduction paid at signing ($)"), "0")?;
+    let residual: String = Input::new()
+        .with_prompt(money_prompt(tr("Residual value ($ or % of the capitalized cost)")))
+        .with_initial_text("55%")
+        .validate_with(|input: &String| {
+            parse_residual(input, cap_cost).map(|_| ()).ok_or(tr("Enter an amount like 18000 or a percent like 55%"))
+        })
+        .interact_text()?;
+    let residual = parse_residual(&residual, cap_cost).ok_or(tr("Residual must be an amount or a percent"))?;
+    let money_factor: f64 = Input::new()
+        .with_prompt(tr("Money factor"))
+        .with_initial_text("0.0025")
+        .validate_with(|input: &f64| {
+            if *input >= 0.0 && *input < 0.01 {
+                Ok(())
+            } else {
+                Err(tr("Money factor must be at least 0 and below 0.01 (rate in % / 2400)"))
+            }
+        })
+        .interact_text()?;
+    let term_months = get_months_input(tr("Lease term (months)"), "36", 60)?;
+    let allowed_per_year: u32 = Input::new()
+        .with_prompt(tr("Miles allowed per year"))
+        .with_initial_text("12000")
+        .interact_text()?;
+    let expected_per_year: u32 = Input::new()
+        .with_prompt(tr("Miles you expect to drive per year"))
+        .with_initial_text(allowed_per_year.to_string())
+        .interact_text()?;
+    let excess_fee = get_money_input(tr("Charge per mile over the allowance ($)"), "0.25")?;
+    let lease = Lease::new(
+        cap_cost,
+        cap_cost_reduction,
+        residual,
+        Decimal::from_f64(money_factor).unwrap(),
+        term_months,
+        Mileage {
+            allowed_per_year,
+            expected_per_year,
+            excess_fee,
+        },
+    );
+    if let Err(e) = &lease {
+        println!("{} {}", tr("Cannot price this lease:").yellow().bold(), e);
+    }
+    Ok(lease.ok())
+}
+
+// Residuals are quoted as a percent of the car's price as often as in dollars
+fn parse_residual(input: &str, cap_cost: Decimal) -> Option<Decimal> {
+    let input = input.trim();
+    match input.strip_suffix('%') {
+        Some(percent) => Decimal::from_str(percent.trim()).ok().map(|percent| (cap_cost * percent / dec!(100)).round_dp(2)),
+        None => Decimal::from_str(&input.trim_start_matches('$').replace(',', "")).ok(),
+    }
+}
+
+// A lease set beside buying the car with each bank's loan. Over the lease's
+// months the buyer keeps a car worth the residual, less what is still owed,
+// where the lessee hands it back.
+fn print_lease_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    purchase: Option<&Purchase>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cash_down = purchase.map(|purchase| purchase.down_payment).unwrap_or_default();
+    let Some(lease) = get_lease(loan_amount + cash_down)? else {
+        return Ok(());
+    };
+    let months = lease.term_months;
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Option"),
+        tr("Monthly Payment"),
+        trf("Paid Over {} Months", &[&months]),
+        trf("Owed at Month {}", &[&months]),
+        trf("Equity at Month {}", &[&months]),
+        tr("Net Cost")
+    ]);
+    table.add_row(row![
+        tr("Lease"),
+        format_money(lease.monthly_payment()),
+        format_money(lease.total_cost()),
+        "-",
+        "-",
+        format_money(lease.total_cost())
+    ]);
+    let mut best: Option<(&str, Decimal)> = None;
+    for (name, rate) in offers {
+        let financed = calculator.finance_over_lease(name, loan_amount, *rate, loan_term, cash_down, &lease);
+        table.add_row(row![
+            name,
+            format_money(calculator.calculate_monthly_payment(loan_amount, *rate, loan_term)),
+            format_money(financed.paid),
+            format_money(financed.balance),
+            signed_money(financed.equity),
+            format_money(financed.net_cost())
+        ]);
+        if best.is_none_or(|(_, cost)| financed.net_cost() < cost) {
+            best = Some((name, financed.net_cost()));
+        }
+    }
+    println!("\n{}", trf("Lease vs. Buying over {}:", &[&format_months(months)]));
+    print_table(&table);
+
+    println!(
+        "{}",
+        trf(
+            "Lease payment: {} depreciation + {} rent charge (money factor {}, about {}% APR)",
+            &[
+                &format_money(lease.depreciation_fee()),
+                &format_money(lease.rent_charge()),
+                &lease.money_factor,
+                &format!("{:.2}", lease.equivalent_rate().round_dp(2)),
+            ],
+        )
+    );
+    if lease.excess_miles() > 0 {
+        println!(
+            "{} {}",
+            tr("Warning:").yellow().bold(),
+            trf(
+                "{} miles over the allowance cost {} at turn-in, counted in the lease's total.",
+                &[&lease.excess_miles(), &format_money(lease.mileage_charge())],
+            )
+        );
+    }
+    if let Some((name, cost)) = best {
+        println!(
+            "{}",
+            trf(
+                "Cheaper over {}: {}",
+                &[&format_months(months), &describe_cheaper(tr("Leasing"), name, cost - lease.total_cost())],
+            )
+        );
+    }
+    Ok(())
+}
+
+// Compares borrowing today, borrowing today and refinancing once the score
+// improves, and waiting to borrow at the better score. Totals include each
+// loan's closing costs, so refinancing pays them twice.
+fn print_credit_trajectory(
+    calculator: &LoanCalculator,
+    loan_type: &LoanType,
+    loan_amount: Decimal,
+    loan_term: u32,
+    credit_score: u16,
+    collateral: &[Collateral],
+) -> Result<(), Box<dyn std::error::Error>> {
+    if loan_term < 2 {
+        println!("\n{}", tr("The loan term is too short to refinance part way through."));
+        return Ok(());
+    }
+    let future_score: u16 = Input::new()
+        .with_prompt(tr("Expected credit score later (300-850)"))
+        .with_initial_text(format!("{}", (credit_score + 60).min(850)))
+        .validate_with(|input: &u16| {
+            if *input >= 300 && *input <= 850 {
+                Ok(())
+            } else {
+                Err(tr("Credit score must be between 300 and 850"))
+            }
+        })
+        .interact_text()?;
+    let months: u32 = Input::new()
+        .with_prompt(trf("Months until you reach that score (1-{})", &[&(loan_term - 1)]))
+        .with_initial_text(format!("{}", (loan_term - 1).min(18)))
+        .validate_with(move |input: &u32| {
+            if *input >= 1 && *input < loan_term {
+                Ok(())
+            } else {
+                Err(tr("The score must improve before the loan is paid off"))
+            }
+        })
+        .interact_text()?;
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Borrow Now"),
+        trf("Refinance After {}", &[&format_months(months)]),
+        trf("Wait {}", &[&format_months(months)]),
+        tr("Cheapest")
+    ]);
+    let format_path = |path: Option<(Decimal, Decimal)>| match path {
+        Some((rate, cost)) => format!("{:.2}% / {}", rate.round_dp(2), format_money(cost)),
+        None => tr("Not eligible").to_string(),
+    };
+
+    for bank in calculator.banks() {
+        if bank.check_term(loan_type, loan_term).is_some()
+            || bank.check_collateral(loan_amount, collateral).is_some()
+        {
+            continue;
+        }
+        let closing_costs = calculator.get_closing_costs(&bank.name, loan_type);
+        let now_rate = (credit_score >= bank.min_credit_score)
+            .then(|| calculator.quote_rate(bank, loan_type, credit_score));
+        let later_rate = (future_score >= bank.min_credit_score)
+            .then(|| calculator.quote_rate(bank, loan_type, future_score));
+
+        let now = now_rate.map(|rate| {
+            let schedule = calculator.generate_schedule(loan_amount, rate, loan_term, &PaymentStrategy::Standard);
+            (rate, loan_amount + schedule.total_interest() + closing_costs)
+        });
+        // Refinancing only helps if the later rate is lower
+        let refinance = now_rate.zip(later_rate).filter(|(rate, new_rate)| new_rate < rate).map(
+            |(rate, new_rate)| {
+                let schedule =
+                    calculator.refinance_later_schedule(loan_amount, rate, loan_term, months, new_rate);
+                (new_rate, loan_amount + schedule.total_interest() + closing_costs * dec!(2))
+            },
+        );
+        let wait = later_rate.map(|rate| {
+            let schedule = calculator.generate_schedule(loan_amount, rate, loan_term, &PaymentStrategy::Standard);
+            (rate, loan_amount + schedule.total_interest() + closing_costs)
+        });
+        if now.is_none() && wait.is_none() {
+            continue;
+        }
+
+        let cheapest = [("Borrow now", now), ("Refinance later", refinance), ("Wait", wait)]
+            .into_iter()
+            .filter_map(|(label, path)| path.map(|(_, cost)| (label, cost)))
+            .min_by_key(|(_, cost)| *cost)
+            .map(|(label, _)| tr(label))
+            .unwrap_or_default();
+        table.add_row(row![
+            bank.name,
+            format_path(now),
+            format_path(refinance),
+            format_path(wait),
+            cheapest
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Credit score {} now, {} in {} (rate / total cost including closing costs):",
+            &[&credit_score, &future_score, &format_months(months)],
+        )
+    );
+    print_table(&table);
+    println!(
+        "{}",
+        trf(
+            "Waiting leaves you without the loan for {}; refinancing pays closing costs twice.",
+            &[&format_months(months)],
+        )
+    );
+    Ok(())
+}
+
+fn print_assumable_comparison(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let assumed_balance = get_money_input(tr("Remaining balance on the seller's mortgage ($)"), "200000")?;
+    let assumed_rate = get_rate_input(tr("Seller's mortgage rate (%)"), "3.0")?;
+    let assumed_years = get_years_input(tr("Years remaining on the seller's mortgage (1-40)"), "25", 40)?;
+
+    let assumed_balance = if assumed_balance > loan_amount {
+        println!(
+            "{}",
+            trf(
+                "The assumable balance exceeds the financing needed; assuming {} only.",
+                &[&format_money(loan_amount)],
+            )
+        );
+        loan_amount
+    } else {
+        assumed_balance
+    };
+
+    let mut assumption = CombinedSchedule::default();
+    assumption.add(
+        assumed_balance,
+        assumed_rate,
+        calculator.generate_schedule(
+            assumed_balance,
+            assumed_rate,
+            assumed_years * 12,
+            &PaymentStrategy::Standard,
+        ),
+    );
+
+    let gap = loan_amount - assumed_balance;
+    let mut gap_description = String::new();
+    if gap > dec!(0) {
+        let gap_rate = get_rate_input(
+            &trf("Rate on a second loan covering the {} gap (%)", &[&format_money(gap)]),
+            "8.5",
+        )?;
+        let gap_years = get_years_input(tr("Second loan term (1-30 years)"), "15", 30)?;
+        assumption.add(
+            gap,
+            gap_rate,
+            calculator.generate_schedule(gap, gap_rate, gap_years * 12, &PaymentStrategy::Standard),
+        );
+        gap_description = trf(
+            " plus {} at {}% over {} years",
+            &[&format_money(gap), &format!("{:.2}", gap_rate.round_dp(2)), &gap_years],
+        );
+    }
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Option"),
+        tr("Monthly Payment"),
+        tr("Blended Rate"),
+        tr("Total Interest"),
+        tr("Last Payoff")
+    ]);
+    table.add_row(row![
+        tr("Assume + Second Loan"),
+        format_money(assumption.payment_in(1)),
+        format!("{:.2}%", assumption.blended_rate().round_dp(2)),
+        format_money(assumption.total_interest()),
+        format_months(assumption.num_payments())
+    ]);
+    for (name, rate) in offers {
+        let schedule =
+            calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+        table.add_row(row![
+            name,
+            format_money(schedule.payment_in(1)),
+            format!("{:.2}%", rate.round_dp(2)),
+            format_money(schedule.total_interest()),
+            format_months(schedule.num_payments())
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Assuming {} at {}% ({} years left){} vs. new financing:",
+            &[
+                &format_money(assumed_balance),
+                &format!("{:.2}", assumed_rate.round_dp(2)),
+                &assumed_years,
+                &gap_description,
+            ],
+        )
+    );
+    print_table(&table);
+    Ok(())
+}
+
+// Every bank side by side at 0, 1 and 2 discount points
+fn print_points_buydown(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) {
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Bank"),
+        tr("Points"),
+        tr("Rate"),
+        tr("Upfront Cost"),
+        tr("Monthly Payment"),
+        tr("Break-even")
+    ]);
+    for (name, rate) in offers {
+        for points in [dec!(0), dec!(1), dec!(2)] {
+            let buydown = calculator.calculate_buydown(name, loan_amount, *rate, loan_term, points);
+            let break_even = match buydown.break_even_month {
+                Some(0) => "-".to_string(),
+                Some(month) => trf("Payment {} ({})", &[&month, &format_months(month)]),
+                None => tr("Never").to_string(),
+            };
+            table.add_row(row![
+                name,
+                buydown.points,
+                format!("{:.3}%", buydown.rate.round_dp(3)),
+                format_money(buydown.upfront_cost),
+                format_money(buydown.monthly_payment),
+                break_even
+            ]);
+        }
+    }
+
+    println!("\n{}", tr("Discount Points Buy-down:"));
+    print_table(&table);
+    let unpriced = offers.iter().any(|(name, _)| {
+        let pricing = calculator.get_points_pricing(name).unwrap_or_default();
+        [dec!(1), dec!(2)].iter().any(|points| !pricing.iter().any(|option| option.points == *points))
+    });
+    if unpriced {
+        println!(
+            "{}",
+            tr("Banks that do not price a number of points are assumed to take 0.25% off the rate per point.")
+        );
+    }
+}
+
+fn print_points_tradeoff(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let priced_offers: Vec<&(String, Decimal)> = offers
+        .iter()
+        .filter(|(name, _)| calculator.get_points_pricing(name).is_some())
+        .collect();
+    let names: Vec<&str> = priced_offers.iter().map(|(name, _)| name.as_str()).collect();
+    let selection = Select::new()
+        .with_prompt(tr("Show the pricing grid for"))
+        .items(&names)
+        .default(0)
+        .interact()?;
+    let (name, rate) = priced_offers[selection];
+    let max_horizon = (loan_term / 12).max(1);
+    let horizon = get_years_input(
+        &trf("How many years do you expect to keep the loan? (1-{})", &[&max_horizon]),
+        &format!("{}", max_horizon.min(7)),
+        max_horizon,
+    )?;
+
+    let mut pricing = calculator.get_points_pricing(name).unwrap_or_default().to_vec();
+    pricing.sort_by_key(|option| option.points);
+    let closing_costs = calculator.get_closing_costs(name, &LoanType::Home);
+    let costs: Vec<_> = pricing
+        .iter()
+        .map(|option| {
+            calculator.calculate_points_cost(
+                loan_amount,
+                *rate,
+                loan_term,
+                option,
+                closing_costs,
+                (horizon * 12).min(loan_term),
+            )
+        })
+        .collect();
+
+    let lowest = costs.iter().map(|cost| cost.cost_at_horizon).min().unwrap_or(dec!(0));
+    let highest = costs.iter().map(|cost| cost.cost_at_horizon).max().unwrap_or(dec!(0));
+
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Points"),
+        tr("Rate"),
+        tr("APR"),
+        tr("Points / Credit"),
+        tr("Cash to Close"),
+        tr("Monthly Payment"),
+        trf("Cost at Year {}", &[&horizon]),
+        ""
+    ]);
+    for (option, cost) in pricing.iter().zip(&costs) {
+        // Bars span the spread between the cheapest and dearest option so
+        // small differences stay visible
+        let bar_width = if highest > lowest {
+            ((cost.cost_at_horizon - lowest) / (highest - lowest) * dec!(30))
+                .round()
+                .to_usize()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let mut bar = "#".repeat(bar_width + 1);
+        if cost.cost_at_horizon == lowest {
+            bar.push_str(&format!(" <- {}", tr("lowest")));
+        }
+        table.add_row(row![
+            format!("{:.2}", option.points.round_dp(2)),
+            format!("{:.3}%", cost.rate.round_dp(3)),
+            cost.apr
+                .map(|apr| format!("{:.3}%", apr.round_dp(3)))
+                .unwrap_or_else(|| "-".to_string()),
+            format_money(cost.points_cost),
+            format_money(cost.cash_to_close),
+            format_money(cost.monthly_payment),
+            format_money(cost.cost_at_horizon),
+            bar
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "{} pricing grid over {} years ({} closing costs; cost = cash to close plus interest paid):",
+            &[name, &horizon, &format_money(closing_costs)],
+        )
+    );
+    print_table(&table);
+    Ok(())
+}
+
+fn get_existing_loan() -> Result<ExistingLoan, Box<dyn std::error::Error>> {
+    let balance = get_money_input(tr("Current mortgage balance ($)"), "250000")?;
+    let annual_rate = get_rate_input(tr("Current mortgage rate (%)"), "7.0")?;
+    let remaining_years = get_years_input(tr("Years remaining on the current mortgage (1-40)"), "25", 40)?;
+    let payments_made: u32 = Input::new()
+        .with_prompt(tr("Monthly payments already made on the current mortgage"))
+        .with_initial_text("60")
+        .interact_text()?;
+    Ok(ExistingLoan {
+        balance,
+        annual_rate,
+        remaining_payments: remaining_years * 12,
+        payments_made,
+    })
+}
+
+// Refinancing the existing balance at each bank's rate, closing costs paid
+// in cash and earned back through the lower payment
+fn print_rate_refinance(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let existing = get_existing_loan()?;
+    let closing_costs = get_money_input(tr("Closing costs on the refinance ($)"), "5000")?;
+    let new_years = get_years_input(
+        tr("New loan term (1-40 years)"),
+        &format!("{}", (existing.remaining_payments / 12).max(1)),
+        40,
+    )?;
+
+    let current = calculator.existing_schedule(&existing);
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Option"),
+        tr("Rate"),
+        tr("Monthly Payment"),
+        tr("Monthly Savings"),
+        tr("Break-even"),
+        tr("Lifetime Interest Change")
+    ]);
+    table.add_row(row![
+        tr("Keep current loan"),
+        format!("{:.2}%", existing.annual_rate.round_dp(2)),
+        format_money(current.payment_in(1)),
+        "-",
+        "-",
+        "-"
+    ]);
+    for (name, rate) in offers {
+        let refinance = calculator.calculate_rate_refinance(&existing, closing_costs, *rate, new_years * 12);
+        let break_even = match refinance.break_even_month {
+            Some(0) => tr("Immediately").to_string(),
+            Some(month) => trf("Payment {} ({})", &[&month, &format_months(month)]),
+            None => tr("Never").to_string(),
+        };
+        table.add_row(row![
+            name,
+            format!("{:.2}%", rate.round_dp(2)),
+            format_money(refinance.monthly_payment),
+            format_money(refinance.monthly_savings),
+            break_even,
+            format_money(refinance.interest_delta)
+        ]);
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Refinance: {} balance at {}% with {} left, {} closing costs paid at closing \
+             (keeping the current loan costs {} in interest):",
+            &[
+                &format_money(existing.balance),
+                &format!("{:.2}", existing.annual_rate.round_dp(2)),
+                &format_months(existing.remaining_payments),
+                &format_money(closing_costs),
+                &format_money(current.total_interest()),
+            ],
+        )
+    );
+    print_table(&table);
+    println!("{}", tr("A negative interest change is interest saved over the life of the new loan."));
+    Ok(())
+}
+
+fn print_cash_out_refinance(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let existing = get_existing_loan()?;
+    let property_value = get_money_input(tr("Current property value ($)"), "400000")?;
+    let cash_out = get_money_input(tr("Cash to take out ($)"), "30000")?;
+    let new_years = get_years_input(tr("New loan term (1-40 years)"), "30", 40)?;
+    let alternative_rate = get_rate_input(
+        tr("Rate on a personal loan or HELOC for the same cash instead (%)"),
+        "9.0",
+    )?;
+    let alternative_years = get_years_input(tr("Alternative loan term (1-40 years)"), "10", 40)?;
+
+    let current = calculator.existing_schedule(&existing);
+    let mut alternative = CombinedSchedule::default();
+    alternative.add(existing.balance, existing.annual_rate, current.clone());
+    alternative.add(
+        cash_out,
+        alternative_rate,
+        calculator.generate_schedule(
+            cash_out,
+            alternative_rate,
+            alternative_years * 12,
+            &PaymentStrategy::Standard,
+        ),
+    );
+
+    let mut table = Table::new();
+    let mut warnings = Vec::new();
+    let mut refinances = Vec::new();
+    table.add_row(row![
+        tr("Option"),
+        tr("Rate"),
+        tr("New Loan"),
+        tr("LTV"),
+        tr("Monthly Payment"),
+        tr("Total Interest"),
+        tr("Interest Added for Cash"),
+        tr("Effective Rate on Cash")
+    ]);
+    table.add_row(row![
+        tr("Keep + Personal/HELOC"),
+        format!("{:.2}% / {:.2}%", existing.annual_rate.round_dp(2), alternative_rate.round_dp(2)),
+        format_money(cash_out),
+        "-",
+        format_money(alternative.payment_in(1)),
+        format_money(alternative.total_interest()),
+        format_money(alternative.total_interest() - current.total_interest()),
+        format!("{:.2}%", alternative_rate.round_dp(2))
+    ]);
+
+    for (name, rate) in offers {
+        let rolled_costs = calculator.get_closing_costs(name, &LoanType::Home);
+        let refinance = calculator.calculate_cash_out_refinance(
+            &existing,
+            cash_out,
+            rolled_costs,
+            *rate,
+            new_years * 12,
+        );
+        let ltv = refinance.new_amount / property_value * dec!(100);
+        if let Some(max_ltv) = calculator.get_bank(name).and_then(|bank| bank.max_cash_out_ltv) {
+            if ltv > max_ltv {
+                warnings.push(trf(
+                    "{} limits cash-out refinances to {}% LTV; this one would be {}%",
+                    &[name, &format!("{:.1}", max_ltv.round_dp(1)), &format!("{:.1}", ltv.round_dp(1))],
+                ));
+                continue;
+            }
+        }
+
+        table.add_row(row![
+            name,
+            format!("{:.2}%", rate.round_dp(2)),
+            format_money(refinance.new_amount),
+            format!("{:.1}%", ltv.round_dp(1)),
+            format_money(refinance.schedule.payment_in(1)),
+            format_money(refinance.schedule.total_interest()),
+            format_money(refinance.schedule.total_interest() - current.total_interest()),
+            refinance
+                .effective_cash_rate
+                .map(|rate| format!("{:.2}%", rate.round_dp(2)))
+                .unwrap_or_else(|| tr("n/a").to_string())
+        ]);
+        refinances.push((name.as_str(), refinance.schedule));
+    }
+
+    println!(
+        "\n{}",
+        trf(
+            "Cash-out refinance: {} balance at {}% + {} cash out, closing costs rolled in \
+             (current loan alone costs {} in interest):",
+            &[
+                &format_money(existing.balance),
+                &format!("{:.2}", existing.annual_rate.round_dp(2)),
+                &format_money(cash_out),
+                &format_money(current.total_interest()),
+            ],
+        )
+    );
+    print_table(&table);
+    println!(
+        "{}",
+        tr("Effective rate is n/a when refinancing also lowers the payment on the existing balance.")
+    );
+    for warning in &warnings {
+        println!("{} {}", tr("Warning:").yellow().bold(), warning);
+    }
+
+    if refinances.is_empty() {
+        return Ok(());
+    }
+    let mut choices: Vec<&str> = refinances.iter().map(|(name, _)| *name).collect();
+    choices.push(tr("Skip"));
+    let selection = Select::new()
+        .with_prompt(tr("Show the full timeline including payments already made for"))
+        .items(&choices)
+        .default(choices.len() - 1)
+        .interact()?;
+    if let Some((name, schedule)) = refinances.get(selection) {
+        print_refinance_timeline(calculator, &existing, name, schedule);
+    }
+    Ok(())
+}
+
+fn print_refinance_timeline(
+    calculator: &LoanCalculator,
+    existing: &ExistingLoan,
+    name: &str,
+    new_loan: &Schedule,
+) {
+    let history = calculator.existing_history(existing);
+    let timeline = refinance::refinance_timeline(&history, new_loan);
+    let current = calculator.existing_schedule(existing);
+    let kept = refinance::refinance_timeline(&history, &current);
+
+    let mut table = Table::new();
+    table.add_row(row![tr("Year"), tr("Loan"), tr("Payments"), tr("Interest"), tr("Ending Balance")]);
+    for year in 1..=timeline.num_payments().div_ceil(12) {
+        let months = (year - 1) * 12 + 1..=year * 12;
+        let loan = if *months.end() <= existing.payments_made {
+            tr("Original")
+        } else if *months.start() > existing.payments_made {
+            tr("Refinanced")
+        } else {
+            tr("Original -> Refinanced")
+        };
+        let payments: Decimal = months.clone().map(|month| timeline.payment_in(month)).sum();
+        let interest: Decimal = months.clone().map(|month| timeline.interest_in(month)).sum();
+        let last_month = (*months.end()).min(timeline.num_payments());
+        table.add_row(row![
+            year,
+            loan,
+            format_money(payments),
+            format_money(interest),
+            format_money(timeline.balance_after(last_month))
+        ]);
+    }
+
+    println!("\n{}", trf("Full Loan Timeline ({}):", &[&name]));
+    print_table(&table);
+    println!(
+        "{}",
+        trf(
+            "Interest before refinancing: {}  After: {}  Whole journey: {} (keeping the original loan: {})",
+            &[
+                &format_money(history.total_interest()),
+                &format_money(new_loan.total_interest()),
+                &format_money(timeline.total_interest()),
+                &format_money(kept.total_interest()),
+            ],
+        )
+    );
+}
+
+fn print_schedule_year(schedule: &Schedule, calendar: &Calendar, year: i32, months: &std::ops::RangeInclusive<u32>) {
+    let mut table = Table::new();
+    table.add_row(row![
+        tr("Payment"),
+        tr("Due Date"),
+        tr("Payment Amount"),
+        tr("Principal"),
+        tr("Interest"),
+        tr("Balance")
+    ]);
+    for month in months.clone() {
+        let row = &schedule.rows[month as usize - 1];
+        table.add_row(row![
+            month,
+            format_date(calendar.payment_date(month)),
+            format_money(row.payment),
+            format_money(row.payment - row.interest),
+            format_money(row.interest),
+            format_money(row.balance)
+        ]);
+    }
+    println!("\n{}", trf("Payments due in {}:", &[&year]));
+    print_table(&table);
+}
+
+fn print_schedule_years(schedule: &Schedule, years: &[(i32, std::ops::RangeInclusive<u32>)]) {
+    let mut table = Table::new();
+    table.add_row(row![tr("Year"), tr("Payments"), tr("Paid"), tr("Principal"), tr("Interest"), tr("Ending Balance")]);
+    for (year, months) in years {
+        let paid: Decimal = months.clone().map(|month| schedule.payment_in(month)).sum();
+        let interest: Decimal = months.clone().map(|month| schedule.interest_in(month)).sum();
+        table.add_row(row![
+            year,
+            months.clone().count(),
+            format_money(paid),
+            format_money(paid - interest),
+            format_money(interest),
+            format_money(schedule.balance_after(*months.end()))
+        ]);
+    }
+    println!("\n{}", tr("Annual Summary:"));
+    print_table(&table);
+}
+
+// Steps through one offer's schedule a year at a time, with jumps and an
+// annual rollup, instead of printing every month at once
+// Roughly, for chart axes: $350k, $1.2M
+fn compact_money(amount: f64) -> String {
+    let number = if amount >= 1_000_000.0 {
+        format!("{:.1}M", amount / 1_000_000.0)
+    } else if amount >= 1_000.0 {
+        format!("{:.0}k", amount / 1_000.0)
+    } else {
+        format!("{:.0}", amount)
+    };
+    money::money_format().with_symbol(&number)
+}
+
+fn print_balance_chart(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let names: Vec<&str> = offers.iter().map(|(name, _)| name.as_str()).collect();
+    let selection = Select::new()
+        .with_prompt(tr("Chart the loan from"))
+        .items(&names)
+        .default(0)
+        .interact()?;
+    let (name, rate) = &offers[selection];
+    let schedule = calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+
+    // Point 0 is closing, before any payment
+    let mut balances = vec![loan_amount.to_f64().unwrap_or(0.0)];
+    let mut interest = vec![0.0];
+    let mut paid_interest = dec!(0);
+    let mut crossover = None;
+    for (index, row) in schedule.rows.iter().enumerate() {
+        paid_interest += row.interest;
+        if crossover.is_none() && paid_interest > row.balance {
+            crossover = Some(index as u32 + 1);
+        }
+        balances.push(row.balance.to_f64().unwrap_or(0.0));
+        interest.push(paid_interest.to_f64().unwrap_or(0.0));
+    }
+
+    let columns = console::Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| columns as usize)
+        .unwrap_or(80);
+    let width = columns.saturating_sub(12).clamp(40, 100);
+    let chart = loancalc::plot::line_chart(
+        &[
+            loancalc::plot::Series {
+                label: tr("Remaining balance"),
+                marker: '*',
+                values: &balances,
+            },
+            loancalc::plot::Series {
+                label: tr("Interest paid so far"),
+                marker: '+',
+                values: &interest,
+            },
+        ],
+        width,
+        18,
+        compact_money,
+        |month| {
+            if month == 0 {
+                tr("Closing").to_string()
+            } else {
+                format_months(month as u32)
+            }
+        },
+    );
+
+    println!(
+        "\n{}",
+        trf("Balance and Interest Over Time ({} at {}%):", &[name, &format!("{:.3}", rate.round_dp(3))])
+    );
+    print!("{}", chart);
+    match crossover {
+        Some(month) => println!(
+            "{}",
+            trf(
+                "Interest paid overtakes the remaining balance with payment {} ({})",
+                &[&month, &format_date(calendar.payment_date(month))],
+            )
+        ),
+        None => println!("{}", tr("Interest paid stays below the remaining balance until the final payment")),
+    }
+    println!(
+        "{}",
+        trf(
+            "Total interest: {} over {}",
+            &[&format_money(schedule.total_interest()), &format_months(schedule.num_payments())],
+        )
+    );
+    Ok(())
+}
+
+fn browse_schedule(
+    calculator: &LoanCalculator,
+    offers: &[(String, Decimal)],
+    loan_amount: Decimal,
+    loan_term: u32,
+    calendar: &Calendar,
+) -> Result<Schedule, Box<dyn std::error::Error>> {
+    let names: Vec<&str> = offers.iter().map(|(name, _)| name.as_str()).collect();
+    let selection = Select::new()
+        .with_prompt(tr("Browse the schedule for"))
+        .items(&names)
+        .default(0)
+        .interact()?;
+    let (_, rate) = &offers[selection];
+    let mut payments = vec![tr("Scheduled payments"), tr("With extra principal each month")];
+    // At least a year of amortizing payments has to follow the interest-only period
+    if loan_term >= 24 {
+        payments.push(tr("Interest-only at first"));
+        payments.push(tr("Balloon before the end of the term"));
+    }
+    let baseline = calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+    let schedule = match Select::new()
+        .with_prompt(tr("Payments"))
+        .items(&payments)
+        .default(0)
+        .interact()?
+    {
+        0 => baseline,
+        1 => {
+            let strategy = PaymentStrategy::ExtraMonthly {
+                amount: get_money_input(tr("Extra principal with each payment ($)"), "100")?,
+            };
+            let schedule = calculator.generate_schedule(loan_amount, *rate, loan_term, &strategy);
+            println!(
+                "\n{}",
+                trf(
+                    "Paid off in {} instead of {}, saving {} in interest.",
+                    &[
+                        &format_months(schedule.num_payments()),
+                        &format_months(baseline.num_payments()),
+                        &format_money(baseline.total_interest() - schedule.total_interest()),
+                    ],
+                )
+            );
+            schedule
+        }
+        3 => {
+            let balloon_month = get_balloon_month(loan_term)?;
+            let schedule = calculator.generate_balloon_schedule(loan_amount, *rate, loan_term, balloon_month);
+            println!(
+                "\n{}",
+                trf(
+                    "{} balloon due with payment {} ({}), after {} in interest.",
+                    &[
+                        &format_money(schedule.payment_in(balloon_month) - schedule.payment_in(1)),
+                        &balloon_month,
+                        &format_date(calendar.payment_date(balloon_month)),
+                        &format_money(schedule.total_interest()),
+                    ],
+                )
+            );
+            schedule
+        }
+        _ => {
+            let io_months = get_interest_only_months(loan_term)?;
+            let schedule =
+                calculator.generate_interest_only_schedule(loan_amount, *rate, io_months, *rate, loan_term - io_months);
+            println!(
+                "\n{}",
+                trf(
+                    "Interest only at {} a month for {}, then {} a month: {} in interest over the whole loan, \
+                     {} more than paying it down from the start.",
+                    &[
+                        &format_money(schedule.payment_in(1)),
+                        &format_months(io_months),
+                        &format_money(schedule.payment_in(io_months + 1)),
+                        &format_money(schedule.total_interest()),
+                        &format_money(schedule.total_interest() - baseline.total_interest()),
+                    ],
+                )
+            );
+            schedule
+        }
+    };
+    let years = calendar.payment_years(schedule.num_payments());
+
+    let mut current = 0;
+    let mut annual = false;
+    loop {
+        if annual {
+            print_schedule_years(&schedule, &years);
+        } else {
+            let (year, months) = &years[current];
+            print_schedule_year(&schedule, calendar, *year, months);
+        }
+
+        let options = [
+            tr("Next year"),
+            tr("Previous year"),
+            tr("Jump to year"),
+            tr("Find when the balance drops below an amount"),
+            if annual { tr("Show monthly payments") } else { tr("Show annual summary") },
+            tr("Back"),
+        ];
+        let choice = Select::new()
+            .with_prompt(trf("{} schedule", &[&names[selection]]))
+            .items(&options)
+            .default(0)
+            .interact()?;
+        match choice {
+            0 => {
+                current = (current + 1).min(years.len() - 1);
+                annual = false;
+            }
+            1 => {
+                current = current.saturating_sub(1);
+                annual = false;
+            }
+            2 => {
+                let (first, last) = (years[0].0, years[years.len() - 1].0);
+                let year: i32 = Input::new()
+                    .with_prompt(trf("Year ({}-{})", &[&first, &last]))
+                    .with_initial_text(format!("{}", years[current].0))
+                    .validate_with(|input: &i32| {
+                        if *input >= first && *input <= last {
+                            Ok(())
+                        } else {
+                            Err(tr("Year must fall within the loan"))
+                        }
+                    })
+                    .interact_text()?;
+                current = years.iter().position(|(y, _)| *y == year).unwrap_or(current);
+                annual = false;
+            }
+            3 => {
+                let threshold = get_money_input(tr("Balance threshold ($)"), &format!("{}", (loan_amount / dec!(2)).round()))?;
+                match schedule.rows.iter().position(|row| row.balance < threshold) {
+                    Some(index) => {
+                        let month = index as u32 + 1;
+                        println!(
+                            "\n{}",
+                            trf(
+                                "The balance drops below {} with payment {} due {}.",
+                                &[&format_money(threshold), &month, &format_date(calendar.payment_date(month))],
+                            )
+                        );
+                        current = years.iter().position(|(_, months)| months.contains(&month)).unwrap_or(current);
+                        annual = false;
+                    }
+                    None => println!("\n{}", trf("The balance never drops below {}.", &[&format_money(threshold)])),
+                }
+            }
+            4 => annual = !annual,
+            _ => break,
+        }
+    }
+    Ok(schedule)
+}
+
+enum ExportFormat {
+    #[cfg(feature = "arrow")]
+    Arrow,
+    #[cfg(feature = "parquet")]
+    Parquet,
+    #[cfg(feature = "chart")]
+    Chart { path: String },
+    Application { path: String },
+    Disclosure { path: String },
+    Report { format: ReportFormat, path: String },
+    #[cfg(feature = "pdf")]
+    Pdf { path: String },
+}
+
+// `--export <format>` writes the comparison and schedules alongside the
+// interactive output. `--export chart <path>` draws them to an SVG or PNG, and
+// `--export application <path>` hands the entered details to other systems.
+// `--export disclosure <path>` writes the Truth in Lending disclosures as text.
+// `--export md [path]` and `--export html [path]` write a shareable report,
+// and `--export pdf <path>` the same with the full schedule.
+fn get_export_format(args: Option<&[String]>) -> Result<Option<ExportFormat>, Box<dyn std::error::Error>> {
+    let Some(args) = args else {
+        return Ok(None);
+    };
+
+    match args.first().map(String::as_str) {
+        #[cfg(feature = "arrow")]
+        Some("arrow") => Ok(Some(ExportFormat::Arrow)),
+        #[cfg(not(feature = "arrow"))]
+        Some("arrow") => Err("Arrow export requires building with `--features arrow`".into()),
+        #[cfg(feature = "parquet")]
+        Some("parquet") => Ok(Some(ExportFormat::Parquet)),
+        #[cfg(not(feature = "parquet"))]
+        Some("parquet") => Err("Parquet export requires building with `--features parquet`".into()),
+        #[cfg(feature = "chart")]
+        Some("chart") => {
+            let path = args.get(1).ok_or("--export chart needs an output path (.svg or .png)")?;
+            Ok(Some(ExportFormat::Chart { path: path.clone() }))
+        }
+        #[cfg(not(feature = "chart"))]
+        Some("chart") => Err("Chart export requires building with `--features chart`".into()),
+        Some("application") => {
+            let path = args.get(1).ok_or("--export application needs an output path (.json or .xml)")?;
+            Ok(Some(ExportFormat::Application { path: path.clone() }))
+        }
+        Some("disclosure") => {
+            let path = args.get(1).ok_or("--export disclosure needs an output path")?;
+            Ok(Some(ExportFormat::Disclosure { path: path.clone() }))
+        }
+        Some("md" | "markdown") => Ok(Some(ExportFormat::Report {
+            format: ReportFormat::Markdown,
+            path: args.get(1).cloned().unwrap_or_else(|| "loans.md".to_string()),
+        })),
+        Some("html") => Ok(Some(ExportFormat::Report {
+            format: ReportFormat::Html,
+            path: args.get(1).cloned().unwrap_or_else(|| "loans.html".to_string()),
+        })),
+        #[cfg(feature = "pdf")]
+        Some("pdf") => {
+            let path = args.get(1).ok_or("--export pdf needs an output path")?;
+            Ok(Some(ExportFormat::Pdf { path: path.clone() }))
+        }
+        #[cfg(not(feature = "pdf"))]
+        Some("pdf") => Err("PDF export requires building with `--features pdf`".into()),
+        Some(other) => Err(format!(
+            "Unknown export format '{}' (expected arrow, parquet, chart, application, disclosure, md, html or pdf)",
+            other
+        )
+        .into()),
+        None => Err("--export needs a format (arrow, parquet, chart, application, disclosure, md, html or pdf)".into()),
+    }
+}
+
+// `--columns rate,apr,payment,total_interest,total_cost,fees,rating,balance`
+// picks the comparison table columns
+fn get_columns(names: Option<&str>) -> Result<Vec<Column>, Box<dyn std::error::Error>> {
+    let Some(names) = names else {
+        return Ok(DEFAULT_COLUMNS.to_vec());
+    };
+    names
+        .split(',')
+        .map(|name| {
+            Column::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown column '{}' (expected rate, apr, payment, total_interest, total_cost, fees, rating or balance)",
+                    name
+                )
+                .into()
+            })
+        })
+        .collect()
+}
+
+fn pick_columns(current: &[Column]) -> Result<Vec<Column>, Box<dyn std::error::Error>> {
+    let labels: Vec<&str> = ALL_COLUMNS.iter().map(Column::get_description).collect();
+    let defaults: Vec<bool> = ALL_COLUMNS.iter().map(|column| current.contains(column)).collect();
+    let selections = MultiSelect::new()
+        .with_prompt(tr("Columns to show (space to toggle, enter to confirm)"))
+        .items(&labels)
+        .defaults(&defaults)
+        .interact()?;
+    Ok(selections.into_iter().map(|index| ALL_COLUMNS[index]).collect())
+}
+
+// `--date-format iso|us|dmy` overrides the date order taken from the locale
+fn get_date_format(name: Option<&str>) -> Result<DateFormat, Box<dyn std::error::Error>> {
+    if let Some(name) = name {
+        return DateFormat::from_name(name)
+            .ok_or_else(|| format!("Unknown date format '{}' (expected iso, us or dmy)", name).into());
+    }
+
+    let locale = ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    Ok(DateFormat::from_locale(&locale))
+}
+
+fn record_history(comparison: serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+    let path = history::default_path().ok_or("no home directory to keep the history in")?;
+    history::append(&path, &HistoryEntry::new(comparison))?;
+    Ok(())
+}
+
+fn open_history() -> Result<Vec<HistoryEntry>, Box<dyn std::error::Error>> {
+    let path = history::default_path().ok_or("no home directory to keep the history in")?;
+    let entries = history::read(&path)?;
+    if entries.is_empty() {
+        println!("{}", trf("No comparisons recorded in {} yet", &[&path.display()]));
+    }
+    Ok(entries)
+}
+
+// Amounts in the history are strings to the cent, as in `--output json`
+fn history_money(value: &serde_json::Value) -> String {
+    value
+        .as_str()
+        .and_then(|amount| Decimal::from_str(amount).ok())
+        .map(format_money)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn history_rate(value: &serde_json::Value) -> String {
+    value
+        .as_str()
+        .and_then(|rate| Decimal::from_str(rate).ok())
+        .map(|rate| format!("{:.2}%", rate.round_dp(2)))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn describe_run(entry: &HistoryEntry) -> String {
+    let loan = &entry.comparison["loan"];
+    trf(
+        "{} loan of {}, {}, credit score {}",
+        &[
+            &loan["type"].as_str().unwrap_or("unknown"),
+            &history_money(&loan["amount"]),
+            &loan["term_months"].as_u64().map(|months| format_months(months as u32)).unwrap_or_default(),
+            &loan["credit_score"],
+        ],
+    )
+}
+
+// When a run was recorded, to the minute, in the local date format
+fn recorded_at(entry: &HistoryEntry) -> String {
+    chrono::DateTime::parse_from_rfc3339(&entry.recorded_at)
+        .map(|time| format!("{} {}", format_date(time.date_naive()), time.format("%H:%M")))
+        .unwrap_or_else(|_| entry.recorded_at.clone())
+}
+
+fn list_history() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = open_history()?;
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table.set_titles(row![tr("Run"), tr("Recorded"), tr("Loan"), tr("Recommended")]);
+    for (index, entry) in entries.iter().enumerate().rev() {
+        table.add_row(row![
+            index + 1,
+            recorded_at(entry),
+            describe_run(entry),
+            entry.comparison["recommended"].as_str().unwrap_or("-")
+        ]);
+    }
+    print_table(&table);
+    println!("{}", tr("Open one with loancalc history show RUN"));
+    Ok(())
+}
+
+// The comparison as it came out at the time, whatever the banks quote now
+fn print_run(entry: &HistoryEntry) {
+    let comparison = &entry.comparison;
+    let loan = &comparison["loan"];
+    println!("\n{}", trf("Recorded {}: {}", &[&recorded_at(entry), &describe_run(entry)]));
+    if let Some(method) = loan["interest_method"].as_str() {
+        println!("{}", trf("Interest Method: {}", &[&method]));
+    }
+    if let Some(price) = loan["purchase_price"].as_str() {
+        println!(
+            "{}",
+            trf(
+                "Purchase Price: {} with {} down",
+                &[&history_money(&serde_json::Value::from(price)), &history_money(&loan["down_payment"])],
+            )
+        );
+    }
+
+    let banks = comparison["banks"].as_array().cloned().unwrap_or_default();
+    let mut table = Table::new();
+    table.set_titles(row![
+        tr("Bank"),
+        tr("Rate"),
+        tr("APR"),
+        tr("Monthly Payment"),
+        tr("Total Interest"),
+        tr("Total Payment")
+    ]);
+    for bank in banks.iter().filter(|bank| bank["monthly_payment"].is_string()) {
+        table.add_row(row![
+            bank["bank"].as_str().unwrap_or_default(),
+            history_rate(&bank["rate"]),
+            history_rate(&bank["apr"]),
+            history_money(&bank["monthly_payment"]),
+            history_money(&bank["total_interest"]),
+            history_money(&bank["total_payment"])
+        ]);
+    }
+    println!("\n{}", tr("Comparison of Options:"));
+    print_table(&table);
+
+    for bank in banks.iter().filter(|bank| bank["reason"].is_string()) {
+        println!(
+            "{} {} {}",
+            tr("Declined:"),
+            bank["bank"].as_str().unwrap_or_default(),
+            bank["reason"].as_str().unwrap_or_default()
+        );
+    }
+    for warning in comparison["warnings"].as_array().into_iter().flatten() {
+        println!("{} {}", tr("Warning:").yellow().bold(), warning.as_str().unwrap_or_default());
+    }
+    if let Some(recommended) = comparison["recommended"].as_str() {
+        println!("\n{}", trf("Recommended: {}", &[&recommended]));
+    }
+    if let Some(checksum) = comparison["provenance"]["config_sha256"].as_str() {
+        println!(
+            "{}",
+            trf(
+                "Rates as of {}, banks.yaml sha256 {}",
+                &[
+                    &comparison["provenance"]["rates_as_of"].as_str().unwrap_or("unknown"),
+                    &&checksum[..checksum.len().min(12)],
+                ],
+            )
+        );
+    }
+}
+
+fn show_run(run: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = open_history()?;
+    let entry = run
+        .checked_sub(1)
+        .and_then(|index| entries.get(index))
+        .ok_or_else(|| trf("No run {} in the history (see loancalc history list)", &[&run]))?;
+    print_run(entry);
+    Ok(())
+}
+
+// Newest first, reopening runs until Done
+fn browse_history() -> Result<(), Box<dyn std::error::Error>> {
+    let entries = open_history()?;
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut labels: Vec<String> = entries
+        .iter()
+        .rev()
+        .map(|entry| format!("{}  {}", recorded_at(entry), describe_run(entry)))
+        .collect();
+    labels.push(tr("Done").to_string());
+    loop {
+        let selection = Select::new()
+            .with_prompt(format!("\n{}", tr("Open a past comparison")))
+            .items(&labels)
+            .default(0)
+            .interact()?;
+        if selection == entries.len() {
+            return Ok(());
+        }
+        print_run(&entries[entries.len() - 1 - selection]);
+    }
+}
+
+fn open_scenarios() -> Result<ScenarioStore, Box<dyn std::error::Error>> {
+    let path = ScenarioStore::default_path().ok_or("no home directory to keep scenarios in")?;
+    Ok(ScenarioStore::open(&path)?)
+}
+
+fn describe_scenario(scenario: &Scenario) -> String {
+    let principal = match (scenario.price, scenario.amount) {
+        (Some(price), _) => trf(
+            "{} purchase price",
+            &[&format_money(Decimal::from_f64(price).unwrap_or_default())],
+        ),
+        (None, Some(amount)) => format_money(Decimal::from_f64(amount).unwrap_or_default()),
+        (None, None) => tr("no amount").to_string(),
+    };
+    trf(
+        "{} loan of {}, {}, credit score {}",
+        &[&scenario.loan_type, &principal, &format_months(scenario.term_months), &scenario.credit_score],
+    )
+}
+
+fn list_scenarios() -> Result<(), Box<dyn std::error::Error>> {
+    let store = open_scenarios()?;
+    if store.scenarios().is_empty() {
+        println!("{}", trf("No saved scenarios in {}", &[&store.path().display()]));
+        println!("{}", tr("Save one with --save-scenario NAME, or from the menu after a comparison."));
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table.set_titles(row![tr("Name"), tr("Loan"), tr("Saved")]);
+    for scenario in store.scenarios() {
+        table.add_row(row![
+            scenario.name,
+            describe_scenario(scenario),
+            scenario.saved_at.format("%Y-%m-%d %H:%M")
+        ]);
+    }
+    print_table(&table);
+    println!("{}", tr("Run one again with loancalc --scenario NAME"));
+    Ok(())
+}
+
+fn delete_scenario(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = open_scenarios()?;
+    if !store.remove(name)? {
+        return Err(trf("No saved scenario named '{}'", &[&name]).into());
+    }
+    println!("{}", trf("Deleted scenario {}", &[&name]));
+    Ok(())
+}
+
+// A scenario priced at the offer the calculator would recommend today
+struct ScenarioQuote {
+    bank: String,
+    rate: Decimal,
+    schedule: Schedule,
+}
+
+// Zero for whatever a scenario leaves out, which is all of it for a home
+fn scenario_vehicle_deal(scenario: &Scenario) -> VehicleDeal {
+    let amount = |value: Option<f64>| value.and_then(Decimal::from_f64).unwrap_or_default();
+    VehicleDeal {
+        sales_tax_rate: amount(scenario.sales_tax_rate),
+        trade_in_value: amount(scenario.trade_in_value),
+        trade_in_payoff: amount(scenario.trade_in_payoff),
+        dealer_fees: amount(scenario.dealer_fees),
+    }
+}
+
+fn quote_scenario(calculator: &LoanCalculator, scenario: &Scenario) -> Result<ScenarioQuote, String> {
+    let loan_type = LoanType::from_name(&scenario.loan_type)
+        .ok_or_else(|| trf("{} has an unknown loan type '{}'", &[&scenario.name, &scenario.loan_type]))?;
+    let amount = match (scenario.price, scenario.down_payment, scenario.amount) {
+        (Some(price), down_payment, _) => price - down_payment.unwrap_or(0.0),
+        (None, _, Some(amount)) => amount,
+        (None, _, None) => return Err(trf("{} has no loan amount", &[&scenario.name])),
+    };
+    let amount = Decimal::from_f64(amount).ok_or_else(|| trf("{} has no usable loan amount", &[&scenario.name]))?;
+    let amount = match scenario.price.and_then(Decimal::from_f64) {
+        Some(price) => amount + scenario_vehicle_deal(scenario).added_to_loan(price),
+        None => amount,
+    };
+    let offers: Vec<(String, Decimal)> = calculator
+        .banks()
+        .iter()
+        .filter(|bank| scenario.credit_score >= bank.min_credit_score)
+        .filter(|bank| bank.check_term(&loan_type, scenario.term_months).is_none())
+        .map(|bank| (bank.name.clone(), calculator.quote_rate(bank, &loan_type, scenario.credit_score)))
+        .collect();
+    let best = calculator
+        .rank_offers(
+            &InterestMethod::Amortizing,
+            &loan_type,
+            &offers,
+            amount,
+            scenario.term_months,
+            calculator.ranking(),
+        )
+        .into_iter()
+        .next()
+        .ok_or_else(|| trf("No bank offers {} at a credit score of {}", &[&scenario.name, &scenario.credit_score]))?;
+    let rate = offers
+        .iter()
+        .find(|(name, _)| *name == best.name)
+        .map(|(_, rate)| *rate)
+        .unwrap_or_default();
+    let prepayments: Vec<Prepayment> = scenario
+        .prepayments
+        .iter()
+        .filter_map(|prepayment| {
+            Some(Prepayment {
+                amount: Decimal::from_f64(prepayment.amount)?,
+                month: prepayment.month,
+            })
+        })
+        .collect();
+    Ok(ScenarioQuote {
+        schedule: calculator.generate_prepaid_schedule(amount, rate, scenario.term_months, &prepayments),
+        bank: best.name,
+        rate,
+    })
+}
+
+// Which scenario wins by how much, on a figure where lower is better
+fn describe_cheaper(first: &str, second: &str, difference: Decimal) -> String {
+    match difference {
+        difference if difference > dec!(0) => trf("{} by {}", &[&first, &format_money(difference)]),
+        difference if difference < dec!(0) => trf("{} by {}", &[&second, &format_money(-difference)]),
+        _ => tr("Neither; they are the same").to_string(),
+    }
+}
+
+// Both priced with the closing and payments a scripted run assumes: today,
+// due on the 1st
+fn compare_scenarios(calculator: &LoanCalculator, first: &str, second: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let store = open_scenarios()?;
+    let find = |name: &str| {
+        store
+            .get(name)
+            .ok_or_else(|| trf("No saved scenario named '{}' (see loancalc scenarios list)", &[&name]))
+    };
+    let (first, second) = (find(first)?, find(second)?);
+    let (a, b) = (quote_scenario(calculator, first)?, quote_scenario(calculator, second)?);
+    let calendar = Closing {
+        date: chrono::Local::now().date_naive(),
+        payment_day: PaymentDay::Day(1),
+        holidays: None,
+        stub_interest: StubInterest::Prepaid,
+    }
+    .calendar();
+
+    let total_paid = |quote: &ScenarioQuote| quote.schedule.rows.iter().map(|row| row.payment).sum::<Decimal>();
+    let payoff = |quote: &ScenarioQuote| {
+        let months = quote.schedule.num_payments();
+        format!("{} ({})", format_date(calendar.payment_date(months)), format_months(months))
+    };
+    let months_apart = b.schedule.num_payments() as i64 - a.schedule.num_payments() as i64;
+
+    let mut table = Table::new();
+    table.set_titles(row!["", first.name, second.name, tr("Difference")]);
+    table.add_row(row![tr("Loan"), describe_scenario(first), describe_scenario(second), ""]);
+    table.add_row(row![
+        tr("Best Offer"),
+        trf("{} at {}%", &[&a.bank, &format!("{:.3}", a.rate.round_dp(3))]),
+        trf("{} at {}%", &[&b.bank, &format!("{:.3}", b.rate.round_dp(3))]),
+        format!("{:+.3}%", b.rate - a.rate)
+    ]);
+    for (label, value_a, value_b) in [
+        ("Monthly Payment", a.schedule.payment_in(1), b.schedule.payment_in(1)),
+        ("Total Interest", a.schedule.total_interest(), b.schedule.total_interest()),
+        ("Total Paid", total_paid(&a), total_paid(&b)),
+    ] {
+        table.add_row(row![
+            tr(label),
+            format_money(value_a),
+            format_money(value_b),
+            signed_money(value_b - value_a)
+        ]);
+    }
+    table.add_row(row![
+        tr("Paid Off"),
+        payoff(&a),
+        payoff(&b),
+        match months_apart {
+            0 => tr("Same month").to_string(),
+            months if months > 0 => trf("{} later", &[&format_months(months as u32)]),
+            months => trf("{} sooner", &[&format_months(months.unsigned_abs() as u32)]),
+        }
+    ]);
+
+    println!(
+        "\n{}",
+        trf(
+            "{} vs. {} (Difference is {} less {}):",
+            &[&first.name, &second.name, &second.name, &first.name],
+        )
+    );
+    print_table(&table);
+    println!(
+        "{} {}",
+        tr("Lower monthly payment:").green().bold(),
+        describe_cheaper(&first.name, &second.name, b.schedule.payment_in(1) - a.schedule.payment_in(1))
+    );
+    println!(
+        "{} {}",
+        tr("Cheaper over the life of the loan:").green().bold(),
+        describe_cheaper(&first.name, &second.name, total_paid(&b) - total_paid(&a))
+    );
+    Ok(())
+}
+
+fn get_scenario_name(store: &ScenarioStore) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let name: String = Input::new()
+        .with_prompt(tr("Name for this scenario"))
+        .validate_with(|input: &String| {
+            if input.trim().is_empty() {
+                Err(tr("Enter a name"))
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()?;
+    let name = name.trim().to_string();
+    if store.get(&name).is_some() {
+        let replace = Select::new()
+            .with_prompt(trf("A scenario named '{}' is already saved", &[&name]))
+            .items(&[tr("Replace it"), tr("Keep it")])
+            .default(1)
+            .interact()?;
+        if replace != 0 {
+            return Ok(None);
+        }
+    }
+    Ok(Some(name))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Analysis {
+    Strategies,
+    PrepayVsSave,
+    Prepayments,
+    Blended,
+    CreditTrajectory,
+    Ranking,
+    Disclosure,
+    TaxDeduction,
+    Heloc,
+    SellerFinancing,
+    Lease,
+    Equity,
+    SaveScenario,
+    Assumable,
+    Points,
+    Buydown,
+    Arm,
+    InterestOnly,
+    Balloon,
+    Terms,
+    BalanceChart,
+    Sensitivity,
+    Refinance,
+    CashOutRefinance,
+    Schedule,
+    Columns,
+    SortFilter,
+    Csv,
+    Done,
+}
+
+fn load_calculator(cli: &cli::Cli, source: &ConfigSource) -> Result<LoanCalculator, Box<dyn std::error::Error>> {
+    let mut calculator = LoanCalculator::load(source)?;
+    if let Some(compounding) = cli.compounding {
+        calculator.set_compounding(compounding);
+    }
+    // Every command that compares loans loads the calculator first, so the
+    // currency is settled here, and amounts bill in its minor units
+    let mut format = get_money_format(cli, &calculator)?;
+    format.rounding = calculator.rounding().mode;
+    calculator.set_currency(format.currency);
+    money::set_money_format(format);
+    Ok(calculator)
+}
+
+// `--currency` over the banks.yaml currency, written with the separators of
+// `--locale` or else the environment's
+fn get_money_format(cli: &cli::Cli, calculator: &LoanCalculator) -> Result<MoneyFormat, Box<dyn std::error::Error>> {
+    let currency = match &cli.currency {
+        Some(code) => Currency::from_code(code).ok_or_else(|| {
+            format!("Unknown currency '{}' (expected one of {})", code, Currency::codes().join(", "))
+        })?,
+        None => calculator.currency().unwrap_or_default(),
+    };
+    let locale = cli.locale.clone().unwrap_or_else(|| {
+        ["LC_ALL", "LC_MONETARY", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+    });
+    Ok(MoneyFormat::from_locale(currency, &locale))
+}
+
+// Fills in the inputs the flags left out from stdin, which holds either a
+// JSON object with the fields of a saved scenario or one answer per line,
+// so the comparison can run in a pipeline without prompting
+fn read_piped_inputs(cli: &mut cli::Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    if input.trim_start().starts_with('{') {
+        let mut value: serde_json::Value = serde_json::from_str(&input).map_err(|e| format!("stdin: {}", e))?;
+        // Only needed once a scenario is saved
+        if let Some(fields) = value.as_object_mut() {
+            fields.entry("name").or_insert_with(|| "stdin".into());
+            if !fields.contains_key("saved_at") {
+                fields.insert("saved_at".into(), serde_json::to_value(chrono::Local::now().naive_local())?);
+            }
+        }
+        let scenario: Scenario = serde_json::from_value(value).map_err(|e| format!("stdin: {}", e))?;
+        cli.apply_scenario(&scenario).map_err(|e| format!("stdin: {}", e))?;
+    } else {
+        let answers = input.lines().map(str::trim).filter(|line| !line.is_empty());
+        cli.apply_answers(answers).map_err(|e| format!("stdin: {}", e))?;
+    }
+    let missing = cli.missing_inputs();
+    if !missing.is_empty() {
+        return Err(format!("stdin is not a terminal, so {} must be given to run without prompting", missing.join(", ")).into());
+    }
+    Ok(())
+}
+
+// Errors print as their message, not the Debug form a `main` returning them
+// would show
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{} {}", "error:".red().bold(), e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cli = cli::Cli::parse();
+    i18n::set_language(cli.lang.as_deref())?;
+    let config_source = config::resolve_config(cli.config.as_deref());
+    match &cli.command {
+        Some(cli::Command::Config {
+            command: cli::ConfigCommand::Lint { path },
+        }) => {
+            let source = path.as_ref().map(|path| ConfigSource::File(path.into())).unwrap_or(config_source);
+            return lint::run(&source);
+        }
+        Some(cli::Command::Banks { command }) => {
+            return match command {
+                None | Some(cli::BanksCommand::List) => banks::list(&config_source),
+                Some(cli::BanksCommand::Add) => banks::add(&config_source),
+                Some(cli::BanksCommand::Edit { name }) => banks::edit(&config_source, name.as_deref()),
+                Some(cli::BanksCommand::Remove { name }) => banks::remove(&config_source, name.as_deref()),
+            }
+        }
+        Some(cli::Command::History { command }) => {
+            return match command {
+                None => browse_history(),
+                Some(cli::HistoryCommand::List) => list_history(),
+                Some(cli::HistoryCommand::Show { run }) => show_run(*run),
+            }
+        }
+        Some(cli::Command::Scenarios { command }) => {
+            return match command {
+                None | Some(cli::ScenariosCommand::List) => list_scenarios(),
+                Some(cli::ScenariosCommand::Delete { name }) => delete_scenario(name),
+                Some(cli::ScenariosCommand::Compare { first, second }) => {
+                    compare_scenarios(&load_calculator(&cli, &config_source)?, first, second)
+                }
+            }
+        }
+        Some(cli::Command::Data { command }) => {
+            let path = packs::config_path(cli.config.as_deref())?;
+            return match command {
+                cli::DataCommand::List => packs::list(&path),
+                cli::DataCommand::Install { pack } => packs::install(&path, pack),
+                cli::DataCommand::Update => packs::update(&path),
+            };
+        }
+        Some(cli::Command::Batch { path, format }) => {
+            return batch::run(load_calculator(&cli, &config_source)?, path, *format)
+        }
+        Some(cli::Command::Debts { path, debts, budget }) => {
+            // Only for the currency the amounts are in
+            load_calculator(&cli, &config_source)?;
+            return debts::run(path.as_deref(), debts, *budget);
+        }
+        Some(cli::Command::RentVsBuy(args)) => return rent::run(load_calculator(&cli, &config_source)?, args),
+        #[cfg(feature = "serve")]
+        Some(cli::Command::Serve { listen }) => return serve::run(load_calculator(&cli, &config_source)?, listen),
+        #[cfg(not(feature = "serve"))]
+        Some(cli::Command::Serve { .. }) => return Err("loancalc serve requires building with `--features serve`".into()),
+        None => {}
+    }
+    if let Some(name) = cli.scenario.clone() {
+        let store = open_scenarios()?;
+        let scenario = store
+            .get(&name)
+            .ok_or_else(|| format!("No saved scenario named '{}' (see loancalc scenarios list)", name))?
+            .clone();
+        cli.apply_scenario(&scenario).map_err(|e| format!("Scenario {}: {}", name, e))?;
+    }
+    if !cli.is_scripted() && !std::io::stdin().is_terminal() {
+        read_piped_inputs(&mut cli)?;
+    }
+
+    let export_format = get_export_format(cli.export.as_deref())?;
+    let date_format = get_date_format(cli.date_format.as_deref())?;
+    let template_path = cli.template.clone();
+    let mut columns = get_columns(cli.columns.as_deref())?;
+    let _ = DATE_FORMAT.set(date_format);
+    let _ = USE_PAGER.set(!cli.no_pager);
+    let scripted = cli.is_scripted();
+    let json_output = cli.output == cli::OutputFormat::Json;
+    let mut calculator = load_calculator(&cli, &config_source)?;
+    let mut warnings = Vec::new();
+    calculator.use_market_rates(cli.refresh_rates, &mut warnings);
+
+    // Select loan type
+    let loan_type = match &cli.loan_type {
+        Some(loan_type) => loan_type.clone(),
+        None => {
+            let loan_types = vec![tr("Home Loan"), tr("Car Loan"), tr("Personal Loan"), tr("Student Loan")];
+            let loan_type_selection = Select::new()
+                .with_prompt(tr("Select loan type"))
+                .items(&loan_types)
+                .default(0)
+                .interact()?;
+
+            match loan_type_selection {
+                0 => LoanType::Home,
+                1 => LoanType::Car,
+                2 => LoanType::Personal,
+                3 => LoanType::Student,
+                _ => unreachable!(),
+            }
+        }
+    };
+
+    // Get loan details with validation, prompting for whatever the command
+    // line left out
+    let entry = match (cli.price, cli.payment) {
+        (Some(price), _) => {
+            let price = Decimal::from_f64(price).ok_or("--price must be a number")?;
+            match cli.down {
+                Some(down_payment) => LoanEntry::Purchase(
+                    check_purchase(&loan_type, price, down_payment).map_err(|e| format!("--price: {}", e))?,
+                ),
+                None => LoanEntry::Purchase(get_down_payment(&loan_type, price)?),
+            }
+        }
+        (None, Some(payment)) => {
+            check_payment(payment).map_err(|e| format!("--payment: {}", e))?;
+            LoanEntry::Payment(Decimal::from_f64(payment).ok_or("--payment must be a number")?)
+        }
+        (None, None) if cli.amount.is_none() => get_loan_entry(&loan_type)?,
+        (None, None) => LoanEntry::Amount,
+    };
+    if let LoanEntry::Payment(payment) = entry {
+        let loan_term = match &cli.term {
+            Some(term) => check_loan_term(&loan_type, term).map_err(|e| format!("--term: {}", e))?,
+            None => get_valid_loan_term(&loan_type)?,
+        };
+        let credit_score = match cli.score {
+            Some(score) => score,
+            None => get_valid_credit_score()?,
+        };
+        for warning in &warnings {
+            eprintln!("{} {}", tr("Warning:").yellow().bold(), warning);
+        }
+        return print_affordability(&calculator, &loan_type, payment, loan_term, credit_score, json_output);
+    }
+    let purchase = match entry {
+        LoanEntry::Purchase(purchase) => Some(purchase),
+        _ => None,
+    };
+    // A car's sales tax, trade-in and fees are financed along with its price
+    let vehicle = match &purchase {
+        _ if cli.has_vehicle_costs() && !matches!(loan_type, LoanType::Car) => {
+            return Err("--sales-tax, --trade-in and --dealer-fees only apply to car loans".into());
+        }
+        Some(_) if cli.has_vehicle_costs() => {
+            if let Some(rate) = cli.sales_tax {
+                check_sales_tax(rate).map_err(|e| format!("--sales-tax: {}", e))?;
+            }
+            Some(VehicleDeal {
+                sales_tax_rate: flag_amount(cli.sales_tax, "--sales-tax")?,
+                trade_in_value: flag_amount(cli.trade_in, "--trade-in")?,
+                trade_in_payoff: flag_amount(cli.trade_in_payoff, "--trade-in-payoff")?,
+                dealer_fees: flag_amount(cli.dealer_fees, "--dealer-fees")?,
+            })
+        }
+        Some(_) if matches!(loan_type, LoanType::Car) && !scripted => get_vehicle_deal()?,
+        _ => None,
+    };
+    let loan_amount = match (&purchase, cli.amount) {
+        (Some(purchase), _) => match &vehicle {
+            Some(deal) => {
+                let amount = deal.amount_financed(purchase);
+                check_loan_amount(&loan_type, amount.to_f64().unwrap_or_default())
+                    .map_err(|e| format!("{} after the sales tax, trade-in and fees", e))?;
+                amount
+            }
+            None => purchase.financed_amount(),
+        },
+        (None, Some(amount)) => {
+            check_loan_amount(&loan_type, amount).map_err(|e| format!("--amount: {}", e))?;
+            Decimal::from_f64(amount).ok_or("--amount must be a number")?
+        }
+        (None, None) => get_valid_loan_amount(&loan_type)?,
+    };
+    let ltv = match (&purchase, &vehicle) {
+        (Some(purchase), Some(deal)) => Some(deal.loan_to_value(purchase)),
+        (Some(purchase), None) => Some(purchase.loan_to_value()),
+        (None, _) => None,
+    };
+    // Mortgage insurance only applies to home purchases
+    let pmi_purchase = purchase.as_ref().filter(|_| matches!(loan_type, LoanType::Home));
+    let loan_term = match &cli.term {
+        Some(term) => check_loan_term(&loan_type, term).map_err(|e| format!("--term: {}", e))?,
+        None => get_valid_loan_term(&loan_type)?,
+    };
+    let credit_score = match cli.score {
+        Some(score) => score,
+        None => get_valid_credit_score()?,
+    };
+    // Runs that say when the loan starts get its payoff laid out on the calendar
+    let show_payoff = !scripted || cli.closing.is_some() || cli.first_payment.is_some();
+    let closing = match (cli.closing, cli.first_payment) {
+        (_, Some(first_payment)) => Closing::for_first_payment(first_payment),
+        (None, None) if !scripted => get_closing(&calculator)?,
+        (date, None) => Closing {
+            date: date.unwrap_or_else(|| chrono::Local::now().date_naive()),
+            payment_day: PaymentDay::Day(1),
+            holidays: None,
+            stub_interest: StubInterest::Prepaid,
+        },
+    };
+    let calendar = closing.calendar();
+    let mut prepayments = cli.prepay.clone();
+    if let Some(prepayment) = prepayments.iter().find(|prepayment| prepayment.month > loan_term) {
+        return Err(format!("--prepay: payment {} comes after the last payment", prepayment.month).into());
+    }
+    let (interest_method, collateral) = if scripted {
+        (calculator.default_interest_method(&loan_type, &calendar), Vec::new())
+    } else {
+        (
+            get_interest_method(loan_term, &calendar, calculator.interest_convention(&loan_type))?,
+            get_collateral()?,
+        )
+    };
+    let frequency = match cli.frequency {
+        Some(frequency) if frequency != PaymentFrequency::Monthly && interest_method != InterestMethod::Amortizing => {
+            return Err("--frequency: only amortizing loans can be paid more often than monthly".into());
+        }
+        Some(frequency) => frequency,
+        None if scripted || interest_method != InterestMethod::Amortizing => PaymentFrequency::Monthly,
+        None => get_payment_frequency()?,
+    };
+    let housing = if !matches!(loan_type, LoanType::Home) {
+        if cli.has_housing_costs() {
+            return Err("--property-tax, --insurance and --hoa only apply to home loans".into());
+        }
+        None
+    } else if cli.has_housing_costs() {
+        Some(HousingCosts {
+            property_tax: flag_amount(cli.property_tax, "--property-tax")?,
+            insurance: flag_amount(cli.insurance, "--insurance")?,
+            hoa_dues: flag_amount(cli.hoa, "--hoa")?,
+        })
+    } else if scripted {
+        None
+    } else {
+        get_housing_costs()?
+    };
+    let deferment = if !matches!(loan_type, LoanType::Student) {
+        if cli.has_deferment() {
+            return Err("--in-school and --grace only apply to student loans".into());
+        }
+        None
+    } else if cli.has_deferment() {
+        Some(Deferment {
+            in_school_months: cli.in_school.unwrap_or(0),
+            grace_months: cli.grace.unwrap_or(DEFAULT_GRACE_MONTHS),
+        })
+    } else if scripted {
+        None
+    } else {
+        get_deferment()?
+    };
+    let income = match cli.income {
+        Some(gross_monthly) if gross_monthly > 0.0 => Some(Income {
+            gross_monthly: flag_amount(Some(gross_monthly), "--income")?,
+            monthly_debts: flag_amount(cli.debts, "--debts")?,
+        }),
+        Some(_) => return Err("--income: income must be greater than 0".into()),
+        None if scripted => None,
+        None => get_income()?,
+    };
+    let tax_rate = match cli.tax_rate {
+        Some(_) if !matches!(loan_type, LoanType::Home) => {
+            return Err("--tax-rate only applies to home loans".into());
+        }
+        Some(rate) if rate > 0.0 && rate < 100.0 => Decimal::from_f64(rate),
+        Some(_) => return Err("--tax-rate: the tax rate must be between 0 and 100".into()),
+        None => None,
+    };
+    let depreciation = match &cli.depreciation {
+        Some(_) if !matches!(loan_type, LoanType::Car) => {
+            return Err("--depreciation only applies to car loans".into());
+        }
+        depreciation => depreciation.clone(),
+    };
+    // Named and stamped when it is saved
+    let scenario = Scenario {
+        name: String::new(),
+        saved_at: chrono::Local::now().naive_local(),
+        loan_type: loan_type.get_name().to_string(),
+        amount: purchase.is_none().then(|| loan_amount.to_f64()).flatten(),
+        price: purchase.as_ref().and_then(|purchase| purchase.price.to_f64()),
+        down_payment: purchase.as_ref().and_then(|purchase| purchase.down_payment.to_f64()),
+        term_months: loan_term,
+        credit_score,
+        payment_frequency: Some(frequency.get_description().to_string()),
+        property_tax: housing.as_ref().and_then(|housing| housing.property_tax.to_f64()),
+        insurance: housing.as_ref().and_then(|housing| housing.insurance.to_f64()),
+        hoa_dues: housing.as_ref().and_then(|housing| housing.hoa_dues.to_f64()),
+        sales_tax_rate: vehicle.as_ref().and_then(|deal| deal.sales_tax_rate.to_f64()),
+        trade_in_value: vehicle.as_ref().and_then(|deal| deal.trade_in_value.to_f64()),
+        trade_in_payoff: vehicle.as_ref().and_then(|deal| deal.trade_in_payoff.to_f64()),
+        dealer_fees: vehicle.as_ref().and_then(|deal| deal.dealer_fees.to_f64()),
+        in_school_months: deferment.as_ref().map(|deferment| deferment.in_school_months),
+        grace_months: deferment.as_ref().map(|deferment| deferment.grace_months),
+        gross_monthly_income: income.as_ref().and_then(|income| income.gross_monthly.to_f64()),
+        monthly_debts: income.as_ref().and_then(|income| income.monthly_debts.to_f64()),
+        prepayments: Vec::new(),
+    };
+    let save_scenario = |name: &str, prepayments: &[Prepayment]| -> Result<(), Box<dyn std::error::Error>> {
+        let mut store = open_scenarios()?;
+        store.save(Scenario {
+            name: name.to_string(),
+            saved_at: chrono::Local::now().naive_local(),
+            prepayments: prepayments
+                .iter()
+                .filter_map(|prepayment| {
+                    Some(ScenarioPrepayment {
+                        amount: prepayment.amount.to_f64()?,
+                        month: prepayment.month,
+                    })
+                })
+                .collect(),
+            ..scenario.clone()
+        })?;
+        let line = trf("Saved scenario {} to {}", &[&name, &store.path().display()]);
+        if json_output {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+        Ok(())
+    };
+
+    let mut has_qualifying_banks = false;
+    let mut offers = Vec::new();
+
+    let live_quotes = calculator.fetch_live_quotes(
+        &QuoteRequest {
+            loan_type: loan_type.get_name(),
+            amount: loan_amount,
+            term_months: loan_term,
+            credit_score,
+        },
+        &mut warnings,
+    );
+    let mut declined = Vec::new();
+
+    for bank in calculator.banks() {
+        // Skip if credit score is too low
+        if credit_score < bank.min_credit_score {
+            declined.push((
+                bank.name.clone(),
+                trf("requires a credit score of at least {}", &[&bank.min_credit_score]),
+            ));
+            continue;
+        }
+
+        if let Some(reason) = bank.check_term(&loan_type, loan_term) {
+            warnings.push(format!("{} {}", bank.name, reason));
+            declined.push((bank.name.clone(), reason));
+            continue;
+        }
+
+        if let Some(reason) = bank.check_collateral(loan_amount, &collateral) {
+            warnings.push(format!("{} {}", bank.name, reason));
+            declined.push((bank.name.clone(), reason));
+            continue;
+        }
+
+        if let Some(warning) = ltv.and_then(|ltv| bank.check_ltv(&loan_type, ltv)) {
+            warnings.push(format!("{} {}", bank.name, warning));
+        }
+
+        has_qualifying_banks = true;
+
+        let adjusted_rate = match live_quotes.get(&bank.name) {
+            Some(Some(rate)) => {
+                if !json_output {
+                    println!(
+                        "{}",
+                        trf(
+                            "{}: live quote of {}% from {}",
+                            &[
+                                &bank.name,
+                                &format!("{:.3}", rate.round_dp(3)),
+                                &calculator.quote_source(&bank.name).unwrap_or(tr("its quote API")),
+                            ],
+                        )
+                    );
+                }
+                calculator.monthly_rate(&bank.name, *rate)
+            }
+            Some(None) => {
+                warnings.push(format!("{} {}", bank.name, tr("declined to quote this loan")));
+                declined.push((bank.name.clone(), tr("declined to quote this loan").to_string()));
+                continue;
+            }
+            None => calculator.quote_rate(bank, &loan_type, credit_score),
+        };
+        
+        let cost = calculator.calculate_cost(
+            &interest_method,
+            loan_amount,
+            adjusted_rate,
+            loan_term,
+        );
+        let dti = cost.as_ref().zip(income.as_ref()).and_then(|(cost, income)| {
+            let extras = TableExtras {
+                prepayments: &[],
+                pmi_purchase,
+                housing: housing.as_ref(),
+                income: Some(income),
+                loan_type: &loan_type,
+            };
+            extras.dti(&calculator, &interest_method, &bank.name, adjusted_rate, loan_term, cost.monthly_payment)
+        });
+        if let Some(problem) = dti.and_then(|dti| dti.problem()) {
+            warnings.push(format!("{} {}", bank.name, problem));
+        }
+        add_offer_warnings(&mut warnings, &bank.name, adjusted_rate, cost);
+        offers.push((bank.name.clone(), adjusted_rate));
+    }
+
+    // Offers priced from a bank's range can also show either end of it
+    let rate_quotes: Vec<(String, RateQuotes)> = if cli.rates.unwrap_or(calculator.rate_display()) == RateDisplay::Range {
+        offers
+            .iter()
+            .filter(|(name, _)| !live_quotes.contains_key(name))
+            .filter_map(|(name, _)| calculator.get_bank(name))
+            .map(|bank| (bank.name.clone(), calculator.quote_rate_range(bank, &loan_type, credit_score)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if !has_qualifying_banks && !json_output {
+        println!("\n{}", trf("No banks available for credit score {}.", &[&credit_score]));
+        println!("{}", trf("Minimum required credit score is {}.", &[&calculator.get_min_credit_score()]));
+        println!("{}", tr("Consider using a custom interest rate to estimate payments."));
+    }
+
+    // Option for custom rate
+    let custom_rate_selection = if scripted {
+        1
+    } else {
+        eprintln!("\n{}", tr("Would you like to calculate with a custom interest rate?"));
+        let custom_rate_options = vec![tr("Yes"), tr("No")];
+        Select::new()
+            .items(&custom_rate_options)
+            .default(1)
+            .interact()?
+    };
+
+    if custom_rate_selection == 0 {
+        let custom_rate: f64 = Input::new()
+            .with_prompt(tr("Enter custom interest rate (%)"))
+            .validate_with(|input: &f64| {
+                if *input > 0.0 && *input < 100.0 {
+                    Ok(())
+                } else {
+                    Err(tr("Interest rate must be between 0 and 100"))
+                }
+            })
+            .interact_text()?;
+        let custom_rate = calculator.monthly_rate("Custom Rate", Decimal::from_f64(custom_rate).unwrap());
+
+        let cost = calculator.calculate_cost(
+            &interest_method,
+            loan_amount,
+            custom_rate,
+            loan_term,
+        );
+        add_offer_warnings(&mut warnings, "Custom Rate", custom_rate, cost);
+        offers.push(("Custom Rate".to_string(), custom_rate));
+    }
+
+    let mut offer_view = OfferView::from_cli(&cli)?;
+    let all_offers = offers.clone();
+    let mut filtered = Vec::new();
+    let offers = offer_view.apply(&calculator, &interest_method, &all_offers, loan_amount, loan_term, &mut filtered);
+
+    if !json_output {
+        // Print loan details
+        println!("\n{}", tr("Loan Details:"));
+        println!("{}", trf("Amount: {}", &[&format_money(loan_amount)]));
+        if let Some(purchase) = &purchase {
+            println!(
+                "{}",
+                trf(
+                    "Purchase Price: {} with {} down ({}%), LTV {}%",
+                    &[
+                        &format_money(purchase.price),
+                        &format_money(purchase.down_payment),
+                        &format!("{:.1}", purchase.down_payment_percent().round_dp(1)),
+                        &format!("{:.1}", ltv.unwrap_or_default().round_dp(1)),
+                    ],
+                )
+            );
+        }
+        if let (Some(purchase), Some(deal)) = (&purchase, &vehicle) {
+            println!(
+                "{}",
+                trf(
+                    "Sales Tax: {} at {}% of {}",
+                    &[
+                        &format_money(deal.sales_tax(purchase.price)),
+                        &deal.sales_tax_rate.normalize(),
+                        &format_money((purchase.price - deal.trade_in_value).max(Decimal::ZERO)),
+                    ],
+                )
+            );
+            if deal.trade_in_value > Decimal::ZERO {
+                println!(
+                    "{}",
+                    trf(
+                        "Trade-In: {} less {} owed, {} equity",
+                        &[
+                            &format_money(deal.trade_in_value),
+                            &format_money(deal.trade_in_payoff),
+                            &signed_money(deal.trade_in_equity()),
+                        ],
+                    )
+                );
+            }
+            println!("{}", trf("Dealer Fees: {}", &[&format_money(deal.dealer_fees)]));
+        }
+        println!("{}", trf("Term: {}", &[&format_months(loan_term)]));
+        println!("{}", trf("Credit Score: {}", &[&credit_score]));
+        println!("{}", trf("Interest Method: {}", &[&describe_interest_method(&interest_method)]));
+        if let Some(market_rate) = calculator.market_rate(&loan_type) {
+            match market_rate.adjustment {
+                Some(adjustment) => println!(
+                    "{}",
+                    trf(
+                        "Market Average: {}, bank ranges moved {} points to match",
+                        &[&describe_market_rate(market_rate), &format!("{:+.2}", adjustment)],
+                    )
+                ),
+                None => println!("{}", trf("Market Average: {}", &[&describe_market_rate(market_rate)])),
+            }
+        }
+        if frequency != PaymentFrequency::Monthly {
+            println!("{}", trf("Payment Frequency: {}", &[&tr(frequency.get_description())]));
+        }
+        println!(
+            "{}",
+            trf(
+                "Closing Date: {} (payments due on {}, first {}, last {})",
+                &[
+                    &format_date(closing.date),
+                    &describe_payment_day(&closing.payment_day),
+                    &format_date(closing.first_payment_date()),
+                    &format_date(calendar.payment_date(loan_term)),
+                ],
+            )
+        );
+        if !collateral.is_empty() {
+            let descriptions: Vec<&str> = collateral.iter().map(|item| item.description.as_str()).collect();
+            println!(
+                "{}",
+                trf(
+                    "Collateral: {} ({}), LTV {}%",
+                    &[
+                        &format_money(collateral::combined_value(&collateral)),
+                        &descriptions.join(", "),
+                        &format!("{:.1}", collateral::loan_to_value(loan_amount, &collateral).round_dp(1)),
+                    ],
+                )
+            );
+        }
+        println!("\n{}", tr("Comparison of Options:"));
+        print_table(&comparison_table(
+            &calculator,
+            &interest_method,
+            &offers,
+            loan_amount,
+            loan_term,
+            &columns,
+            &TableExtras {
+                prepayments: &prepayments,
+                pmi_purchase,
+                housing: housing.as_ref(),
+                income: income.as_ref(),
+                loan_type: &loan_type,
+            },
+        ));
+
+        print_hidden_offers(&filtered);
+        for warning in &warnings {
+            println!("{} {}", tr("Warning:").yellow().bold(), warning);
+        }
+
+        if !rate_quotes.is_empty() {
+            print_rate_quotes(&calculator, &interest_method, &rate_quotes, loan_amount, loan_term);
+        }
+
+        print_housing_costs(
+            &calculator,
+            &interest_method,
+            &offers,
+            loan_amount,
+            loan_term,
+            &TableExtras {
+                prepayments: &prepayments,
+                pmi_purchase,
+                housing: housing.as_ref(),
+                income: income.as_ref(),
+                loan_type: &loan_type,
+            },
+        );
+
+        if let Some(deferment) = &deferment {
+            print_student_repayment(&calculator, &interest_method, &offers, loan_amount, loan_term, deferment, &calendar);
+        }
+
+        if show_payoff {
+            print_payoff_projection(
+                &calculator,
+                &interest_method,
+                &offers,
+                loan_amount,
+                loan_term,
+                &TableExtras {
+                    prepayments: &prepayments,
+                    pmi_purchase,
+                    housing: housing.as_ref(),
+                    income: income.as_ref(),
+                    loan_type: &loan_type,
+                },
+                &calendar,
+            );
+        }
+
+        if let Some(tax_rate) = tax_rate {
+            print_tax_savings(&calculator, &interest_method, &offers, loan_amount, loan_term, &calendar, tax_rate);
+        }
+
+        if let Some(curve) = depreciation.as_ref().filter(|_| interest_method == InterestMethod::Amortizing) {
+            // Without a purchase price the car is taken to be worth the loan
+            let vehicle_value = purchase.as_ref().map(|purchase| purchase.price).unwrap_or(loan_amount);
+            print_equity_timeline(&calculator, &offers, loan_amount, loan_term, vehicle_value, curve, &calendar);
+        }
+
+        print_recommendation(&calculator, &interest_method, &loan_type, &offers, loan_amount, loan_term);
+
+        if interest_method == InterestMethod::Amortizing && !offers.is_empty() {
+            print_frequency_comparison(&calculator, &offers, loan_amount, loan_term, frequency);
+        }
+
+        if !prepayments.is_empty() && interest_method == InterestMethod::Amortizing {
+            print_prepayment_effects(&calculator, &offers, loan_amount, loan_term, &calendar, &prepayments);
+        }
+
+        if closing.stub_days() > 0 && !offers.is_empty() {
+            print_stub_interest(
+                &calculator,
+                &loan_type,
+                &offers,
+                &interest_method,
+                loan_amount,
+                loan_term,
+                &closing,
+            );
+        }
+    }
+
+    let mut inputs = vec![
+        ("loan_type", loan_type.get_name().to_string()),
+        ("loan_amount", format!("{:.2}", loan_amount.round_dp(2))),
+        ("term_months", loan_term.to_string()),
+        ("credit_score", credit_score.to_string()),
+        ("interest_method", interest_method.get_description()),
+        ("payment_frequency", frequency.get_description().to_string()),
+        ("closing_date", closing.date.to_string()),
+        ("payment_day", closing.payment_day.get_description()),
+        ("business_day_roll", closing.holidays.is_some().to_string()),
+        ("stub_interest", closing.stub_interest.get_description().to_string()),
+        ("interest_convention", calculator.interest_convention(&loan_type).get_description().to_string()),
+        ("rounding", calculator.rounding().get_description().to_string()),
+        ("currency", money::money_format().currency.code.to_string()),
+    ];
+    if let Some(tax_rate) = tax_rate {
+        inputs.push(("tax_rate", tax_rate.to_string()));
+    }
+    if let Some(purchase) = &purchase {
+        inputs.push(("purchase_price", format!("{:.2}", purchase.price.round_dp(2))));
+        inputs.push(("down_payment", format!("{:.2}", purchase.down_payment.round_dp(2))));
+    }
+    if let Some(curve) = &depreciation {
+        inputs.push(("depreciation", curve.get_description()));
+    }
+    if let Some(deal) = &vehicle {
+        inputs.push(("sales_tax_rate", deal.sales_tax_rate.to_string()));
+        inputs.push(("trade_in_value", format!("{:.2}", deal.trade_in_value.round_dp(2))));
+        inputs.push(("trade_in_payoff", format!("{:.2}", deal.trade_in_payoff.round_dp(2))));
+        inputs.push(("dealer_fees", format!("{:.2}", deal.dealer_fees.round_dp(2))));
+    }
+    if let Some(housing) = &housing {
+        inputs.push(("property_tax", format!("{:.2}", housing.property_tax.round_dp(2))));
+        inputs.push(("insurance", format!("{:.2}", housing.insurance.round_dp(2))));
+        inputs.push(("hoa_dues", format!("{:.2}", housing.hoa_dues.round_dp(2))));
+    }
+    if !rate_quotes.is_empty() {
+        inputs.push(("rate_display", "range".to_string()));
+    }
+    if let Some(income) = &income {
+        inputs.push(("gross_monthly_income", format!("{:.2}", income.gross_monthly.round_dp(2))));
+        inputs.push(("monthly_debts", format!("{:.2}", income.monthly_debts.round_dp(2))));
+    }
+    if let Some(deferment) = &deferment {
+        inputs.push(("in_school_months", deferment.in_school_months.to_string()));
+        inputs.push(("grace_months", deferment.grace_months.to_string()));
+    }
+    if let Some(market_rate) = calculator.market_rate(&loan_type) {
+        inputs.push(("market_rate", market_rate.get_description()));
+    }
+    let provenance = calculator.provenance(inputs);
+
+    let comparison = results::ComparisonResults {
+        loan_type: &loan_type,
+        amount: loan_amount,
+        purchase: purchase.as_ref(),
+        housing: housing.as_ref(),
+        deferment: deferment.as_ref(),
+        income: income.as_ref(),
+        tax_rate,
+        rate_quotes: &rate_quotes,
+        term_months: loan_term,
+        credit_score,
+        closing: &closing,
+        interest_method: &interest_method,
+        frequency,
+        offers: &offers,
+        declined: &declined,
+        filtered: &filtered,
+        warnings: &warnings,
+        provenance: &provenance,
+    };
+    let document = calculator.comparison_document(&comparison);
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&document)?);
+    }
+    if !cli.no_history {
+        if let Err(e) = record_history(document) {
+            eprintln!(
+                "{} {}",
+                tr("Warning:").yellow().bold(),
+                trf("this run was not added to the history: {}", &[&e])
+            );
+        }
+    }
+
+    // Keep stdout to the JSON document alone when it is requested
+    let status = |line: String| {
+        if json_output {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    };
+
+    let build_report = || {
+        let recommended = calculator
+            .rank_offers(&interest_method, &loan_type, &offers, loan_amount, loan_term, calculator.ranking())
+            .into_iter()
+            .next()
+            .map(|best| best.name);
+        let schedule = recommended
+            .as_ref()
+            .filter(|_| interest_method == InterestMethod::Amortizing)
+            .and_then(|name| offers.iter().find(|(offer, _)| offer == name))
+            .map(|(name, rate)| calculator.report_schedule(name, loan_amount, *rate, loan_term));
+        let mut assumptions = vec![
+            format!(
+                "Rates are estimates from each bank's configured range for a credit score of {}",
+                credit_score
+            ),
+            format!("Interest method: {}", interest_method.get_description()),
+            format!(
+                "{} payments due on {}, the first on {}",
+                frequency.get_description(),
+                closing.payment_day.get_description(),
+                format_date(closing.first_payment_date())
+            ),
+        ];
+        if !live_quotes.is_empty() {
+            assumptions.push("Banks with a quote API were asked for a live quote instead".to_string());
+        }
+        if closing.stub_days() > 0 {
+            assumptions.push(format!(
+                "Interest for the {} days between closing and the first period is {}",
+                closing.stub_days(),
+                closing.stub_interest.get_description()
+            ));
+        }
+        assumptions.push(format!("Calculated with {}", provenance.get_description()));
+        report::Report {
+            loan_type: loan_type.get_name().to_string(),
+            amount: format_money(loan_amount),
+            term: format_months(loan_term),
+            term_months: loan_term,
+            credit_score,
+            interest_method: interest_method.get_description(),
+            closing_date: format_date(closing.date),
+            first_payment_date: format_date(closing.first_payment_date()),
+            offers: calculator.report_offers(&interest_method, &loan_type, &offers, loan_amount, loan_term),
+            schedule,
+            recommended,
+            assumptions,
+            warnings: warnings.clone(),
+            provenance: provenance.clone(),
+        }
+    };
+
+    if let Some(path) = &template_path {
+        println!("\n{}", report::render_template(path, &build_report())?);
+    }
+
+    if let Some(format) = export_format {
+        #[cfg(feature = "arrow")]
+        let data = columnar::ExportData::new(
+            &calculator,
+            interest_method.clone(),
+            &offers,
+            loan_amount,
+            loan_term,
+            provenance.entries(),
+        );
+        match format {
+            #[cfg(feature = "arrow")]
+            ExportFormat::Arrow => {
+                for path in columnar::write_arrow_ipc(&data, "loans")? {
+                    status(trf("Wrote {}", &[&path]));
+                }
+            }
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => {
+                for path in columnar::write_parquet(&data, "loans")? {
+                    status(trf("Wrote {}", &[&path]));
+                }
+            }
+            #[cfg(feature = "chart")]
+            ExportFormat::Chart { path } => {
+                chart::write_charts(
+                    &calculator,
+                    &interest_method,
+                    &offers,
+                    loan_amount,
+                    loan_term,
+                    &provenance.get_description(),
+                    &path,
+                )?;
+                status(trf("Wrote {}", &[&path]));
+            }
+            ExportFormat::Application { path } => {
+                let document = calculator.application_document(&application::Application {
+                    loan_type: &loan_type,
+                    amount: loan_amount,
+                    term_months: loan_term,
+                    credit_score,
+                    closing: &closing,
+                    interest_method: &interest_method,
+                    collateral: &collateral,
+                    offers: &offers,
+                    provenance: &provenance,
+                });
+                application::write_application(&document, &path)?;
+                status(trf("Wrote {}", &[&path]));
+            }
+            ExportFormat::Report { format, path } => {
+                fs::write(&path, report::render_report(format, &build_report())?)?;
+                status(trf("Wrote {}", &[&path]));
+            }
+            #[cfg(feature = "pdf")]
+            ExportFormat::Pdf { path } => {
+                let report = build_report();
+                let schedule = report
+                    .schedule
+                    .as_ref()
+                    .and_then(|summary| offers.iter().find(|(name, _)| *name == summary.bank))
+                    .map(|(_, rate)| calculator.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard));
+                loancalc::pdf::write_report_pdf(&report, schedule.as_ref(), &calendar, date_format, &path)?;
+                status(trf("Wrote {}", &[&path]));
+            }
+            ExportFormat::Disclosure { path } => {
+                let disclosures = disclosure_tables(
+                    &calculator,
+                    &interest_method,
+                    &loan_type,
+                    &offers,
+                    loan_amount,
+                    loan_term,
+                    &calendar,
+                );
+                write_disclosures(disclosures, &provenance, &path)?;
+                status(trf("Wrote {}", &[&path]));
+            }
+        }
+        status(trf("Stamped with {}", &[&provenance.get_description()]));
+    }
+
+    // The schedule last browsed goes out alongside the comparison
+    let write_csv = |path: &str, schedule: Option<&Schedule>| -> Result<(), Box<dyn std::error::Error>> {
+        spreadsheet::write_comparison_csv(&calculator, &comparison, path)?;
+        status(trf("Wrote {}", &[&path]));
+        if let Some(schedule) = schedule {
+            let schedule_path = spreadsheet::schedule_path(path);
+            spreadsheet::write_schedule_csv(schedule, &calendar, &provenance, &schedule_path)?;
+            status(trf("Wrote {}", &[&schedule_path]));
+        }
+        Ok(())
+    };
+    let mut browsed_schedule = None;
+
+    if let Some(name) = &cli.save_scenario {
+        save_scenario(name, &prepayments)?;
+    }
+
+    // Extra payments only change the outcome when they reduce an amortizing balance
+    if !scripted && !json_output && interest_method == InterestMethod::Amortizing && !offers.is_empty() {
+        let mut analyses = vec![
+            (Analysis::Strategies, "Compare payment strategies"),
+            (Analysis::PrepayVsSave, "Prepay the loan vs. save the cash"),
+            (Analysis::Prepayments, "Model lump-sum prepayments"),
+            (Analysis::Blended, "Combine with a second loan (blended rate)"),
+            (Analysis::CreditTrajectory, "Borrow now vs. after your credit improves"),
+            (Analysis::Ranking, "Rank offers by your own priorities"),
+            (Analysis::Disclosure, "Truth in Lending disclosures"),
+        ];
+        if let LoanType::Home = loan_type {
+            analyses.push((Analysis::TaxDeduction, "Mortgage interest deduction (after-tax cost)"));
+            analyses.push((Analysis::Heloc, "Add a HELOC (combined monthly view)"));
+            analyses.push((Analysis::Assumable, "Assume the seller's mortgage"));
+            analyses.push((Analysis::Arm, "Adjustable-rate mortgage payment paths"));
+            if loan_term >= 24 {
+                analyses.push((Analysis::InterestOnly, "Start with an interest-only period"));
+            }
+            analyses.push((Analysis::Refinance, "Refinance an existing mortgage (break-even)"));
+            analyses.push((Analysis::CashOutRefinance, "Cash-out refinance of an existing mortgage"));
+            if offers.iter().any(|(name, _)| calculator.get_points_pricing(name).is_some()) {
+                analyses.push((Analysis::Points, "Points and lender credits tradeoff"));
+            }
+            analyses.push((Analysis::Buydown, "Buy 0, 1 or 2 discount points at each bank"));
+        }
+        if let LoanType::Car = loan_type {
+            analyses.push((Analysis::Lease, "Lease instead of buying (cost over the lease)"));
+            analyses.push((Analysis::Equity, "Negative equity timeline (GAP coverage)"));
+        }
+        if loan_term >= 24 {
+            analyses.push((Analysis::Balloon, "Balloon payment before the end of the term"));
+        }
+        analyses.push((Analysis::Terms, "Compare loan terms side by side"));
+        analyses.push((Analysis::Sensitivity, "Rate sensitivity (if rates move before you lock)"));
+        analyses.push((Analysis::SellerFinancing, "Compare a seller-financing offer"));
+        analyses.push((Analysis::Schedule, "Browse an amortization schedule"));
+        analyses.push((Analysis::BalanceChart, "Chart the balance and interest paid over time"));
+        analyses.push((Analysis::SaveScenario, "Save these inputs as a scenario"));
+        analyses.push((Analysis::Columns, "Choose comparison table columns"));
+        analyses.push((Analysis::SortFilter, "Sort and filter the comparison table"));
+        analyses.push((Analysis::Csv, "Export to CSV"));
+        analyses.push((Analysis::Done, "Done"));
+        let labels: Vec<&str> = analyses.iter().map(|(_, label)| tr(label)).collect();
+
+        loop {
+            let selection = Select::new()
+                .with_prompt(format!("\n{}", tr("Additional analysis")))
+                .items(&labels)
+                .default(labels.len() - 1)
+                .interact()?;
+
+            match analyses[selection].0 {
+                Analysis::Strategies => {
+                    let strategies = get_payment_strategies(&loan_type)?;
+                    print_strategy_comparison(&calculator, &offers, loan_amount, loan_term, &strategies);
+                }
+                Analysis::Prepayments => {
+                    prepayments = get_prepayments(loan_term)?;
+                    println!("\n{}", tr("Comparison of Options:"));
+                    print_table(&comparison_table(
+                        &calculator,
+                        &interest_method,
+                        &offers,
+                        loan_amount,
+                        loan_term,
+                        &columns,
+                        &TableExtras {
+                prepayments: &prepayments,
+                pmi_purchase,
+                housing: housing.as_ref(),
+                income: income.as_ref(),
+                loan_type: &loan_type,
+            },
+                    ));
+                    print_prepayment_effects(&calculator, &offers, loan_amount, loan_term, &calendar, &prepayments);
+                }
+                Analysis::PrepayVsSave => {
+                    print_prepay_comparison(&calculator, &offers, loan_amount, loan_term, &calendar)?
+                }
+                Analysis::Blended => {
+                    print_blended_comparison(&calculator, &offers, loan_amount, loan_term, &calendar)?
+                }
+                Analysis::TaxDeduction => {
+                    let tax_rate: f64 = Input::new()
+                        .with_prompt(tr("Marginal income tax rate (%)"))
+                        .with_initial_text(tax_rate.map(|rate| rate.to_string()).unwrap_or_else(|| "24".to_string()))
+                        .validate_with(|input: &f64| {
+                            if *input > 0.0 && *input < 100.0 {
+                                Ok(())
+                            } else {
+                                Err(tr("Tax rate must be between 0 and 100"))
+                            }
+                        })
+                        .interact_text()?;
+                    let tax_rate = Decimal::from_f64(tax_rate).unwrap();
+                    print_tax_savings(&calculator, &interest_method, &offers, loan_amount, loan_term, &calendar, tax_rate);
+                }
+                Analysis::Heloc => {
+                    print_heloc_comparison(&calculator, &offers, loan_amount, loan_term, &calendar)?
+                }
+                Analysis::SellerFinancing => {
+                    print_seller_financing_comparison(&calculator, &offers, loan_amount, loan_term)?
+                }
+                Analysis::Equity => {
+                    let initial = depreciation.as_ref().unwrap_or(calculator.depreciation()).yearly();
+                    let curve: String = Input::new()
+                        .with_prompt(tr("Yearly depreciation (% of value lost each year, the last repeating)"))
+                        .with_initial_text(initial.iter().map(|rate| rate.normalize().to_string()).collect::<Vec<_>>().join(","))
+                        .validate_with(|input: &String| DepreciationCurve::parse(input).map(|_| ()))
+                        .interact_text()?;
+                    let curve = DepreciationCurve::parse(&curve)?;
+                    let vehicle_value = match &purchase {
+                        Some(purchase) => purchase.price,
+                        None => get_money_input(tr("Car's value today ($)"), &loan_amount.to_string())?,
+                    };
+                    print_equity_timeline(&calculator, &offers, loan_amount, loan_term, vehicle_value, &curve, &calendar);
+                }
+                Analysis::Lease => {
+                    print_lease_comparison(&calculator, &offers, loan_amount, loan_term, purchase.as_ref())?
+                }
+                Analysis::CreditTrajectory => print_credit_trajectory(
+                    &calculator,
+                    &loan_type,
+                    loan_amount,
+                    loan_term,
+                    credit_score,
+                    &collateral,
+                )?,
+                Analysis::Ranking => print_offer_ranking(
+                    &calculator,
+                    &interest_method,
+                    &loan_type,
+                    &offers,
+                    loan_amount,
+                    loan_term,
+                )?,
+                Analysis::Disclosure => print_disclosures(
+                    &calculator,
+                    &interest_method,
+                    &loan_type,
+                    &offers,
+                    loan_amount,
+                    loan_term,
+                    &calendar,
+                ),
+                Analysis::Assumable => {
+                    print_assumable_comparison(&calculator, &offers, loan_amount, loan_term)?
+                }
+                Analysis::Buydown => print_points_buydown(&calculator, &offers, loan_amount, loan_term),
+                Analysis::Points => {
+                    print_points_tradeoff(&calculator, &offers, loan_amount, loan_term)?
+                }
+                Analysis::Arm => print_arm_paths(&calculator, &offers, loan_amount, loan_term)?,
+                Analysis::Balloon => {
+                    print_balloon_comparison(&calculator, &offers, loan_amount, loan_term, &calendar)?
+                }
+                Analysis::Sensitivity => {
+                    print_rate_sensitivity(&calculator, &interest_method, &offers, loan_amount, loan_term)
+                }
+                Analysis::Terms => print_term_matrix(&calculator, &loan_type, &offers, loan_amount, loan_term, &calendar),
+                Analysis::InterestOnly => {
+                    print_interest_only_comparison(&calculator, &offers, loan_amount, loan_term)?
+                }
+                Analysis::Refinance => print_rate_refinance(&calculator, &offers)?,
+                Analysis::CashOutRefinance => print_cash_out_refinance(&calculator, &offers)?,
+                Analysis::BalanceChart => {
+                    print_balance_chart(&calculator, &offers, loan_amount, loan_term, &calendar)?
+                }
+                Analysis::Schedule => {
+                    browsed_schedule = Some(browse_schedule(&calculator, &offers, loan_amount, loan_term, &calendar)?);
+                }
+                Analysis::SaveScenario => {
+                    if let Some(name) = get_scenario_name(&open_scenarios()?)? {
+                        save_scenario(&name, &prepayments)?;
+                    }
+                }
+                Analysis::Csv => {
+                    let path: String = Input::new()
+                        .with_prompt(tr("CSV file for the comparison"))
+                        .with_initial_text("loans.csv")
+                        .interact_text()?;
+                    write_csv(&path, browsed_schedule.as_ref())?;
+                }
+                Analysis::Columns => {
+                    columns = pick_columns(&columns)?;
+                    println!("\n{}", tr("Comparison of Options:"));
+                    print_table(&comparison_table(
+                        &calculator,
+                        &interest_method,
+                        &offers,
+                        loan_amount,
+                        loan_term,
+                        &columns,
+                        &TableExtras {
+                prepayments: &prepayments,
+                pmi_purchase,
+                housing: housing.as_ref(),
+                income: income.as_ref(),
+                loan_type: &loan_type,
+            },
+                    ));
+                }
+                Analysis::SortFilter => {
+                    offer_view = pick_offer_view(&offer_view)?;
+                    let mut hidden = Vec::new();
+                    let shown = offer_view.apply(
+                        &calculator,
+                        &interest_method,
+                        &all_offers,
+                        loan_amount,
+                        loan_term,
+                        &mut hidden,
+                    );
+                    println!("\n{}", tr("Comparison of Options:"));
+                    print_table(&comparison_table(
+                        &calculator,
+                        &interest_method,
+                        &shown,
+                        loan_amount,
+                        loan_term,
+                        &columns,
+                        &TableExtras {
+                            prepayments: &prepayments,
+                            pmi_purchase,
+                            housing: housing.as_ref(),
+                            income: income.as_ref(),
+                            loan_type: &loan_type,
+                        },
+                    ));
+                    print_hidden_offers(&hidden);
+                }
+                Analysis::Done => break,
+            }
+        }
+    }
+
+    if let Some(path) = &cli.export_csv {
+        write_csv(path, browsed_schedule.as_ref())?;
+    }
 
     Ok(())
 }
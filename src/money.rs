@@ -0,0 +1,223 @@
+use std::sync::OnceLock;
+
+use rust_decimal::prelude::*;
+
+use crate::conventions::{Rounding, RoundingMode};
+use crate::LoanCalculator;
+
+/// A currency's symbol and how many decimal places its amounts are shown to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency {
+    pub code: &'static str,
+    pub symbol: &'static str,
+    pub minor_units: u32,
+}
+
+const CURRENCIES: &[Currency] = &[
+    Currency { code: "USD", symbol: "$", minor_units: 2 },
+    Currency { code: "CAD", symbol: "$", minor_units: 2 },
+    Currency { code: "AUD", symbol: "$", minor_units: 2 },
+    Currency { code: "NZD", symbol: "$", minor_units: 2 },
+    Currency { code: "MXN", symbol: "$", minor_units: 2 },
+    Currency { code: "EUR", symbol: "€", minor_units: 2 },
+    Currency { code: "GBP", symbol: "£", minor_units: 2 },
+    Currency { code: "JPY", symbol: "¥", minor_units: 0 },
+    Currency { code: "CNY", symbol: "¥", minor_units: 2 },
+    Currency { code: "KRW", symbol: "₩", minor_units: 0 },
+    Currency { code: "INR", symbol: "₹", minor_units: 2 },
+    Currency { code: "CHF", symbol: "CHF", minor_units: 2 },
+    Currency { code: "SEK", symbol: "kr", minor_units: 2 },
+    Currency { code: "NOK", symbol: "kr", minor_units: 2 },
+    Currency { code: "DKK", symbol: "kr", minor_units: 2 },
+    Currency { code: "PLN", symbol: "zł", minor_units: 2 },
+    Currency { code: "BRL", symbol: "R$", minor_units: 2 },
+    Currency { code: "ZAR", symbol: "R", minor_units: 2 },
+    Currency { code: "KWD", symbol: "KD", minor_units: 3 },
+    Currency { code: "BHD", symbol: "BD", minor_units: 3 },
+];
+
+impl Currency {
+    // ISO 4217 codes like EUR
+    pub fn from_code(code: &str) -> Option<Currency> {
+        CURRENCIES
+            .iter()
+            .find(|currency| currency.code.eq_ignore_ascii_case(code.trim()))
+            .copied()
+    }
+
+    pub fn codes() -> Vec<&'static str> {
+        CURRENCIES.iter().map(|currency| currency.code).collect()
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        CURRENCIES[0]
+    }
+}
+
+/// How amounts are written: the currency, the separators of the locale,
+/// which side of the number the symbol goes and how it rounds to the
+/// currency's minor units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoneyFormat {
+    pub currency: Currency,
+    pub grouping: &'static str,
+    pub decimal: &'static str,
+    pub symbol_first: bool,
+    // A space between the symbol and the number
+    pub spaced: bool,
+    // The banks.yaml `rounding`, so shown amounts match the billed ones
+    pub rounding: RoundingMode,
+}
+
+impl MoneyFormat {
+    // POSIX locale names like de_DE.UTF-8. English, the C locale and most
+    // Asian languages group with commas; much of Europe swaps the two marks
+    // and writes the symbol after the amount.
+    pub fn from_locale(currency: Currency, locale: &str) -> MoneyFormat {
+        let name = locale.split(['.', '@']).next().unwrap_or("");
+        let language = name.split('_').next().unwrap_or("");
+        let (grouping, decimal, symbol_first, spaced) = match (language, name) {
+            (_, "de_CH" | "fr_CH" | "it_CH") => ("’", ".", true, true),
+            ("nl", _) | (_, "pt_BR") => (".", ",", true, true),
+            ("de" | "es" | "it" | "pt" | "da" | "el" | "ro" | "hr" | "sl" | "sr" | "ca" | "tr" | "id", _) => {
+                (".", ",", false, true)
+            }
+            ("fr" | "sv" | "nb" | "nn" | "no" | "fi" | "pl" | "cs" | "sk" | "ru" | "uk" | "bg" | "hu" | "lt" | "lv"
+            | "et", _) => (" ", ",", false, true),
+            _ => (",", ".", true, false),
+        };
+        // Symbols that are letters need the space even where $ does not
+        let spaced = spaced || currency.symbol.chars().all(char::is_alphabetic);
+        MoneyFormat {
+            currency,
+            grouping,
+            decimal,
+            symbol_first,
+            spaced,
+            rounding: RoundingMode::default(),
+        }
+    }
+
+    pub fn format(&self, amount: Decimal) -> String {
+        let rounded = Rounding::new(self.rounding, self.currency.minor_units).round_cents(amount);
+        let digits = format!("{:.*}", self.currency.minor_units as usize, rounded.abs());
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+        let mut number = String::new();
+        for (index, digit) in whole.chars().enumerate() {
+            if index > 0 && (whole.len() - index) % 3 == 0 {
+                number.push_str(self.grouping);
+            }
+            number.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            number.push_str(self.decimal);
+            number.push_str(fraction);
+        }
+        let sign = if rounded < Decimal::ZERO { "-" } else { "" };
+        format!("{}{}", sign, self.with_symbol(&number))
+    }
+
+    // The symbol put beside an already formatted number, such as the 350k
+    // of a chart axis
+    pub fn with_symbol(&self, number: &str) -> String {
+        let space = if self.spaced { " " } else { "" };
+        if self.symbol_first {
+            format!("{}{}{}", self.currency.symbol, space, number)
+        } else {
+            format!("{}{}{}", number, space, self.currency.symbol)
+        }
+    }
+}
+
+impl Default for MoneyFormat {
+    fn default() -> Self {
+        MoneyFormat::from_locale(Currency::default(), "en_US")
+    }
+}
+
+impl LoanCalculator {
+    // The banks.yaml `currency`, if it names one, or the one passed to
+    // `set_currency`
+    pub fn currency(&self) -> Option<Currency> {
+        self.currency
+    }
+
+    // Amounts round to the currency's minor units, so a `--currency` that
+    // overrides banks.yaml changes what the loans bill
+    pub fn set_currency(&mut self, currency: Currency) {
+        self.currency = Some(currency);
+    }
+}
+
+static MONEY_FORMAT: OnceLock<MoneyFormat> = OnceLock::new();
+
+/// Sets how [`crate::format_money`] writes amounts for the rest of the
+/// process. Only the first call takes effect; until then amounts are in US
+/// dollars.
+pub fn set_money_format(format: MoneyFormat) {
+    let _ = MONEY_FORMAT.set(format);
+}
+
+pub fn money_format() -> MoneyFormat {
+    MONEY_FORMAT.get().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::PaymentStrategy;
+    use crate::{BankConfig, LoanType};
+    use rust_decimal_macros::dec;
+
+    fn format(code: &str, locale: &str) -> MoneyFormat {
+        MoneyFormat::from_locale(Currency::from_code(code).unwrap(), locale)
+    }
+
+    #[test]
+    fn amounts_show_the_currency_minor_units() {
+        assert_eq!(format("USD", "en_US").format(dec!(1234567.891)), "$1,234,567.89");
+        assert_eq!(format("JPY", "ja_JP").format(dec!(1234567.5)), "¥1,234,568");
+        assert_eq!(format("KWD", "en_US").format(dec!(1234.5675)), "KD 1,234.568");
+        assert_eq!(format("USD", "en_US").format(dec!(-0.004)), "$0.00");
+    }
+
+    #[test]
+    fn amounts_use_the_locale_separators() {
+        assert_eq!(format("EUR", "de_DE.UTF-8").format(dec!(1234567.5)), "1.234.567,50 €");
+        assert_eq!(format("EUR", "fr_FR").format(dec!(1234.5)), "1 234,50 €");
+        assert_eq!(format("CHF", "de_CH").format(dec!(1234.5)), "CHF 1’234.50");
+        assert_eq!(format("BRL", "pt_BR").format(dec!(-1234.5)), "-R$ 1.234,50");
+    }
+
+    #[test]
+    fn amounts_round_as_configured() {
+        let mut format = format("USD", "en_US");
+        assert_eq!(format.format(dec!(0.125)), "$0.13");
+        format.rounding = RoundingMode::Bankers;
+        assert_eq!(format.format(dec!(0.125)), "$0.12");
+        assert_eq!(format.format(dec!(0.135)), "$0.14");
+    }
+
+    #[test]
+    fn loans_bill_in_the_currency_minor_units() {
+        let bank = BankConfig::new("Bank").rates(&LoanType::Personal, 5.0, 5.0);
+        let calculator = LoanCalculator::builder().bank(bank).currency("JPY").build().unwrap();
+        assert_eq!(calculator.rounding().minor_units, 0);
+        let amortization = crate::exact_amortization(dec!(1000000), dec!(5), 12, calculator.rounding()).unwrap();
+        // 1,000,000 × (5/1200) / (1 − (1 + 5/1200)^−12) = 85,607.48
+        assert_eq!(amortization.payment, dec!(85607));
+        assert_eq!(amortization.final_payment.scale(), 0);
+        let rows: Vec<_> = calculator
+            .schedule_rows(dec!(1000000), dec!(5), 12, &PaymentStrategy::Standard)
+            .collect();
+        // 1,000,000 × 5/1200 = 4,166.67 yen billed as 4,167
+        assert_eq!(rows[0].interest, dec!(4167));
+        assert_eq!(rows[0].balance, dec!(918560));
+        assert_eq!(rows.iter().map(|row| row.payment).sum::<Decimal>() - dec!(1000000), amortization.total_interest);
+    }
+}
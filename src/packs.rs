@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use loancalc::config::{self, ConfigFormat};
+use loancalc::provenance::config_checksum;
+use loancalc::LoanCalculator;
+
+// Packs shipped inside the binary, so `data install` works offline
+const BUNDLED_INDEX: &str = include_str!("../packs/index.yaml");
+const BUNDLED_PACKS: [(&str, &str); 3] = [
+    ("us-midwest.yaml", include_str!("../packs/us-midwest.yaml")),
+    ("us-northeast.yaml", include_str!("../packs/us-northeast.yaml")),
+    ("us-west.yaml", include_str!("../packs/us-west.yaml")),
+];
+
+// `data update` fetches index.yaml and the packs it lists from here;
+// LOANCALC_PACKS_URL points it at a mirror. The index's checksums come from
+// the same server as the packs, so they catch a damaged download but not a
+// server serving something else; HTTPS is what ties both to the host.
+const DEFAULT_PACKS_URL: &str = "https://raw.githubusercontent.com/frgmt0/loans/main/packs";
+
+#[derive(Debug, Deserialize)]
+struct PackIndex {
+    packs: Vec<PackEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackEntry {
+    name: String,
+    description: String,
+    file: String,
+    rates_as_of: NaiveDate,
+    sha256: String,
+}
+
+// The fields of banks.yaml that say which pack it came from
+#[derive(Debug, Default, Deserialize)]
+struct InstalledPack {
+    #[serde(default)]
+    pack: Option<String>,
+    #[serde(default)]
+    rates_as_of: Option<NaiveDate>,
+}
+
+impl PackIndex {
+    fn find(&self, name: &str) -> Result<&PackEntry, Box<dyn std::error::Error>> {
+        self.packs.iter().find(|entry| entry.name == name).ok_or_else(|| {
+            let names: Vec<&str> = self.packs.iter().map(|entry| entry.name.as_str()).collect();
+            format!("No data pack named '{}' (available: {})", name, names.join(", ")).into()
+        })
+    }
+}
+
+// Where packs are installed: the file --config or LOANS_CONFIG names, or
+// banks.yaml in the user config directory, which the config search reads
+// before the working directory
+pub fn config_path(explicit: Option<&Path>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = match explicit {
+        Some(path) => path.to_path_buf(),
+        None => match std::env::var_os(config::CONFIG_ENV).filter(|path| !path.is_empty()) {
+            Some(path) => PathBuf::from(path),
+            None => config::user_config_dir()
+                .ok_or("no home directory to keep the bank config in")?
+                .join(config::CONFIG_FILES[0]),
+        },
+    };
+    if ConfigFormat::from_path(&path) != Some(ConfigFormat::Yaml) {
+        return Err(format!("{}: data packs are YAML and can only be installed as a YAML config", path.display()).into());
+    }
+    Ok(path)
+}
+
+fn installed_pack(path: &Path) -> InstalledPack {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|config_str| serde_yaml::from_str(&config_str).ok())
+        .unwrap_or_default()
+}
+
+// Written only once the checksum matches and the pack loads like a
+// hand-written banks.yaml; the previous file is kept as banks.yaml.bak
+fn activate(path: &Path, entry: &PackEntry, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let checksum = config_checksum(contents);
+    if checksum != entry.sha256 {
+        return Err(format!(
+            "{} failed verification: expected sha256 {}, got {}",
+            entry.file, entry.sha256, checksum
+        )
+        .into());
+    }
+    LoanCalculator::from_config(contents).map_err(|e| format!("{} does not load: {}", entry.file, e))?;
+
+    if path.exists() {
+        let backup = format!("{}.bak", path.display());
+        fs::copy(path, &backup)?;
+        println!("Saved the previous config as {}", backup);
+    }
+    config::write_atomically(path, contents)?;
+    println!(
+        "Installed {} (rates as of {}) as {}",
+        entry.name,
+        entry.rates_as_of,
+        path.display()
+    );
+    Ok(())
+}
+
+pub fn list(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let index: PackIndex = serde_yaml::from_str(BUNDLED_INDEX)?;
+    let installed = installed_pack(path);
+    for entry in &index.packs {
+        let marker = if installed.pack.as_deref() == Some(entry.name.as_str()) {
+            " (installed)"
+        } else {
+            ""
+        };
+        println!(
+            "{:<14} {} (rates as of {}){}",
+            entry.name, entry.description, entry.rates_as_of, marker
+        );
+    }
+    Ok(())
+}
+
+pub fn install(path: &Path, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let index: PackIndex = serde_yaml::from_str(BUNDLED_INDEX)?;
+    let entry = index.find(name)?;
+    let contents = BUNDLED_PACKS
+        .iter()
+        .find(|(file, _)| *file == entry.file)
+        .map(|(_, contents)| *contents)
+        .ok_or_else(|| format!("Data pack {} is listed but not bundled", entry.file))?;
+    activate(path, entry, contents)
+}
+
+// Replaces the installed pack when the published one has newer rates
+pub fn update(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let installed = installed_pack(path);
+    let Some(name) = installed.pack else {
+        return Err(format!(
+            "{} was not installed from a data pack; run `loancalc data install <pack>` first",
+            path.display()
+        )
+        .into());
+    };
+
+    let base_url = std::env::var("LOANCALC_PACKS_URL").unwrap_or_else(|_| DEFAULT_PACKS_URL.to_string());
+    let base_url = base_url.trim_end_matches('/');
+    if !base_url.starts_with("https://") {
+        return Err(format!("{} is not an https:// URL; data packs are only fetched over HTTPS", base_url).into());
+    }
+    let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build();
+    let index: PackIndex =
+        serde_yaml::from_str(&agent.get(&format!("{}/index.yaml", base_url)).call()?.into_string()?)?;
+    let entry = index.find(&name)?;
+    if installed.rates_as_of.is_some_and(|date| date >= entry.rates_as_of) {
+        println!("{} is up to date (rates as of {})", entry.name, entry.rates_as_of);
+        return Ok(());
+    }
+
+    let contents = agent.get(&format!("{}/{}", base_url, entry.file)).call()?.into_string()?;
+    activate(path, entry, &contents)
+}
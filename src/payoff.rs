@@ -0,0 +1,45 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::dates::Calendar;
+use crate::pmi::Pmi;
+use crate::schedule::PaymentStrategy;
+use crate::LoanCalculator;
+
+/// An amortizing loan laid out on the calendar: when it is paid off, when
+/// its mortgage insurance stops and the interest due in each calendar year,
+/// as a tax year counts it.
+#[derive(Debug, Clone)]
+pub struct PayoffProjection {
+    pub payoff_date: NaiveDate,
+    // Due date of the first payment without PMI; None when there is no PMI
+    // or it lasts until the loan is paid off
+    pub pmi_drops: Option<NaiveDate>,
+    // (year, interest due in it)
+    pub interest_by_year: Vec<(i32, Decimal)>,
+}
+
+impl LoanCalculator {
+    pub fn project_payoff(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        calendar: &Calendar,
+        pmi: Option<&Pmi>,
+    ) -> PayoffProjection {
+        let schedule = self.generate_schedule(principal, annual_rate, num_payments, &PaymentStrategy::Standard);
+        let interest_by_year = calendar
+            .payment_years(schedule.num_payments())
+            .into_iter()
+            .map(|(year, months)| (year, months.map(|month| schedule.interest_in(month)).sum()))
+            .collect();
+        PayoffProjection {
+            payoff_date: calendar.payment_date(schedule.num_payments()),
+            pmi_drops: pmi
+                .filter(|pmi| pmi.last_month < schedule.num_payments())
+                .map(|pmi| calendar.payment_date(pmi.last_month + 1)),
+            interest_by_year,
+        }
+    }
+}
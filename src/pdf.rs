@@ -0,0 +1,213 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+use crate::dates::{Calendar, DateFormat};
+use crate::format_money;
+use crate::report::Report;
+use crate::schedule::Schedule;
+
+// US Letter, in millimetres
+const PAGE_WIDTH: f32 = 215.9;
+const PAGE_HEIGHT: f32 = 279.4;
+const MARGIN: f32 = 18.0;
+// Characters of body text that fit across the page
+const TEXT_COLUMNS: usize = 95;
+
+enum Line {
+    Title(String),
+    Heading(String),
+    Text(String),
+    // Courier, so table columns line up
+    Table(String),
+    Blank,
+}
+
+impl Line {
+    fn height(&self) -> f32 {
+        match self {
+            Line::Title(_) => 10.0,
+            Line::Heading(_) => 8.0,
+            Line::Text(_) => 5.0,
+            Line::Table(_) => 4.2,
+            Line::Blank => 3.0,
+        }
+    }
+}
+
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > columns {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// Cut to `width` characters and padded out to it
+fn cell(text: &str, width: usize) -> String {
+    let text: String = text.chars().take(width).collect();
+    format!("{:<width$}", text, width = width)
+}
+
+fn amount_cell(text: &str, width: usize) -> String {
+    format!("{:>width$}", text, width = width)
+}
+
+fn report_lines(report: &Report, schedule: Option<&Schedule>, calendar: &Calendar, date_format: DateFormat) -> Vec<Line> {
+    let mut lines = vec![
+        Line::Title("Loan Comparison".to_string()),
+        Line::Text(format!("Generated {}", report.provenance.generated_at)),
+        Line::Blank,
+        Line::Heading("Loan Details".to_string()),
+    ];
+    for (label, value) in [
+        ("Loan Type", &report.loan_type),
+        ("Amount", &report.amount),
+        ("Term", &report.term),
+        ("Credit Score", &report.credit_score.to_string()),
+        ("Interest Method", &report.interest_method),
+        ("Closing Date", &report.closing_date),
+        ("First Payment", &report.first_payment_date),
+    ] {
+        lines.push(Line::Table(format!("{}{}", cell(label, 18), value)));
+    }
+
+    lines.push(Line::Blank);
+    lines.push(Line::Heading("Comparison".to_string()));
+    lines.push(Line::Table(format!(
+        "{}{}{}{}{}",
+        cell("Bank", 22),
+        cell("Rate", 20),
+        amount_cell("Monthly Payment", 16),
+        amount_cell("Total Interest", 16),
+        amount_cell("Total Payment", 16)
+    )));
+    for offer in &report.offers {
+        lines.push(Line::Table(format!(
+            "{}{}{}{}{}",
+            cell(&offer.bank, 22),
+            cell(&offer.rate, 20),
+            amount_cell(&offer.monthly_payment, 16),
+            amount_cell(&offer.total_interest, 16),
+            amount_cell(&offer.total_payment, 16)
+        )));
+    }
+    if let Some(recommended) = &report.recommended {
+        lines.push(Line::Blank);
+        lines.push(Line::Text(format!("Recommended: {}", recommended)));
+    }
+    for warning in &report.warnings {
+        lines.extend(wrap(&format!("Warning: {}", warning), TEXT_COLUMNS).into_iter().map(Line::Text));
+    }
+
+    if let (Some(schedule), Some(summary)) = (schedule, &report.schedule) {
+        lines.push(Line::Blank);
+        lines.push(Line::Heading(format!("Amortization Schedule ({})", summary.bank)));
+        lines.push(Line::Table(format!(
+            "{}{}{}{}{}{}",
+            amount_cell("No.", 5),
+            amount_cell("Due Date", 13),
+            amount_cell("Payment", 15),
+            amount_cell("Principal", 15),
+            amount_cell("Interest", 15),
+            amount_cell("Balance", 17)
+        )));
+        for (index, row) in schedule.rows.iter().enumerate() {
+            let month = index as u32 + 1;
+            lines.push(Line::Table(format!(
+                "{}{}{}{}{}{}",
+                amount_cell(&month.to_string(), 5),
+                amount_cell(&date_format.format(calendar.payment_date(month)), 13),
+                amount_cell(&format_money(row.payment), 15),
+                amount_cell(&format_money(row.payment - row.interest), 15),
+                amount_cell(&format_money(row.interest), 15),
+                amount_cell(&format_money(row.balance), 17)
+            )));
+        }
+    }
+
+    lines.push(Line::Blank);
+    lines.push(Line::Heading("Assumptions".to_string()));
+    for assumption in &report.assumptions {
+        for (index, text) in wrap(assumption, TEXT_COLUMNS - 2).into_iter().enumerate() {
+            let bullet = if index == 0 { "- " } else { "  " };
+            lines.push(Line::Text(format!("{}{}", bullet, text)));
+        }
+    }
+    lines
+}
+
+struct Fonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    mono: IndirectFontRef,
+}
+
+fn new_page(doc: &PdfDocumentReference) -> PdfLayerReference {
+    let (page, layer) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Report");
+    doc.get_page(page).get_layer(layer)
+}
+
+fn footer(layer: &PdfLayerReference, fonts: &Fonts, page: usize, generated_at: &str) {
+    layer.use_text(
+        format!("Page {}, generated {}", page, generated_at),
+        8.0,
+        Mm(MARGIN),
+        Mm(MARGIN / 2.0),
+        &fonts.regular,
+    );
+}
+
+// Writes the report, and the month-by-month schedule of the recommended
+// offer when there is one, as a PDF. The standard PDF fonts are used, so
+// no font files have to be shipped.
+pub fn write_report_pdf(
+    report: &Report,
+    schedule: Option<&Schedule>,
+    calendar: &Calendar,
+    date_format: DateFormat,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (doc, page, layer) = PdfDocument::new("Loan Comparison", Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Report");
+    let fonts = Fonts {
+        regular: doc.add_builtin_font(BuiltinFont::Helvetica)?,
+        bold: doc.add_builtin_font(BuiltinFont::HelveticaBold)?,
+        mono: doc.add_builtin_font(BuiltinFont::Courier)?,
+    };
+    let generated_at = &report.provenance.generated_at;
+
+    let mut layer = doc.get_page(page).get_layer(layer);
+    let mut page_number = 1;
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for line in report_lines(report, schedule, calendar, date_format) {
+        if y - line.height() < MARGIN {
+            footer(&layer, &fonts, page_number, generated_at);
+            layer = new_page(&doc);
+            page_number += 1;
+            y = PAGE_HEIGHT - MARGIN;
+        }
+        y -= line.height();
+        match &line {
+            Line::Title(text) => layer.use_text(text, 18.0, Mm(MARGIN), Mm(y), &fonts.bold),
+            Line::Heading(text) => layer.use_text(text, 13.0, Mm(MARGIN), Mm(y), &fonts.bold),
+            Line::Text(text) => layer.use_text(text, 10.0, Mm(MARGIN), Mm(y), &fonts.regular),
+            Line::Table(text) => layer.use_text(text, 9.0, Mm(MARGIN), Mm(y), &fonts.mono),
+            Line::Blank => {}
+        }
+    }
+    footer(&layer, &fonts, page_number, generated_at);
+
+    doc.save(&mut BufWriter::new(File::create(path)?))?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+// Line charts drawn with characters, for terminals. `chart` draws images
+// with plotters; this needs nothing beyond the standard library.
+
+pub struct Series<'a> {
+    pub label: &'a str,
+    pub marker: char,
+    pub values: &'a [f64],
+}
+
+// Row of the grid a value lands on, 0 being the top
+fn row_for(value: f64, max: f64, height: usize) -> usize {
+    let fraction = if max > 0.0 { (value / max).clamp(0.0, 1.0) } else { 0.0 };
+    ((1.0 - fraction) * (height - 1) as f64).round() as usize
+}
+
+// Every series shares one y axis from zero to the largest value, labelled by
+// `y_label`. Points are spread evenly across `width` columns; the x axis is
+// labelled at both ends and the middle by `x_label`, given a point index.
+pub fn line_chart(
+    series: &[Series],
+    width: usize,
+    height: usize,
+    y_label: impl Fn(f64) -> String,
+    x_label: impl Fn(usize) -> String,
+) -> String {
+    let width = width.max(10);
+    let height = height.max(3);
+    let points = series.iter().map(|series| series.values.len()).max().unwrap_or(0);
+    if points == 0 {
+        return String::new();
+    }
+    let max = series
+        .iter()
+        .flat_map(|series| series.values.iter().copied())
+        .fold(0.0_f64, f64::max);
+
+    let mut grid = vec![vec![' '; width]; height];
+    for series in series {
+        let count = series.values.len();
+        if count == 0 {
+            continue;
+        }
+        let mut previous: Option<usize> = None;
+        for column in 0..width {
+            let index = if width == 1 { 0 } else { column * (count - 1) / (width - 1) };
+            let row = row_for(series.values[index], max, height);
+            // Fill the gap from the last column so steep stretches stay joined
+            let (top, bottom) = match previous {
+                Some(last) if last < row => (last + 1, row),
+                Some(last) if last > row => (row, last - 1),
+                _ => (row, row),
+            };
+            for line in grid.iter_mut().take(bottom + 1).skip(top) {
+                line[column] = series.marker;
+            }
+            previous = Some(row);
+        }
+    }
+
+    let labels = [y_label(max), y_label(max / 2.0), y_label(0.0)];
+    let margin = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+    let mut chart = String::new();
+    for (row, line) in grid.iter().enumerate() {
+        let label = if row == 0 {
+            labels[0].as_str()
+        } else if row == (height - 1) / 2 {
+            labels[1].as_str()
+        } else if row == height - 1 {
+            labels[2].as_str()
+        } else {
+            ""
+        };
+        let line: String = line.iter().collect();
+        chart.push_str(&format!("{:>margin$} |{}\n", label, line.trim_end(), margin = margin));
+    }
+    chart.push_str(&format!("{:>margin$} +{}\n", "", "-".repeat(width), margin = margin));
+
+    let (first, middle, last) = (x_label(0), x_label((points - 1) / 2), x_label(points - 1));
+    let mut axis = vec![' '; width];
+    for (text, start) in [
+        (&first, 0),
+        (&middle, (width / 2).saturating_sub(middle.len() / 2)),
+        (&last, width.saturating_sub(last.len())),
+    ] {
+        for (offset, character) in text.chars().enumerate() {
+            if let Some(slot) = axis.get_mut(start + offset) {
+                *slot = character;
+            }
+        }
+    }
+    let axis: String = axis.iter().collect();
+    chart.push_str(&format!("{:>margin$}  {}\n", "", axis.trim_end(), margin = margin));
+
+    let legend: Vec<String> = series
+        .iter()
+        .map(|series| format!("{} {}", series.marker, series.label))
+        .collect();
+    chart.push_str(&format!("{:>margin$}  {}\n", "", legend.join("   "), margin = margin));
+    chart
+}
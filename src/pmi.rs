@@ -0,0 +1,83 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::purchase::Purchase;
+use crate::schedule::PaymentStrategy;
+use crate::LoanCalculator;
+
+// Lenders require mortgage insurance above 80% LTV and must drop it once
+// the scheduled balance reaches 78% of the original value
+const PMI_REQUIRED_LTV: Decimal = dec!(80);
+const PMI_REMOVAL_LTV: Decimal = dec!(78);
+
+#[derive(Debug, Clone)]
+pub struct Pmi {
+    pub monthly: Decimal,
+    // Last payment that includes PMI
+    pub last_month: u32,
+    pub total: Decimal,
+}
+
+impl Purchase {
+    pub fn requires_pmi(&self) -> bool {
+        self.loan_to_value() > PMI_REQUIRED_LTV
+    }
+}
+
+impl LoanCalculator {
+    // None when the purchase is financed at 80% LTV or less
+    pub fn calculate_pmi(
+        &self,
+        bank_name: &str,
+        purchase: &Purchase,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> Option<Pmi> {
+        if !purchase.requires_pmi() {
+            return None;
+        }
+        let bank = self.get_bank(bank_name)?;
+        let loan_amount = purchase.financed_amount();
+        let monthly = (loan_amount * bank.pmi_rate / dec!(100) / dec!(12)).round_dp(2);
+
+        let removal_balance = purchase.price * PMI_REMOVAL_LTV / dec!(100);
+        let schedule = self.generate_schedule(loan_amount, annual_rate, num_payments, &PaymentStrategy::Standard);
+        let last_month = schedule
+            .rows
+            .iter()
+            .position(|row| row.balance <= removal_balance)
+            .map(|index| index as u32 + 1)
+            .unwrap_or(schedule.num_payments());
+
+        Some(Pmi {
+            monthly,
+            last_month,
+            total: monthly * Decimal::from(last_month),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::purchase::DownPayment;
+    use crate::{BankConfig, LoanType};
+
+    #[test]
+    fn pmi_drops_at_78_percent_of_the_price() {
+        let mut bank = BankConfig::new("Bank").rates(&LoanType::Home, 0.0, 0.0);
+        bank.pmi_rate = 0.5;
+        let calculator = LoanCalculator::builder().bank(bank).build().unwrap();
+        let purchase = Purchase::new(dec!(100000), DownPayment::Percent(dec!(10))).unwrap();
+        let pmi = calculator.calculate_pmi("Bank", &purchase, dec!(0), 120).unwrap();
+        // 0.5% a year of 90,000
+        assert_eq!(pmi.monthly, dec!(37.50));
+        // 750 a month brings 90,000 to the 78,000 that is 78% LTV with the
+        // 16th payment
+        assert_eq!(pmi.last_month, 16);
+        assert_eq!(pmi.total, dec!(600));
+
+        let purchase = Purchase::new(dec!(100000), DownPayment::Percent(dec!(20))).unwrap();
+        assert!(calculator.calculate_pmi("Bank", &purchase, dec!(0), 120).is_none());
+    }
+}
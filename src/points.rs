@@ -0,0 +1,144 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+
+use crate::schedule::PaymentStrategy;
+use crate::LoanCalculator;
+
+// One row of a bank's pricing grid: paying `points` (percent of the loan)
+// moves the note rate by `rate_change` percentage points. Negative points are
+// lender credits taken in exchange for a higher rate.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PointsConfig {
+    pub points: f64,
+    pub rate_change: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PointsOption {
+    pub points: Decimal,
+    pub rate_change: Decimal,
+}
+
+impl TryFrom<PointsConfig> for PointsOption {
+    type Error = String;
+
+    fn try_from(config: PointsConfig) -> Result<Self, String> {
+        let usable = |field: &str, value: f64| {
+            Decimal::from_f64(value)
+                .ok_or_else(|| format!("points_pricing {} of {} is not a usable number", field, value))
+        };
+        Ok(PointsOption {
+            points: usable("points", config.points)?,
+            rate_change: usable("rate_change", config.rate_change)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PointsCost {
+    pub rate: Decimal,
+    // Points paid, or lender credit received when negative
+    pub points_cost: Decimal,
+    pub cash_to_close: Decimal,
+    // None where no rate reproduces the payment
+    pub apr: Option<Decimal>,
+    pub monthly_payment: Decimal,
+    // Cash to close plus interest paid through the horizon
+    pub cost_at_horizon: Decimal,
+}
+
+impl LoanCalculator {
+    pub fn calculate_points_cost(
+        &self,
+        principal: Decimal,
+        base_rate: Decimal,
+        num_payments: u32,
+        option: &PointsOption,
+        closing_costs: Decimal,
+        horizon_months: u32,
+    ) -> PointsCost {
+        let rate = (base_rate + option.rate_change).max(dec!(0));
+        let points_cost = principal * option.points / dec!(100);
+        let schedule = self.generate_schedule(principal, rate, num_payments, &PaymentStrategy::Standard);
+        let monthly_payment = schedule.payment_in(1);
+
+        // Lenders only credit up to the closing costs, never cash back
+        let cash_to_close = (closing_costs + points_cost).max(dec!(0));
+
+        // APR spreads the prepaid charges over the loan by solving for the
+        // rate at which the net amount financed carries the same payment
+        let apr = if cash_to_close > dec!(0) {
+            self.solve_rate_for_payment(principal - cash_to_close, monthly_payment, num_payments)
+        } else {
+            Some(rate)
+        };
+
+        PointsCost {
+            rate,
+            points_cost,
+            cash_to_close,
+            apr,
+            monthly_payment,
+            cost_at_horizon: cash_to_close + schedule.interest_through(horizon_months),
+        }
+    }
+}
+
+// Used when a bank has no pricing grid entry for the points bought
+const DEFAULT_RATE_CHANGE_PER_POINT: Decimal = dec!(-0.25);
+
+#[derive(Debug, Clone)]
+pub struct Buydown {
+    pub points: Decimal,
+    pub rate: Decimal,
+    pub upfront_cost: Decimal,
+    pub monthly_payment: Decimal,
+    // Payment at which the monthly savings have repaid the points; None when
+    // the points never pay for themselves within the term
+    pub break_even_month: Option<u32>,
+}
+
+impl LoanCalculator {
+    // The bank's own grid when it prices these points, otherwise a quarter
+    // point off the rate per point
+    pub fn buydown_rate(&self, bank_name: &str, base_rate: Decimal, points: Decimal) -> Decimal {
+        let rate_change = self
+            .get_points_pricing(bank_name)
+            .and_then(|pricing| pricing.iter().find(|option| option.points == points))
+            .map(|option| option.rate_change)
+            .unwrap_or(points * DEFAULT_RATE_CHANGE_PER_POINT);
+        (base_rate + rate_change).max(dec!(0))
+    }
+
+    pub fn calculate_buydown(
+        &self,
+        bank_name: &str,
+        principal: Decimal,
+        base_rate: Decimal,
+        num_payments: u32,
+        points: Decimal,
+    ) -> Buydown {
+        let rate = self.buydown_rate(bank_name, base_rate, points);
+        let upfront_cost = principal * points / dec!(100);
+        let monthly_payment = self.calculate_monthly_payment(principal, rate, num_payments);
+        let savings = self.calculate_monthly_payment(principal, base_rate, num_payments) - monthly_payment;
+
+        let break_even_month = if upfront_cost.is_zero() {
+            Some(0)
+        } else if savings > dec!(0) {
+            (upfront_cost / savings).ceil().to_u32().filter(|month| *month <= num_payments)
+        } else {
+            None
+        };
+
+        Buydown {
+            points,
+            rate,
+            upfront_cost,
+            monthly_payment,
+            break_even_month,
+        }
+    }
+}
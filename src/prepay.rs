@@ -0,0 +1,102 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::schedule::{PaymentStrategy, Schedule};
+use crate::LoanCalculator;
+
+#[derive(Debug, Clone)]
+pub struct PrepayComparison {
+    pub interest_saved: Decimal,
+    // Savings minus remaining loan balance at the horizon for each path
+    pub prepay_net: Decimal,
+    pub save_net: Decimal,
+    // The savings yield, in percent, at which both paths end level; None when
+    // one path wins at every yield from 0 to 100%
+    pub crossover_yield: Option<Decimal>,
+}
+
+// Savings minus loan balance at the horizon for prepaying and for saving
+fn net_at_horizon(
+    baseline: &Schedule,
+    prepaid: &Schedule,
+    lump_sum: Decimal,
+    savings_rate: Decimal,
+    horizon_months: u32,
+) -> (Decimal, Decimal) {
+    let monthly_yield = dec!(1) + savings_rate / dec!(100) / dec!(12);
+    let mut prepay_savings = dec!(0);
+    let mut save_savings = lump_sum;
+
+    for month in 2..=horizon_months {
+        prepay_savings *= monthly_yield;
+        save_savings *= monthly_yield;
+        prepay_savings += baseline.payment_in(month) - prepaid.payment_in(month);
+    }
+
+    (
+        prepay_savings - prepaid.balance_after(horizon_months),
+        save_savings - baseline.balance_after(horizon_months),
+    )
+}
+
+// Saving gains on prepaying as the yield rises, so the crossover is bisected
+// between the yield where prepaying still wins and the one where it loses.
+// With both compounding monthly it lands on the loan's own rate, give or take
+// the cents each schedule rounds to.
+fn crossover_yield(baseline: &Schedule, prepaid: &Schedule, lump_sum: Decimal, horizon_months: u32) -> Option<Decimal> {
+    let lead = |savings_rate: Decimal| {
+        let (prepay_net, save_net) = net_at_horizon(baseline, prepaid, lump_sum, savings_rate, horizon_months);
+        prepay_net - save_net
+    };
+    let mut low = dec!(0);
+    let mut high = dec!(100);
+    if lead(low) < Decimal::ZERO || lead(high) > Decimal::ZERO {
+        return None;
+    }
+    while high - low > dec!(0.0001) {
+        let mid = (low + high) / dec!(2);
+        if lead(mid) > Decimal::ZERO {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(((low + high) / dec!(2)).round_dp(2))
+}
+
+impl LoanCalculator {
+    // Both paths spend the same cash every month. Prepaying applies the lump
+    // sum with the first payment and, once the loan is gone, saves the freed
+    // payment instead. Saving deposits the lump sum at the same time and keeps
+    // paying the loan as scheduled.
+    pub fn compare_prepay_vs_save(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        lump_sum: Decimal,
+        savings_rate: Decimal,
+        horizon_months: u32,
+    ) -> PrepayComparison {
+        let baseline =
+            self.generate_schedule(principal, annual_rate, num_payments, &PaymentStrategy::Standard);
+        let prepaid = self.generate_schedule(
+            principal,
+            annual_rate,
+            num_payments,
+            &PaymentStrategy::LumpSum {
+                amount: lump_sum,
+                month: 1,
+            },
+        );
+
+        let (prepay_net, save_net) = net_at_horizon(&baseline, &prepaid, lump_sum, savings_rate, horizon_months);
+
+        PrepayComparison {
+            interest_saved: baseline.total_interest() - prepaid.total_interest(),
+            prepay_net,
+            save_net,
+            crossover_yield: crossover_yield(&baseline, &prepaid, lump_sum, horizon_months),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::LoanCalculator;
+
+pub fn config_checksum(config: &str) -> String {
+    format!("{:x}", Sha256::digest(config.as_bytes()))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalculationOption {
+    pub name: String,
+    pub value: String,
+}
+
+// Stamped into every export so an archived quote can be traced back to the
+// code, bank data and inputs that produced it
+#[derive(Debug, Clone, Serialize)]
+pub struct Provenance {
+    pub engine_version: String,
+    pub config_sha256: String,
+    pub rates_as_of: Option<String>,
+    pub generated_at: String,
+    pub options: Vec<CalculationOption>,
+}
+
+impl Provenance {
+    // Flat key/value pairs, for formats with string metadata
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = vec![
+            ("engine_version".to_string(), self.engine_version.clone()),
+            ("config_sha256".to_string(), self.config_sha256.clone()),
+            (
+                "rates_as_of".to_string(),
+                self.rates_as_of.clone().unwrap_or_else(|| "unknown".to_string()),
+            ),
+            ("generated_at".to_string(), self.generated_at.clone()),
+        ];
+        entries.extend(self.options.iter().map(|option| (option.name.clone(), option.value.clone())));
+        entries
+    }
+
+    pub fn get_description(&self) -> String {
+        format!(
+            "loancalc {}, banks.yaml sha256 {}, rates as of {}, generated {}",
+            self.engine_version,
+            &self.config_sha256[..12],
+            self.rates_as_of.as_deref().unwrap_or("unknown"),
+            self.generated_at
+        )
+    }
+}
+
+impl LoanCalculator {
+    pub fn provenance(&self, options: Vec<(&str, String)>) -> Provenance {
+        Provenance {
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            config_sha256: self.config_sha256.clone(),
+            rates_as_of: self.rates_as_of.map(|date: NaiveDate| date.to_string()),
+            generated_at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            options: options
+                .into_iter()
+                .map(|(name, value)| CalculationOption {
+                    name: name.to_string(),
+                    value,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::{Bank, Error, LoanType};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DownPayment {
+    Amount(Decimal),
+    // Percent of the purchase price
+    Percent(Decimal),
+}
+
+impl DownPayment {
+    // "60000", "$60,000" or "20%"
+    pub fn parse(input: &str) -> Option<DownPayment> {
+        let input = input.trim();
+        match input.strip_suffix('%') {
+            Some(percent) => Decimal::from_str(percent.trim()).ok().map(DownPayment::Percent),
+            None => Decimal::from_str(&input.trim_start_matches('$').replace(',', ""))
+                .ok()
+                .map(DownPayment::Amount),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Purchase {
+    pub price: Decimal,
+    pub down_payment: Decimal,
+}
+
+impl Purchase {
+    pub fn new(price: Decimal, down_payment: DownPayment) -> Result<Purchase, Error> {
+        if price <= Decimal::ZERO {
+            return Err(Error::InvalidLoan("the purchase price must be greater than 0".to_string()));
+        }
+        let down_payment = match down_payment {
+            DownPayment::Amount(amount) => amount,
+            DownPayment::Percent(percent) => (price * percent / dec!(100)).round_dp(2),
+        };
+        if down_payment < Decimal::ZERO || down_payment >= price {
+            return Err(Error::InvalidLoan(
+                "the down payment must be at least 0 and less than the purchase price".to_string(),
+            ));
+        }
+        Ok(Purchase { price, down_payment })
+    }
+
+    pub fn financed_amount(&self) -> Decimal {
+        self.price - self.down_payment
+    }
+
+    pub fn down_payment_percent(&self) -> Decimal {
+        self.down_payment / self.price * dec!(100)
+    }
+
+    pub fn loan_to_value(&self) -> Decimal {
+        self.financed_amount() / self.price * dec!(100)
+    }
+}
+
+impl Bank {
+    pub fn get_ltv_warning(&self, loan_type: &LoanType) -> Option<Decimal> {
+        match loan_type {
+            LoanType::Home => self.home_ltv_warning,
+            LoanType::Car => self.car_ltv_warning,
+            LoanType::Personal => self.personal_ltv_warning,
+            LoanType::Student => self.student_ltv_warning,
+        }
+    }
+
+    // Warning for a purchase financed above the bank's LTV threshold, if any
+    pub fn check_purchase_ltv(&self, loan_type: &LoanType, purchase: &Purchase) -> Option<String> {
+        self.check_ltv(loan_type, purchase.loan_to_value())
+    }
+
+    pub fn check_ltv(&self, loan_type: &LoanType, ltv: Decimal) -> Option<String> {
+        match self.get_ltv_warning(loan_type) {
+            Some(threshold) if ltv > threshold => Some(format!(
+                "LTV of {:.1}% is above its {:.1}% threshold for this loan type",
+                ltv.round_dp(1), threshold.round_dp(1)
+            )),
+            _ => None,
+        }
+    }
+}
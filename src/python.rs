@@ -0,0 +1,118 @@
+// Python bindings, built into a wheel with `maturin build --features python`
+// (see pyproject.toml). Amounts and rates go both ways as decimal.Decimal;
+// ints, floats and numeric strings are accepted too.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rust_decimal::Decimal;
+
+use crate::config::{self, ConfigSource};
+use crate::interest::InterestMethod;
+use crate::schedule::PaymentStrategy;
+use crate::{Error, LoanCalculator, LoanType};
+
+fn value_error(e: Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+/// The level monthly payment that repays `principal` over `num_payments`
+/// months at `annual_rate` percent, unrounded.
+#[pyfunction]
+fn monthly_payment(principal: Decimal, annual_rate: Decimal, num_payments: u32) -> PyResult<Decimal> {
+    crate::monthly_payment(principal, annual_rate, num_payments).map_err(value_error)
+}
+
+/// The banks loancalc quotes for. With no arguments the config is found as
+/// the command line finds it; `banks_yaml` takes a document instead.
+#[pyclass(name = "Calculator", unsendable)]
+struct PyCalculator {
+    inner: LoanCalculator,
+}
+
+#[pymethods]
+impl PyCalculator {
+    #[new]
+    #[pyo3(signature = (config_path=None, banks_yaml=None))]
+    fn new(config_path: Option<std::path::PathBuf>, banks_yaml: Option<&str>) -> PyResult<Self> {
+        let inner = match (config_path, banks_yaml) {
+            (Some(_), Some(_)) => return Err(PyValueError::new_err("pass config_path or banks_yaml, not both")),
+            (Some(path), None) => LoanCalculator::load(&ConfigSource::File(path)),
+            (None, Some(document)) => LoanCalculator::from_config(document),
+            (None, None) => LoanCalculator::load(&config::resolve_config(None)),
+        }
+        .map_err(value_error)?;
+        Ok(PyCalculator { inner })
+    }
+
+    fn bank_names(&self) -> Vec<String> {
+        self.inner.banks().iter().map(|bank| bank.name.clone()).collect()
+    }
+
+    /// One dict per month with month, payment, principal, interest and
+    /// balance, ready for `pandas.DataFrame`.
+    fn amortization_schedule<'py>(
+        &self,
+        py: Python<'py>,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        // Rejects the loans the schedule cannot be built for
+        crate::monthly_payment(principal, annual_rate, num_payments).map_err(value_error)?;
+        let schedule = self
+            .inner
+            .generate_schedule(principal, annual_rate, num_payments, &PaymentStrategy::Standard);
+        schedule
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let dict = PyDict::new(py);
+                dict.set_item("month", index + 1)?;
+                dict.set_item("payment", row.payment)?;
+                dict.set_item("principal", row.payment - row.interest)?;
+                dict.set_item("interest", row.interest)?;
+                dict.set_item("balance", row.balance)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// The APR of an amortizing `loan_type` loan from `bank`, counting its
+    /// prepaid finance charges, or None when no loan is possible at the rate.
+    #[pyo3(signature = (bank, principal, annual_rate, num_payments, loan_type="home"))]
+    fn apr(
+        &self,
+        bank: &str,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        loan_type: &str,
+    ) -> PyResult<Option<Decimal>> {
+        if self.inner.get_bank(bank).is_none() {
+            return Err(PyValueError::new_err(format!("no bank named '{}'", bank)));
+        }
+        let loan_type = LoanType::from_name(loan_type)
+            .ok_or_else(|| PyValueError::new_err(format!("no loan type named '{}'", loan_type)))?;
+        crate::monthly_payment(principal, annual_rate, num_payments).map_err(value_error)?;
+        Ok(self
+            .inner
+            .calculate_apr(&InterestMethod::Amortizing, bank, &loan_type, principal, annual_rate, num_payments))
+    }
+
+    /// A rate moved by the config's credit tiers for `credit_score`.
+    fn adjust_rate_for_credit(&self, base_rate: Decimal, credit_score: u16) -> PyResult<Decimal> {
+        if !(300..=850).contains(&credit_score) {
+            return Err(PyValueError::new_err("credit scores run from 300 to 850"));
+        }
+        Ok(self.inner.adjust_rate_for_credit(base_rate, credit_score))
+    }
+}
+
+#[pymodule]
+fn loancalc(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(monthly_payment, module)?)?;
+    module.add_class::<PyCalculator>()?;
+    Ok(())
+}
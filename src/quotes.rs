@@ -0,0 +1,110 @@
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+
+// What a lender sees when asked for a soft quote
+pub struct QuoteRequest<'a> {
+    pub loan_type: &'a str,
+    pub amount: Decimal,
+    pub term_months: u32,
+    pub credit_score: u16,
+}
+
+// A live quote replaces the rate a bank would otherwise get from its
+// configured range; closing costs stay as configured unless quoted
+pub struct Quote {
+    pub rate: Decimal,
+    pub closing_costs: Option<Decimal>,
+}
+
+pub trait QuoteProvider {
+    fn source(&self) -> &str;
+
+    // Ok(None) means the lender declined to quote this request
+    fn fetch_quote(&self, request: &QuoteRequest) -> Result<Option<Quote>, Box<dyn std::error::Error>>;
+}
+
+// `url` may contain {loan_type}, {amount}, {term_months} and {credit_score}.
+// The response is JSON; `rate_field` and `closing_costs_field` are JSON
+// pointers such as "/offer/apr", and a null or missing rate is a decline.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpQuoteConfig {
+    pub url: String,
+    #[serde(default = "default_rate_field")]
+    pub rate_field: String,
+    #[serde(default)]
+    pub closing_costs_field: Option<String>,
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_rate_field() -> String {
+    "/rate".to_string()
+}
+
+fn default_timeout_seconds() -> u64 {
+    5
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn get_json(url: &str, timeout_seconds: u64) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    Ok(ureq::get(url)
+        .timeout(std::time::Duration::from_secs(timeout_seconds))
+        .call()?
+        .into_json()?)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn get_json(_url: &str, _timeout_seconds: u64) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    Err("WebAssembly builds cannot make HTTP requests".into())
+}
+
+pub struct HttpJsonProvider {
+    config: HttpQuoteConfig,
+}
+
+impl HttpJsonProvider {
+    pub fn new(config: HttpQuoteConfig) -> Self {
+        HttpJsonProvider { config }
+    }
+}
+
+// Lenders send numbers either as JSON numbers or as strings
+fn decimal_at(body: &serde_json::Value, pointer: &str) -> Result<Option<Decimal>, String> {
+    match body.pointer(pointer) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::Number(number)) => Decimal::from_str(&number.to_string())
+            .map(Some)
+            .map_err(|e| format!("{} is not a decimal: {}", pointer, e)),
+        Some(serde_json::Value::String(text)) => Decimal::from_str(text.trim())
+            .map(Some)
+            .map_err(|e| format!("{} is not a decimal: {}", pointer, e)),
+        Some(other) => Err(format!("{} should be a number, found {}", pointer, other)),
+    }
+}
+
+impl QuoteProvider for HttpJsonProvider {
+    // The endpoint without its query template
+    fn source(&self) -> &str {
+        self.config.url.split('?').next().unwrap_or(&self.config.url)
+    }
+
+    fn fetch_quote(&self, request: &QuoteRequest) -> Result<Option<Quote>, Box<dyn std::error::Error>> {
+        let url = self
+            .config
+            .url
+            .replace("{loan_type}", request.loan_type)
+            .replace("{amount}", &request.amount.round_dp(2).to_string())
+            .replace("{term_months}", &request.term_months.to_string())
+            .replace("{credit_score}", &request.credit_score.to_string());
+        let body = get_json(&url, self.config.timeout_seconds)?;
+
+        let Some(rate) = decimal_at(&body, &self.config.rate_field)? else {
+            return Ok(None);
+        };
+        let closing_costs = match &self.config.closing_costs_field {
+            Some(pointer) => decimal_at(&body, pointer)?,
+            None => None,
+        };
+        Ok(Some(Quote { rate, closing_costs }))
+    }
+}
@@ -0,0 +1,275 @@
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+
+use crate::interest::InterestMethod;
+use crate::schedule::PaymentStrategy;
+use crate::{LoanCalculator, LoanType};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Objective {
+    Payment,
+    TotalCost,
+    Fees,
+    Flexibility,
+    Rating,
+}
+
+pub const OBJECTIVES: [Objective; 5] = [
+    Objective::Payment,
+    Objective::TotalCost,
+    Objective::Fees,
+    Objective::Flexibility,
+    Objective::Rating,
+];
+
+impl Objective {
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            Objective::Payment => "Monthly Payment",
+            Objective::TotalCost => "Total Cost",
+            Objective::Fees => "Fees",
+            Objective::Flexibility => "Flexibility",
+            Objective::Rating => "Rating",
+        }
+    }
+}
+
+// Relative weights of each objective; only their proportions matter. Total
+// cost is measured over `horizon_years`, or the full term when unset.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RankingConfig {
+    #[serde(default)]
+    pub payment: f64,
+    #[serde(default = "default_total_cost_weight")]
+    pub total_cost: f64,
+    #[serde(default)]
+    pub fees: f64,
+    #[serde(default)]
+    pub flexibility: f64,
+    #[serde(default)]
+    pub rating: f64,
+    #[serde(default)]
+    pub horizon_years: Option<u32>,
+}
+
+fn default_total_cost_weight() -> f64 {
+    1.0
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        RankingConfig {
+            payment: 0.0,
+            total_cost: default_total_cost_weight(),
+            fees: 0.0,
+            flexibility: 0.0,
+            rating: 0.0,
+            horizon_years: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RankingWeights {
+    pub weights: Vec<(Objective, Decimal)>,
+    pub horizon_years: Option<u32>,
+}
+
+impl TryFrom<RankingConfig> for RankingWeights {
+    type Error = String;
+
+    fn try_from(config: RankingConfig) -> Result<Self, String> {
+        let weights = [config.payment, config.total_cost, config.fees, config.flexibility, config.rating];
+        // NaN passes both checks below, and infinity the second
+        let weights = weights
+            .into_iter()
+            .map(|weight| Decimal::from_f64(weight).ok_or_else(|| format!("Ranking weight of {} is not a usable number", weight)))
+            .collect::<Result<Vec<Decimal>, String>>()?;
+        if weights.iter().any(|weight| *weight < Decimal::ZERO) || weights.iter().sum::<Decimal>() <= Decimal::ZERO {
+            return Err("Ranking weights must not be negative and must not all be zero".to_string());
+        }
+        Ok(RankingWeights {
+            weights: OBJECTIVES.into_iter().zip(weights).collect(),
+            horizon_years: config.horizon_years,
+        })
+    }
+}
+
+impl RankingWeights {
+    pub fn get(&self, objective: Objective) -> Decimal {
+        self.weights
+            .iter()
+            .find(|(candidate, _)| *candidate == objective)
+            .map(|(_, weight)| *weight)
+            .unwrap_or(dec!(0))
+    }
+
+    pub fn get_description(&self) -> String {
+        self.shares()
+            .iter()
+            .filter(|(_, share)| !share.is_zero())
+            .map(|(objective, share)| {
+                format!("{} {:.0}%", objective.get_description(), (share * dec!(100)).round_dp(0))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Weights as shares of their total, for display and scoring
+    pub fn shares(&self) -> Vec<(Objective, Decimal)> {
+        let total: Decimal = self.weights.iter().map(|(_, weight)| *weight).sum();
+        self.weights
+            .iter()
+            .map(|(objective, weight)| (*objective, weight / total))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RankedOffer {
+    pub name: String,
+    pub score: Decimal,
+    // Each objective's weighted contribution to the score
+    pub breakdown: Vec<(Objective, Decimal)>,
+}
+
+struct OfferMeasures<'a> {
+    name: &'a str,
+    payment: Decimal,
+    total_cost: Decimal,
+    fees: Decimal,
+    has_prepayment_penalty: bool,
+    rating: Option<Decimal>,
+}
+
+// Lower-is-better measures scaled across the offers, so the best scores 1,
+// the worst 0 and the rest in proportion between them. When every offer
+// measures the same, they all score 1.
+fn relative_score(best: Decimal, worst: Decimal, value: Decimal) -> Decimal {
+    if worst == best {
+        dec!(1)
+    } else {
+        (worst - value) / (worst - best)
+    }
+}
+
+impl LoanCalculator {
+    // Scores offers from 0 to 1, best first. Total cost counts closing costs,
+    // interest through the horizon and any prepayment penalty for paying off
+    // at the horizon. Offers without a rating, such as a custom rate, get the
+    // average rating of the others so they are neither rewarded nor penalized.
+    pub fn rank_offers(
+        &self,
+        method: &InterestMethod,
+        loan_type: &LoanType,
+        offers: &[(String, Decimal)],
+        loan_amount: Decimal,
+        loan_term: u32,
+        weights: &RankingWeights,
+    ) -> Vec<RankedOffer> {
+        // Only amortizing loans have a schedule to stop part way through
+        let horizon = match (method, weights.horizon_years) {
+            (InterestMethod::Amortizing, Some(years)) => (years * 12).min(loan_term),
+            _ => loan_term,
+        };
+
+        let measures: Vec<OfferMeasures> = offers
+            .iter()
+            .filter_map(|(name, rate)| {
+                let cost = self.calculate_cost(method, loan_amount, *rate, loan_term)?;
+                let fees = self.prepaid_finance_charges(name, loan_type, loan_amount);
+                let penalty_rate = self.get_prepayment_penalty(name);
+                let total_cost = if horizon < loan_term {
+                    let schedule =
+                        self.generate_schedule(loan_amount, *rate, loan_term, &PaymentStrategy::Standard);
+                    fees + schedule.interest_through(horizon)
+                        + schedule.balance_after(horizon) * penalty_rate / dec!(100)
+                } else {
+                    fees + cost.total_interest
+                };
+                Some(OfferMeasures {
+                    name: name.as_str(),
+                    payment: cost.monthly_payment,
+                    total_cost,
+                    fees,
+                    has_prepayment_penalty: !penalty_rate.is_zero(),
+                    rating: self.get_rating(name),
+                })
+            })
+            .collect();
+        if measures.is_empty() {
+            return Vec::new();
+        }
+
+        let lowest = |measure: fn(&OfferMeasures) -> Decimal| {
+            measures.iter().map(measure).min().unwrap_or(dec!(0))
+        };
+        let highest = |measure: fn(&OfferMeasures) -> Decimal| {
+            measures.iter().map(measure).max().unwrap_or(dec!(0))
+        };
+        let payments = (lowest(|offer| offer.payment), highest(|offer| offer.payment));
+        let total_costs = (lowest(|offer| offer.total_cost), highest(|offer| offer.total_cost));
+        let fees = (lowest(|offer| offer.fees), highest(|offer| offer.fees));
+
+        let ratings: Vec<Decimal> = measures.iter().filter_map(|offer| offer.rating).collect();
+        let average_rating = if ratings.is_empty() {
+            None
+        } else {
+            Some(ratings.iter().sum::<Decimal>() / Decimal::from(ratings.len()))
+        };
+        // With no ratings at all, the rating weight is left out entirely
+        let mut weights = weights.clone();
+        if average_rating.is_none() {
+            weights.weights.retain(|(objective, _)| *objective != Objective::Rating);
+            if weights.weights.iter().all(|(_, weight)| weight.is_zero()) {
+                weights.weights.push((Objective::TotalCost, dec!(1)));
+            }
+        }
+        let shares = weights.shares();
+
+        let mut ranked: Vec<RankedOffer> = measures
+            .iter()
+            .map(|offer| {
+                let breakdown: Vec<(Objective, Decimal)> = shares
+                    .iter()
+                    .map(|(objective, share)| {
+                        let score = match objective {
+                            Objective::Payment => relative_score(payments.0, payments.1, offer.payment),
+                            Objective::TotalCost => relative_score(total_costs.0, total_costs.1, offer.total_cost),
+                            Objective::Fees => relative_score(fees.0, fees.1, offer.fees),
+                            Objective::Flexibility if offer.has_prepayment_penalty => dec!(0),
+                            Objective::Flexibility => dec!(1),
+                            Objective::Rating => {
+                                offer.rating.or(average_rating).unwrap_or(dec!(0)) / dec!(5)
+                            }
+                        };
+                        (*objective, share * score)
+                    })
+                    .collect();
+                RankedOffer {
+                    name: offer.name.to_string(),
+                    score: breakdown.iter().map(|(_, contribution)| *contribution).sum(),
+                    breakdown,
+                }
+            })
+            .collect();
+        ranked.sort_by_key(|offer| std::cmp::Reverse(offer.score));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_scale_between_the_best_and_worst_offer() {
+        // With one car loan charging no fees, the others still score in
+        // proportion rather than all dropping to 0
+        assert_eq!(relative_score(dec!(0), dec!(300), dec!(0)), dec!(1));
+        assert_eq!(relative_score(dec!(0), dec!(300), dec!(100)), dec!(200) / dec!(300));
+        assert_eq!(relative_score(dec!(0), dec!(300), dec!(300)), dec!(0));
+        assert_eq!(relative_score(dec!(250), dec!(250), dec!(250)), dec!(1));
+    }
+}
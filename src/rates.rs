@@ -0,0 +1,353 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate};
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::quotes::get_json;
+use crate::{config, Bank, LoanCalculator, LoanType};
+
+const FRED_URL: &str = "https://api.stlouisfed.org/fred/series/observations";
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketRateSource {
+    // The St. Louis Fed's FRED API, one series per loan type
+    Fred,
+    // One JSON document with a pointer to each loan type's rate
+    Json,
+}
+
+/// What a fetched market rate does to the configured ranges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketRateMode {
+    // Shown beside the comparison, the ranges are left alone
+    #[default]
+    Annotate,
+    // Every bank's range moves by the same amount so that the average
+    // midpoint of the banks offering the loan type matches the market
+    Adjust,
+}
+
+// FRED series ids, or JSON pointers for the `json` source
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MarketSeries {
+    #[serde(default)]
+    pub home: Option<String>,
+    #[serde(default)]
+    pub car: Option<String>,
+    #[serde(default)]
+    pub personal: Option<String>,
+    #[serde(default)]
+    pub student: Option<String>,
+}
+
+impl MarketSeries {
+    pub fn get(&self, loan_type: &LoanType) -> Option<&str> {
+        match loan_type {
+            LoanType::Home => self.home.as_deref(),
+            LoanType::Car => self.car.as_deref(),
+            LoanType::Personal => self.personal.as_deref(),
+            LoanType::Student => self.student.as_deref(),
+        }
+    }
+}
+
+/// The `market_rates` section of banks.yaml.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketRatesConfig {
+    pub source: MarketRateSource,
+    // Required for `json`; `fred` defaults to the public API
+    #[serde(default)]
+    pub url: Option<String>,
+    pub series: MarketSeries,
+    // JSON pointer to the date the rates were published, for `json`
+    #[serde(default)]
+    pub as_of_field: Option<String>,
+    // Environment variable holding the FRED API key
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+    #[serde(default)]
+    pub mode: MarketRateMode,
+    // Fetched rates are reused for this long before asking again
+    #[serde(default = "default_max_age_hours")]
+    pub max_age_hours: i64,
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_api_key_env() -> String {
+    "FRED_API_KEY".to_string()
+}
+
+fn default_max_age_hours() -> i64 {
+    24
+}
+
+fn default_timeout_seconds() -> u64 {
+    5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketRate {
+    pub loan_type: String,
+    pub rate: f64,
+    #[serde(default)]
+    pub as_of: Option<NaiveDate>,
+    // The series or endpoint the rate came from
+    pub source: String,
+    // Percentage points the ranges were moved by, in `adjust` mode
+    #[serde(skip)]
+    pub adjustment: Option<Decimal>,
+}
+
+impl MarketRate {
+    pub fn get_description(&self) -> String {
+        match self.as_of {
+            Some(date) => format!("{:.2}% ({}, as of {})", self.rate, self.source, date),
+            None => format!("{:.2}% ({})", self.rate, self.source),
+        }
+    }
+}
+
+// The last successful fetch, kept so runs within `max_age_hours`, and runs
+// without a network, reuse it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketRates {
+    pub fetched_at: String,
+    pub rates: Vec<MarketRate>,
+}
+
+impl MarketRates {
+    fn age_hours(&self) -> Option<i64> {
+        let fetched_at = DateTime::parse_from_rfc3339(&self.fetched_at).ok()?;
+        Some((chrono::Local::now().fixed_offset() - fetched_at).num_hours())
+    }
+
+    // Fetched for the series configured now, not ones since edited
+    fn matches(&self, config: &MarketRatesConfig) -> bool {
+        LOAN_TYPES.iter().all(|loan_type| match config.series.get(loan_type) {
+            Some(series) => self
+                .rates
+                .iter()
+                .any(|rate| rate.loan_type == loan_type.get_name() && rate.source == source_name(config, series)),
+            None => true,
+        })
+    }
+}
+
+const LOAN_TYPES: [LoanType; 4] = [LoanType::Home, LoanType::Car, LoanType::Personal, LoanType::Student];
+
+pub fn default_cache_path() -> Option<PathBuf> {
+    config::user_data_dir().map(|dir| dir.join("market_rates.json"))
+}
+
+fn source_name(config: &MarketRatesConfig, series: &str) -> String {
+    match config.source {
+        MarketRateSource::Fred => format!("FRED {}", series),
+        MarketRateSource::Json => format!("{}#{}", config.url.as_deref().unwrap_or_default(), series),
+    }
+}
+
+// Numbers may come as JSON numbers or strings; FRED marks a missing
+// observation with "."
+fn rate_value(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(text) => text.trim().trim_end_matches('%').parse().ok(),
+        _ => None,
+    }
+    .filter(|rate: &f64| rate.is_finite() && *rate > 0.0)
+}
+
+fn date_value(value: Option<&serde_json::Value>) -> Option<NaiveDate> {
+    let text = value?.as_str()?;
+    NaiveDate::parse_from_str(text.get(..10).unwrap_or(text), "%Y-%m-%d").ok()
+}
+
+// The latest observation of each configured series
+fn fetch_fred(config: &MarketRatesConfig) -> Result<Vec<MarketRate>, Box<dyn std::error::Error>> {
+    let api_key = std::env::var(&config.api_key_env)
+        .map_err(|_| format!("set {} to a FRED API key to fetch market rates", config.api_key_env))?;
+    let url = config.url.as_deref().unwrap_or(FRED_URL);
+    let mut rates = Vec::new();
+    for loan_type in &LOAN_TYPES {
+        let Some(series) = config.series.get(loan_type) else {
+            continue;
+        };
+        let body = get_json(
+            &format!(
+                "{}?series_id={}&api_key={}&file_type=json&sort_order=desc&limit=10",
+                url, series, api_key
+            ),
+            config.timeout_seconds,
+        )
+        // Errors quote the URL, which carries the key
+        .map_err(|e| e.to_string().replace(&api_key, "***"))?;
+        let observations = body["observations"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let (rate, as_of) = observations
+            .iter()
+            .find_map(|observation| Some((rate_value(&observation["value"])?, date_value(observation.get("date")))))
+            .ok_or_else(|| format!("FRED series {} has no recent observations", series))?;
+        rates.push(MarketRate {
+            loan_type: loan_type.get_name().to_string(),
+            rate,
+            as_of,
+            source: source_name(config, series),
+            adjustment: None,
+        });
+    }
+    Ok(rates)
+}
+
+fn fetch_json(config: &MarketRatesConfig) -> Result<Vec<MarketRate>, Box<dyn std::error::Error>> {
+    let url = config.url.as_deref().ok_or("market_rates needs a url for the json source")?;
+    let body = get_json(url, config.timeout_seconds)?;
+    let as_of = config.as_of_field.as_deref().and_then(|pointer| date_value(body.pointer(pointer)));
+    let mut rates = Vec::new();
+    for loan_type in &LOAN_TYPES {
+        let Some(pointer) = config.series.get(loan_type) else {
+            continue;
+        };
+        let rate = body
+            .pointer(pointer)
+            .and_then(rate_value)
+            .ok_or_else(|| format!("{} has no rate at {}", url, pointer))?;
+        rates.push(MarketRate {
+            loan_type: loan_type.get_name().to_string(),
+            rate,
+            as_of,
+            source: source_name(config, pointer),
+            adjustment: None,
+        });
+    }
+    Ok(rates)
+}
+
+pub fn fetch(config: &MarketRatesConfig) -> Result<MarketRates, Box<dyn std::error::Error>> {
+    let rates = match config.source {
+        MarketRateSource::Fred => fetch_fred(config)?,
+        MarketRateSource::Json => fetch_json(config)?,
+    };
+    Ok(MarketRates {
+        fetched_at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        rates,
+    })
+}
+
+fn read_cache(path: &Path) -> Option<MarketRates> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+// Rates fetched within `max_age_hours` are reused unless `refresh` is set.
+// When fetching fails, the last rates fetched are used however old they
+// are, and with none the configured ranges stand; either way with a warning.
+pub fn load(
+    config: &MarketRatesConfig,
+    cache_path: Option<&Path>,
+    refresh: bool,
+    warnings: &mut Vec<String>,
+) -> Option<MarketRates> {
+    let cached = cache_path.and_then(read_cache).filter(|cached| cached.matches(config));
+    if let Some(cached) = &cached {
+        let fresh = cached.age_hours().is_some_and(|age| age < config.max_age_hours);
+        if fresh && !refresh {
+            return Some(cached.clone());
+        }
+    }
+
+    match fetch(config) {
+        Ok(rates) => {
+            if let Some(path) = cache_path {
+                let saved = serde_json::to_string_pretty(&rates)
+                    .map_err(|e| e.to_string())
+                    .and_then(|contents| config::write_atomically(path, &contents).map_err(|e| e.to_string()));
+                if let Err(e) = saved {
+                    warnings.push(format!("market rates were not saved for offline use: {}", e));
+                }
+            }
+            Some(rates)
+        }
+        Err(e) => match cached {
+            Some(cached) => {
+                warnings.push(format!(
+                    "market rates could not be fetched ({}); using those fetched {}",
+                    e, cached.fetched_at
+                ));
+                Some(cached)
+            }
+            None => {
+                warnings.push(format!("market rates could not be fetched ({}); using configured rates", e));
+                None
+            }
+        },
+    }
+}
+
+fn rate_range_mut<'a>(bank: &'a mut Bank, loan_type: &LoanType) -> &'a mut (Decimal, Decimal) {
+    match loan_type {
+        LoanType::Home => &mut bank.home_loan_range,
+        LoanType::Car => &mut bank.car_loan_range,
+        LoanType::Personal => &mut bank.personal_loan_range,
+        LoanType::Student => &mut bank.student_loan_range,
+    }
+}
+
+impl LoanCalculator {
+    pub fn market_rates_config(&self) -> Option<&MarketRatesConfig> {
+        self.market_rates_config.as_ref()
+    }
+
+    /// Fetches, or reuses, the market rates banks.yaml points at, moving the
+    /// configured ranges to match in `adjust` mode. Does nothing without a
+    /// `market_rates` section.
+    pub fn use_market_rates(&mut self, refresh: bool, warnings: &mut Vec<String>) {
+        let Some(config) = self.market_rates_config.clone() else {
+            if refresh {
+                warnings.push("--refresh-rates has no effect without a market_rates source in banks.yaml".to_string());
+            }
+            return;
+        };
+        let Some(fetched) = load(&config, default_cache_path().as_deref(), refresh, warnings) else {
+            return;
+        };
+
+        self.market_rates = fetched.rates;
+        if config.mode != MarketRateMode::Adjust {
+            return;
+        }
+        for market_rate in &mut self.market_rates {
+            let (Some(loan_type), Some(rate)) =
+                (LoanType::from_name(&market_rate.loan_type), Decimal::from_f64(market_rate.rate))
+            else {
+                continue;
+            };
+            let midpoints: Vec<Decimal> = self
+                .banks
+                .iter()
+                .map(|bank| bank.get_rate_range(&loan_type))
+                .filter(|(min, max)| !min.is_zero() || !max.is_zero())
+                .map(|(min, max)| (min + max) / dec!(2))
+                .collect();
+            if midpoints.is_empty() {
+                continue;
+            }
+            let average = midpoints.iter().sum::<Decimal>() / Decimal::from(midpoints.len());
+            let adjustment = (rate - average).round_dp(4);
+            for bank in &mut self.banks {
+                let range = rate_range_mut(bank, &loan_type);
+                if !range.0.is_zero() || !range.1.is_zero() {
+                    *range = (range.0 + adjustment, range.1 + adjustment);
+                }
+            }
+            market_rate.adjustment = Some(adjustment);
+        }
+    }
+
+    pub fn market_rate(&self, loan_type: &LoanType) -> Option<&MarketRate> {
+        self.market_rates.iter().find(|rate| rate.loan_type == loan_type.get_name())
+    }
+}
@@ -0,0 +1,278 @@
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+
+use crate::schedule::{PaymentStrategy, Schedule, ScheduleRow};
+use crate::LoanCalculator;
+
+#[derive(Debug, Clone)]
+pub struct ExistingLoan {
+    pub balance: Decimal,
+    pub annual_rate: Decimal,
+    pub remaining_payments: u32,
+    pub payments_made: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct CashOutRefinance {
+    pub new_amount: Decimal,
+    pub schedule: Schedule,
+    // Annual rate actually paid on the cash taken out, if the extra payments
+    // are all positive so that it is well defined
+    pub effective_cash_rate: Option<Decimal>,
+}
+
+// A rate-and-term refinance of the existing balance, with closing costs
+// paid in cash
+#[derive(Debug, Clone)]
+pub struct RateRefinance {
+    pub monthly_payment: Decimal,
+    // Positive when the new payment is lower
+    pub monthly_savings: Decimal,
+    // Payment at which the savings have repaid the closing costs
+    pub break_even_month: Option<u32>,
+    // New loan's interest less the existing loan's; negative saves interest
+    pub interest_delta: Decimal,
+}
+
+impl LoanCalculator {
+    pub fn existing_schedule(&self, existing: &ExistingLoan) -> Schedule {
+        self.generate_schedule(
+            existing.balance,
+            existing.annual_rate,
+            existing.remaining_payments,
+            &PaymentStrategy::Standard,
+        )
+    }
+
+    // Rebuilds the payments already made by stepping the balance backward
+    // from today at the loan's fixed payment. Each earlier balance is the
+    // amount in cents that a month of interest, billed to the cent, and the
+    // payment bring down to the balance after it.
+    pub fn existing_history(&self, existing: &ExistingLoan) -> Schedule {
+        let rounding = self.rounding();
+        let monthly_rate = existing.annual_rate / dec!(100) / dec!(12);
+        let payment = rounding.round_cents(self.calculate_monthly_payment(
+            existing.balance,
+            existing.annual_rate,
+            existing.remaining_payments,
+        ));
+
+        let mut rows = Vec::with_capacity(existing.payments_made as usize);
+        let mut balance = rounding.round_cents(existing.balance);
+        for _ in 0..existing.payments_made {
+            let estimate = rounding.round_cents((balance + payment) / (dec!(1) + monthly_rate));
+            let cent = rounding.amount_of(1);
+            let previous_balance = [estimate - cent, estimate, estimate + cent]
+                .into_iter()
+                .min_by_key(|previous| {
+                    (previous + rounding.round_product(*previous, monthly_rate) - payment - balance).abs()
+                })
+                .unwrap_or(estimate);
+            // Where rounding leaves no amount that lands on the cent, the
+            // interest absorbs it so the rows still add up
+            rows.push(ScheduleRow {
+                payment,
+                interest: balance + payment - previous_balance,
+                balance,
+                offset_balance: dec!(0),
+            });
+            balance = previous_balance;
+        }
+        rows.reverse();
+
+        Schedule { rows }
+    }
+
+    // Borrows at `rate` today and refinances whatever is left after
+    // `refinance_after` payments at `new_rate`, keeping the original payoff date
+    pub fn refinance_later_schedule(
+        &self,
+        principal: Decimal,
+        rate: Decimal,
+        num_payments: u32,
+        refinance_after: u32,
+        new_rate: Decimal,
+    ) -> Schedule {
+        let mut schedule = self.generate_schedule(principal, rate, num_payments, &PaymentStrategy::Standard);
+        schedule.rows.truncate(refinance_after as usize);
+        let balance = schedule.balance_after(refinance_after);
+        schedule.rows.extend(
+            self.generate_schedule(
+                balance,
+                new_rate,
+                num_payments - refinance_after,
+                &PaymentStrategy::Standard,
+            )
+            .rows,
+        );
+        schedule
+    }
+
+    // New loan = remaining balance + cash out + closing costs rolled in. The
+    // cash is then priced by what the borrower pays above keeping the
+    // existing loan, month by month.
+    pub fn calculate_cash_out_refinance(
+        &self,
+        existing: &ExistingLoan,
+        cash_out: Decimal,
+        rolled_costs: Decimal,
+        new_rate: Decimal,
+        new_payments: u32,
+    ) -> CashOutRefinance {
+        let new_amount = existing.balance + cash_out + rolled_costs;
+        let schedule =
+            self.generate_schedule(new_amount, new_rate, new_payments, &PaymentStrategy::Standard);
+        let current = self.existing_schedule(existing);
+
+        let months = schedule.num_payments().max(current.num_payments());
+        let extra_payments: Vec<Decimal> = (1..=months)
+            .map(|month| schedule.payment_in(month) - current.payment_in(month))
+            .collect();
+
+        let effective_cash_rate = if cash_out > dec!(0) {
+            solve_irr(cash_out, &extra_payments)
+        } else {
+            None
+        };
+
+        CashOutRefinance {
+            new_amount,
+            schedule,
+            effective_cash_rate,
+        }
+    }
+}
+
+impl LoanCalculator {
+    pub fn calculate_rate_refinance(
+        &self,
+        existing: &ExistingLoan,
+        closing_costs: Decimal,
+        new_rate: Decimal,
+        new_payments: u32,
+    ) -> RateRefinance {
+        let current = self.existing_schedule(existing);
+        let schedule = self.generate_schedule(existing.balance, new_rate, new_payments, &PaymentStrategy::Standard);
+        let monthly_payment = schedule.payment_in(1);
+        let monthly_savings = current.payment_in(1) - monthly_payment;
+
+        let break_even_month = if closing_costs.is_zero() && monthly_savings >= dec!(0) {
+            Some(0)
+        } else if monthly_savings > dec!(0) {
+            (closing_costs / monthly_savings).ceil().to_u32().filter(|month| *month <= new_payments)
+        } else {
+            None
+        };
+
+        RateRefinance {
+            monthly_payment,
+            monthly_savings,
+            break_even_month,
+            interest_delta: schedule.total_interest() - current.total_interest(),
+        }
+    }
+}
+
+// Payments already made on the old loan followed by the new loan
+pub fn refinance_timeline(history: &Schedule, new_loan: &Schedule) -> Schedule {
+    let mut rows = history.rows.clone();
+    rows.extend(new_loan.rows.iter().cloned());
+    Schedule { rows }
+}
+
+// Annual rate at which the monthly payments repay `amount` received today.
+// Only defined when every payment is non-negative.
+fn solve_irr(amount: Decimal, payments: &[Decimal]) -> Option<Decimal> {
+    if payments.iter().any(|payment| *payment < dec!(0)) {
+        return None;
+    }
+    let present_value = |annual_rate: Decimal| -> Decimal {
+        let discount = dec!(1) / (dec!(1) + annual_rate / dec!(1200));
+        let mut factor = dec!(1);
+        let mut total = dec!(0);
+        for payment in payments {
+            factor *= discount;
+            total += payment * factor;
+        }
+        total
+    };
+
+    if present_value(dec!(0)) < amount {
+        return None;
+    }
+
+    // Doubled from 100% until it discounts the payments below `amount`
+    let mut low = dec!(0);
+    let mut high = dec!(100);
+    while present_value(high) > amount {
+        if high >= Decimal::from(crate::approx::MAX_RATE) {
+            return None;
+        }
+        low = high;
+        high *= dec!(2);
+    }
+    while high - low > dec!(0.0001) {
+        let mid = (low + high) / dec!(2);
+        if present_value(mid) > amount {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / dec!(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_bills_whole_cents_down_to_todays_balance() {
+        let calculator = LoanCalculator::from_config(crate::config::DEFAULT_CONFIG).unwrap();
+        let existing = ExistingLoan {
+            balance: dec!(180000),
+            annual_rate: dec!(6.5),
+            remaining_payments: 300,
+            payments_made: 60,
+        };
+        let history = calculator.existing_history(&existing);
+        assert_eq!(history.rows.len(), 60);
+        assert_eq!(history.rows.last().unwrap().balance, dec!(180000));
+        let mut balance = history.rows[0].balance + history.rows[0].payment - history.rows[0].interest;
+        for row in &history.rows {
+            assert_eq!(row.interest.round_dp(2), row.interest);
+            assert_eq!(balance + row.interest - row.payment, row.balance);
+            balance = row.balance;
+        }
+    }
+
+    #[test]
+    fn irr_is_found_above_100_percent() {
+        // 1,000 today repaid with two payments of 600 is 13.066% a month:
+        // 600/1.13066 + 600/1.13066^2 = 1,000
+        let rate = solve_irr(dec!(1000), &[dec!(600), dec!(600)]).unwrap();
+        assert!((rate - dec!(156.79)).abs() < dec!(0.01), "{}", rate);
+    }
+
+    #[test]
+    fn closing_costs_break_even_on_the_monthly_savings() {
+        let calculator = LoanCalculator::from_config(crate::config::DEFAULT_CONFIG).unwrap();
+        let existing = ExistingLoan {
+            balance: dec!(120000),
+            annual_rate: dec!(0),
+            remaining_payments: 120,
+            payments_made: 0,
+        };
+        // 1,000 a month over 120 payments becomes 500 over 240, so 3,200 of
+        // closing costs is repaid in 6.4 months, with the 7th payment
+        let refinance = calculator.calculate_rate_refinance(&existing, dec!(3200), dec!(0), 240);
+        assert_eq!(refinance.monthly_payment, dec!(500));
+        assert_eq!(refinance.monthly_savings, dec!(500));
+        assert_eq!(refinance.break_even_month, Some(7));
+        assert_eq!(refinance.interest_delta, dec!(0));
+
+        // A shorter term raises the payment, so it never breaks even
+        let refinance = calculator.calculate_rate_refinance(&existing, dec!(3200), dec!(0), 60);
+        assert_eq!(refinance.break_even_month, None);
+    }
+}
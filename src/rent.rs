@@ -0,0 +1,208 @@
+use std::io::IsTerminal;
+
+use colored::Colorize;
+use prettytable::{row, Table};
+use rust_decimal::prelude::*;
+
+use loancalc::escrow::HousingCosts;
+use loancalc::interest::InterestMethod;
+use loancalc::rentbuy::{Owning, RentVsBuy, Renting};
+use loancalc::{format_money, LoanCalculator, LoanType};
+
+use crate::cli::RentVsBuyArgs;
+use crate::i18n::{format_months, tr, trf};
+
+// Rents and home values can fall, but nothing else here can be negative
+fn percent_flag(value: f64, flag: &str, can_fall: bool) -> Result<Decimal, String> {
+    let too_low = if can_fall { value <= -100.0 } else { value < 0.0 };
+    if value.is_nan() || too_low || value >= 100.0 {
+        let range = if can_fall { "above -100" } else { "at least 0" };
+        return Err(format!("{} must be {} and below 100", flag, range));
+    }
+    Decimal::from_f64(value).ok_or_else(|| format!("{} must be a number", flag))
+}
+
+// A flag's amount, else asked for at a terminal with the initial answer
+// given, or `fallback` when nothing can be asked
+fn amount_or_prompt(
+    value: Option<f64>,
+    flag: &str,
+    prompt: &'static str,
+    initial: &str,
+    fallback: Option<Decimal>,
+) -> Result<Decimal, Box<dyn std::error::Error>> {
+    match (value, fallback) {
+        (Some(_), _) => Ok(crate::flag_amount(value, flag)?),
+        (None, _) if std::io::stdin().is_terminal() => crate::get_money_input(tr(prompt), initial),
+        (None, Some(fallback)) => Ok(fallback),
+        (None, None) => Err(format!("stdin is not a terminal, so {} must be given", flag).into()),
+    }
+}
+
+fn get_renting(args: &RentVsBuyArgs) -> Result<Renting, Box<dyn std::error::Error>> {
+    let monthly_rent = amount_or_prompt(args.rent, "--rent", "Monthly rent ($)", "2000", None)?;
+    if monthly_rent <= Decimal::ZERO {
+        return Err(tr("Rent must be more than 0").into());
+    }
+    Ok(Renting {
+        monthly_rent,
+        rent_increase: percent_flag(args.rent_increase, "--rent-increase", true)?,
+        monthly_insurance: crate::flag_amount(Some(args.renters_insurance), "--renters-insurance")?,
+    })
+}
+
+fn get_owning(args: &RentVsBuyArgs) -> Result<Owning, Box<dyn std::error::Error>> {
+    let price = amount_or_prompt(args.price, "--price", "Home price ($)", "400000", None)?;
+    let purchase = crate::check_purchase(&LoanType::Home, price, args.down).map_err(|e| format!("--price: {}", e))?;
+    Ok(Owning {
+        purchase,
+        housing: HousingCosts {
+            property_tax: amount_or_prompt(
+                args.property_tax,
+                "--property-tax",
+                "Yearly property tax ($)",
+                "3600",
+                Some(Decimal::ZERO),
+            )?,
+            insurance: amount_or_prompt(
+                args.insurance,
+                "--insurance",
+                "Yearly homeowner's insurance ($)",
+                "1500",
+                Some(Decimal::ZERO),
+            )?,
+            hoa_dues: crate::flag_amount(Some(args.hoa), "--hoa")?,
+        },
+        maintenance_rate: percent_flag(args.maintenance, "--maintenance", false)?,
+        appreciation_rate: percent_flag(args.appreciation, "--appreciation", true)?,
+        selling_cost_rate: percent_flag(args.selling_costs, "--selling-costs", false)?,
+    })
+}
+
+fn print_rent_vs_buy(comparison: &RentVsBuy, renting: &Renting) {
+    let mut table = Table::new();
+    table.set_titles(row![
+        tr("Year"),
+        tr("Rent Paid"),
+        tr("Owning Paid"),
+        tr("Home Value"),
+        tr("Loan Balance"),
+        tr("Left After Sale"),
+        tr("Net Cost of Buying"),
+        tr("Buying vs. Renting")
+    ]);
+    for year in &comparison.years {
+        table.add_row(row![
+            year.year,
+            format_money(year.rent_paid),
+            format_money(year.owning_paid),
+            format_money(year.home_value),
+            format_money(year.balance),
+            format_money(year.sale_proceeds),
+            format_money(year.net_buying_cost()),
+            crate::signed_money(year.buying_over_renting())
+        ]);
+    }
+    crate::print_table(&table);
+
+    let Some(last) = comparison.years.last() else {
+        return;
+    };
+    match comparison.break_even_year() {
+        Some(year) => println!(
+            "\n{} {}",
+            tr("Break-even:").green().bold(),
+            trf(
+                "buying comes out ahead of renting by the end of year {}, and by {} after {} years",
+                &[&year, &format_money(-last.buying_over_renting().min(Decimal::ZERO)), &last.year],
+            )
+        ),
+        None => println!(
+            "\n{} {}",
+            tr("No break-even:").yellow().bold(),
+            trf(
+                "renting costs {} less over {} years, starting at {} a month",
+                &[&format_money(last.buying_over_renting()), &last.year, &format_money(renting.monthly_rent)],
+            )
+        ),
+    }
+    println!(
+        "{}",
+        tr("Owning counts the down payment, closing costs, mortgage payments, PMI, property tax, insurance, \
+            dues and maintenance, less what selling the home would return. Money kept by renting is assumed \
+            to earn nothing.")
+    );
+}
+
+pub fn run(mut calculator: LoanCalculator, args: &RentVsBuyArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let owning = get_owning(args)?;
+    let renting = get_renting(args)?;
+    let term_months = crate::check_loan_term(&LoanType::Home, &args.term).map_err(|e| format!("--term: {}", e))?;
+    let credit_score = match args.score {
+        Some(score) => score,
+        None if std::io::stdin().is_terminal() => crate::get_valid_credit_score()?,
+        None => return Err("stdin is not a terminal, so --score must be given".into()),
+    };
+
+    let mut warnings = Vec::new();
+    calculator.use_market_rates(false, &mut warnings);
+    let mut declined = Vec::new();
+    let amount = owning.purchase.financed_amount();
+    let offers = crate::quote_banks(
+        &mut calculator,
+        &LoanType::Home,
+        amount,
+        term_months,
+        credit_score,
+        &mut declined,
+        &mut warnings,
+    );
+    for warning in &warnings {
+        eprintln!("{} {}", tr("Warning:").yellow().bold(), warning);
+    }
+    let method = InterestMethod::Amortizing;
+    let best = calculator
+        .rank_offers(&method, &LoanType::Home, &offers, amount, term_months, calculator.ranking())
+        .into_iter()
+        .next();
+    let Some((bank, rate)) = best.and_then(|best| offers.iter().find(|(name, _)| *name == best.name)) else {
+        for (bank, reason) in &declined {
+            eprintln!("{} {} {}", tr("Declined:").yellow().bold(), bank, reason);
+        }
+        return Err(tr("No bank offers this mortgage, so there is nothing to compare renting against").into());
+    };
+    let cost = calculator
+        .calculate_cost(&method, amount, *rate, term_months)
+        .ok_or(tr("The recommended mortgage cannot be paid off over this term"))?;
+    let apr = calculator.calculate_apr(&method, bank, &LoanType::Home, amount, *rate, term_months);
+
+    println!("\n{}", trf("Rent vs. Buy over {} years:", &[&args.years]));
+    println!(
+        "{}",
+        trf(
+            "Buying: {} home with {} down, financed by {} at {} over {}: {} a month",
+            &[
+                &format_money(owning.purchase.price),
+                &format_money(owning.purchase.down_payment),
+                bank,
+                &crate::format_offer_rate(&calculator, bank, *rate, apr),
+                &format_months(term_months),
+                &format_money(cost.monthly_payment),
+            ],
+        )
+    );
+    println!(
+        "{}",
+        trf(
+            "Renting: {} a month and {} of insurance, rising {}% a year",
+            &[
+                &format_money(renting.monthly_rent),
+                &format_money(renting.monthly_insurance),
+                &renting.rent_increase.normalize(),
+            ],
+        )
+    );
+    let comparison = calculator.compare_rent_vs_buy(bank, *rate, term_months, &renting, &owning, args.years);
+    print_rent_vs_buy(&comparison, &renting);
+    Ok(())
+}
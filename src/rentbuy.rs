@@ -0,0 +1,155 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::escrow::HousingCosts;
+use crate::purchase::Purchase;
+use crate::schedule::PaymentStrategy;
+use crate::{LoanCalculator, LoanType};
+
+/// Renting instead of buying: rent that goes up once a year, and renter's
+/// insurance.
+#[derive(Debug, Clone)]
+pub struct Renting {
+    pub monthly_rent: Decimal,
+    // Percent a year
+    pub rent_increase: Decimal,
+    pub monthly_insurance: Decimal,
+}
+
+/// The home bought and what owning it costs beyond the mortgage.
+#[derive(Debug, Clone)]
+pub struct Owning {
+    pub purchase: Purchase,
+    // Taxes, insurance and dues stay at today's amounts
+    pub housing: HousingCosts,
+    // Percents a year of the home's value
+    pub maintenance_rate: Decimal,
+    pub appreciation_rate: Decimal,
+    // Percent of the sale price paid to agents and in transfer taxes
+    pub selling_cost_rate: Decimal,
+}
+
+/// Where renting and buying stand at the end of a year.
+#[derive(Debug, Clone)]
+pub struct RentVsBuyYear {
+    pub year: u32,
+    // Everything paid out since the start
+    pub rent_paid: Decimal,
+    pub owning_paid: Decimal,
+    pub home_value: Decimal,
+    pub balance: Decimal,
+    // What selling the home would leave once the agents and the loan are paid
+    pub sale_proceeds: Decimal,
+}
+
+impl RentVsBuyYear {
+    // What owning cost, netting out what a sale would give back
+    pub fn net_buying_cost(&self) -> Decimal {
+        self.owning_paid - self.sale_proceeds
+    }
+
+    // Negative while buying comes out ahead
+    pub fn buying_over_renting(&self) -> Decimal {
+        self.net_buying_cost() - self.rent_paid
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RentVsBuy {
+    pub years: Vec<RentVsBuyYear>,
+}
+
+impl RentVsBuy {
+    // The first year that ends with buying and selling cheaper than renting
+    pub fn break_even_year(&self) -> Option<u32> {
+        self.years
+            .iter()
+            .find(|year| year.buying_over_renting() <= Decimal::ZERO)
+            .map(|year| year.year)
+    }
+}
+
+impl LoanCalculator {
+    // Buying with `bank_name`'s mortgage, paying the down payment and closing
+    // costs up front, against renting the same years
+    pub fn compare_rent_vs_buy(
+        &self,
+        bank_name: &str,
+        annual_rate: Decimal,
+        term_months: u32,
+        renting: &Renting,
+        owning: &Owning,
+        horizon_years: u32,
+    ) -> RentVsBuy {
+        let schedule = self.generate_schedule(
+            owning.purchase.financed_amount(),
+            annual_rate,
+            term_months,
+            &PaymentStrategy::Standard,
+        );
+        let pmi = self.calculate_pmi(bank_name, &owning.purchase, annual_rate, term_months);
+        let mut rent = renting.monthly_rent;
+        let mut home_value = owning.purchase.price;
+        let mut rent_paid = Decimal::ZERO;
+        let mut owning_paid = owning.purchase.down_payment + self.get_closing_costs(bank_name, &LoanType::Home);
+        let mut years = Vec::new();
+        for year in 1..=horizon_years {
+            // Upkeep is budgeted on what the home is worth as the year starts
+            let maintenance = (home_value * owning.maintenance_rate / dec!(100) / dec!(12)).round_dp(2);
+            for month in (year - 1) * 12 + 1..=year * 12 {
+                rent_paid += rent + renting.monthly_insurance;
+                owning_paid += schedule.payment_in(month) + owning.housing.monthly_total() + maintenance;
+                if let Some(pmi) = pmi.as_ref().filter(|pmi| month <= pmi.last_month) {
+                    owning_paid += pmi.monthly;
+                }
+            }
+            rent = (rent * (dec!(1) + renting.rent_increase / dec!(100))).round_dp(2);
+            home_value = (home_value * (dec!(1) + owning.appreciation_rate / dec!(100))).round_dp(2);
+            // Nothing is owed once the loan is paid off
+            let balance = schedule.balance_after(year * 12);
+            let sale_proceeds = (home_value * (dec!(1) - owning.selling_cost_rate / dec!(100))).round_dp(2) - balance;
+            years.push(RentVsBuyYear {
+                year,
+                rent_paid,
+                owning_paid,
+                home_value,
+                balance,
+                sale_proceeds,
+            });
+        }
+        RentVsBuy { years }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::purchase::DownPayment;
+    use crate::BankConfig;
+
+    #[test]
+    fn buying_breaks_even_once_the_sale_covers_its_costs() {
+        let bank = BankConfig::new("Bank").rates(&LoanType::Home, 0.0, 0.0).closing_costs(3000.0);
+        let calculator = LoanCalculator::builder().bank(bank).build().unwrap();
+        let renting = Renting {
+            monthly_rent: dec!(500),
+            rent_increase: dec!(0),
+            monthly_insurance: dec!(0),
+        };
+        let owning = Owning {
+            purchase: Purchase::new(dec!(125000), DownPayment::Amount(dec!(25000))).unwrap(),
+            housing: HousingCosts::default(),
+            maintenance_rate: dec!(1),
+            appreciation_rate: dec!(0),
+            selling_cost_rate: dec!(6),
+        };
+        let comparison = calculator.compare_rent_vs_buy("Bank", dec!(0), 120, &renting, &owning, 5);
+        // At 0% the payments only move equity from cash to the home, so
+        // buying costs the 3,000 closing, the 7,500 selling costs and
+        // 104.17 a month of upkeep: 10,500 + 1,250.04 a year, against
+        // 6,000 a year of rent
+        let years: Vec<Decimal> = comparison.years.iter().map(RentVsBuyYear::buying_over_renting).collect();
+        assert_eq!(years[..3], [dec!(5750.04), dec!(1000.08), dec!(-3749.88)]);
+        assert_eq!(comparison.break_even_year(), Some(3));
+    }
+}
@@ -0,0 +1,135 @@
+use std::fs;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::interest::InterestMethod;
+use crate::schedule::PaymentStrategy;
+use crate::provenance::Provenance;
+use crate::{format_money, format_rate, LoanCalculator, LoanType};
+
+// Results as handed to user templates. Values are preformatted the same way
+// as the tables so templates can drop them straight into text.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub loan_type: String,
+    pub amount: String,
+    pub term: String,
+    pub term_months: u32,
+    pub credit_score: u16,
+    pub interest_method: String,
+    pub closing_date: String,
+    pub first_payment_date: String,
+    pub offers: Vec<ReportOffer>,
+    // Year by year for the recommended offer, on amortizing loans
+    pub schedule: Option<ReportSchedule>,
+    pub recommended: Option<String>,
+    // How the numbers were reached, one sentence each
+    pub assumptions: Vec<String>,
+    pub warnings: Vec<String>,
+    pub provenance: Provenance,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportSchedule {
+    pub bank: String,
+    pub years: Vec<ReportYear>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportYear {
+    pub year: u32,
+    pub principal: String,
+    pub interest: String,
+    pub balance: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportOffer {
+    pub bank: String,
+    pub rate: String,
+    pub monthly_payment: String,
+    pub total_interest: String,
+    pub total_payment: String,
+}
+
+impl LoanCalculator {
+    pub fn report_offers(
+        &self,
+        method: &InterestMethod,
+        loan_type: &LoanType,
+        offers: &[(String, Decimal)],
+        loan_amount: Decimal,
+        loan_term: u32,
+    ) -> Vec<ReportOffer> {
+        offers
+            .iter()
+            .filter_map(|(name, rate)| {
+                let cost = self.calculate_cost(method, loan_amount, *rate, loan_term)?;
+                Some(ReportOffer {
+                    bank: name.clone(),
+                    rate: format_rate(*rate, self.calculate_apr(method, name, loan_type, loan_amount, *rate, loan_term)),
+                    monthly_payment: format_money(cost.monthly_payment),
+                    total_interest: format_money(cost.total_interest),
+                    total_payment: format_money(cost.total_payment),
+                })
+            })
+            .collect()
+    }
+
+    pub fn report_schedule(&self, bank: &str, loan_amount: Decimal, rate: Decimal, loan_term: u32) -> ReportSchedule {
+        let schedule = self.generate_schedule(loan_amount, rate, loan_term, &PaymentStrategy::Standard);
+        let years = schedule
+            .rows
+            .chunks(12)
+            .enumerate()
+            .map(|(index, rows)| {
+                let interest: Decimal = rows.iter().map(|row| row.interest).sum();
+                let paid: Decimal = rows.iter().map(|row| row.payment).sum();
+                ReportYear {
+                    year: index as u32 + 1,
+                    principal: format_money(paid - interest),
+                    interest: format_money(interest),
+                    balance: format_money(rows.last().map(|row| row.balance).unwrap_or_default()),
+                }
+            })
+            .collect();
+        ReportSchedule {
+            bank: bank.to_string(),
+            years,
+        }
+    }
+}
+
+// The reports `--export md` and `--export html` write
+const MARKDOWN_TEMPLATE: &str = include_str!("../templates/report.md.hbs");
+const HTML_TEMPLATE: &str = include_str!("../templates/report.html.hbs");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+// HTML escapes what it substitutes; Markdown is left as written
+pub fn render_report(format: ReportFormat, report: &Report) -> Result<String, Box<dyn std::error::Error>> {
+    let mut handlebars = handlebars::Handlebars::new();
+    handlebars.set_strict_mode(true);
+    let template = match format {
+        ReportFormat::Markdown => {
+            handlebars.register_escape_fn(handlebars::no_escape);
+            MARKDOWN_TEMPLATE
+        }
+        ReportFormat::Html => HTML_TEMPLATE,
+    };
+    Ok(handlebars.render_template(template, report)?)
+}
+
+// Renders a Handlebars template file against the report
+pub fn render_template(path: &str, report: &Report) -> Result<String, Box<dyn std::error::Error>> {
+    let template = fs::read_to_string(path)?;
+    let mut handlebars = handlebars::Handlebars::new();
+    handlebars.set_strict_mode(true);
+    handlebars.register_escape_fn(handlebars::no_escape);
+    Ok(handlebars.render_template(&template, report)?)
+}
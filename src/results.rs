@@ -0,0 +1,257 @@
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use crate::dates::Closing;
+use crate::dti::Income;
+use crate::escrow::HousingCosts;
+use crate::frequency::PaymentFrequency;
+use crate::interest::InterestMethod;
+use crate::provenance::Provenance;
+use crate::purchase::Purchase;
+use crate::student::Deferment;
+use crate::tax;
+use crate::{LoanCalculator, LoanType, RateQuotes};
+
+// Everything the comparison table shows, plus the banks that turned the
+// loan down and why, for `--output json`. Amounts are strings to the cent,
+// as in the application export.
+pub struct ComparisonResults<'a> {
+    pub loan_type: &'a LoanType,
+    pub amount: Decimal,
+    pub purchase: Option<&'a Purchase>,
+    pub housing: Option<&'a HousingCosts>,
+    pub deferment: Option<&'a Deferment>,
+    pub income: Option<&'a Income>,
+    // Marginal rate for the mortgage interest deduction, on home loans
+    pub tax_rate: Option<Decimal>,
+    // Each end of the range, for offers priced from one
+    pub rate_quotes: &'a [(String, RateQuotes)],
+    pub term_months: u32,
+    pub credit_score: u16,
+    pub closing: &'a Closing,
+    pub interest_method: &'a InterestMethod,
+    pub frequency: PaymentFrequency,
+    pub offers: &'a [(String, Decimal)],
+    // (bank name, reason)
+    pub declined: &'a [(String, String)],
+    // Offers left out by --sort and filter flags, with the reason
+    pub filtered: &'a [(String, String)],
+    pub warnings: &'a [String],
+    pub provenance: &'a Provenance,
+}
+
+fn amount(value: Decimal) -> Value {
+    Value::String(format!("{:.2}", value.round_dp(2)))
+}
+
+impl LoanCalculator {
+    pub fn comparison_document(&self, results: &ComparisonResults) -> Value {
+        let mut banks: Vec<Value> = results
+            .offers
+            .iter()
+            .map(|(name, rate)| {
+                let cost = self.calculate_cost(results.interest_method, results.amount, *rate, results.term_months);
+                let frequencies: Option<Vec<Value>> = (*results.interest_method == InterestMethod::Amortizing).then(|| {
+                    self.compare_frequencies(results.amount, *rate, results.term_months, results.frequency)
+                        .into_iter()
+                        .map(|cost| {
+                            json!({
+                                "frequency": cost.frequency.get_description(),
+                                "payment": amount(cost.payment),
+                                "num_payments": cost.num_payments,
+                                "total_interest": amount(cost.total_interest),
+                                "interest_saved": amount(cost.interest_saved),
+                            })
+                        })
+                        .collect()
+                });
+                let pmi = results
+                    .purchase
+                    .filter(|_| matches!(results.loan_type, LoanType::Home) && *results.interest_method == InterestMethod::Amortizing)
+                    .and_then(|purchase| self.calculate_pmi(name, purchase, *rate, results.term_months));
+                let dti = results.income.zip(cost.as_ref()).and_then(|(income, cost)| {
+                    let pmi_monthly = pmi.as_ref().map(|pmi| pmi.monthly).unwrap_or_default();
+                    let housing = results.housing.map(HousingCosts::monthly_total).unwrap_or_default();
+                    let new_payment = cost.monthly_payment + pmi_monthly + housing;
+                    self.check_dti(name, income, new_payment, matches!(results.loan_type, LoanType::Home))
+                });
+                let rate_range = results
+                    .rate_quotes
+                    .iter()
+                    .find(|(quoted, _)| quoted == name)
+                    .map(|(_, quotes)| {
+                        let quote = |rate: Decimal| {
+                            json!({
+                                "rate": rate.round_dp(4).to_string(),
+                                "monthly_payment": self
+                                    .calculate_cost(results.interest_method, results.amount, rate, results.term_months)
+                                    .map(|cost| amount(cost.monthly_payment)),
+                            })
+                        };
+                        json!({
+                            "best": quote(quotes.best),
+                            "midpoint": quote(quotes.midpoint),
+                            "worst": quote(quotes.worst),
+                        })
+                    });
+                let payoff = (*results.interest_method == InterestMethod::Amortizing).then(|| {
+                    self.project_payoff(
+                        results.amount,
+                        *rate,
+                        results.term_months,
+                        &results.closing.calendar(),
+                        pmi.as_ref(),
+                    )
+                });
+                let tax_savings = results
+                    .tax_rate
+                    .zip(payoff.as_ref())
+                    .map(|(tax_rate, payoff)| tax::tax_savings(results.amount, *rate, payoff, tax_rate));
+                let student_repayment = results.deferment.and_then(|deferment| {
+                    self.calculate_student_repayment(
+                        results.interest_method,
+                        results.amount,
+                        *rate,
+                        results.term_months,
+                        deferment,
+                    )
+                });
+                json!({
+                    "bank": name,
+                    "qualified": cost.is_some(),
+                    // Compounded monthly, as every amount here is figured
+                    "rate": rate.round_dp(4).to_string(),
+                    "compounding": self.compounding(name).get_name(),
+                    "quoted_rate": self.quoted_rate(name, *rate).round_dp(4).to_string(),
+                    "rate_range": rate_range,
+                    "apr": self
+                        .calculate_apr(
+                            results.interest_method,
+                            name,
+                            results.loan_type,
+                            results.amount,
+                            *rate,
+                            results.term_months,
+                        )
+                        .map(|apr| apr.round_dp(4).to_string()),
+                    "monthly_payment": cost.as_ref().map(|cost| amount(cost.monthly_payment)),
+                    "total_interest": cost.as_ref().map(|cost| amount(cost.total_interest)),
+                    "total_payment": cost.as_ref().map(|cost| amount(cost.total_payment)),
+                    "closing_costs": amount(self.get_closing_costs(name, results.loan_type)),
+                    "prepaid_finance_charges": amount(self.prepaid_finance_charges(name, results.loan_type, results.amount)),
+                    "rating": self.get_rating(name).map(|rating| rating.round_dp(2).to_string()),
+                    "payment_frequencies": frequencies,
+                    // Payment, PMI, escrow and HOA dues together
+                    "monthly_housing_cost": results.housing.zip(cost.as_ref()).map(|(housing, cost)| {
+                        let pmi_monthly = pmi.as_ref().map(|pmi| pmi.monthly).unwrap_or_default();
+                        amount(cost.monthly_payment + pmi_monthly + housing.monthly_total())
+                    }),
+                    "student_repayment": student_repayment.map(|repayment| json!({
+                        "accrued_interest": amount(repayment.accrued_interest),
+                        "capitalized_principal": amount(repayment.capitalized_principal),
+                        "monthly_payment": amount(repayment.monthly_payment),
+                        "uncapitalized_payment": amount(repayment.uncapitalized_payment),
+                        "total_interest": amount(repayment.total_interest),
+                    })),
+                    "dti": dti.map(|dti| json!({
+                        "front_end": dti.front_end.map(|ratio| ratio.round_dp(2).to_string()),
+                        "back_end": dti.back_end.round_dp(2).to_string(),
+                        "max_front_end": dti.max_front_end.to_string(),
+                        "max_back_end": dti.max_back_end.to_string(),
+                        "within_limits": dti.fits(),
+                    })),
+                    "pmi": pmi.map(|pmi| json!({
+                        "monthly": amount(pmi.monthly),
+                        "last_month": pmi.last_month,
+                        "drops_on": payoff
+                            .as_ref()
+                            .and_then(|payoff| payoff.pmi_drops)
+                            .map(|date| date.to_string()),
+                        "total": amount(pmi.total),
+                    })),
+                    "tax_savings": tax_savings.map(|savings| json!({
+                        "deductible_interest": amount(savings.deductible_interest),
+                        "tax_saved": amount(savings.tax_saved),
+                        "after_tax_interest": amount(savings.after_tax_interest),
+                        "after_tax_rate": savings.after_tax_rate.round_dp(4).to_string(),
+                        "by_year": savings
+                            .by_year
+                            .iter()
+                            .map(|(year, saved)| json!({ "year": year, "tax_saved": amount(*saved) }))
+                            .collect::<Vec<_>>(),
+                    })),
+                    "payoff_date": payoff.as_ref().map(|payoff| payoff.payoff_date.to_string()),
+                    "interest_by_calendar_year": payoff.as_ref().map(|payoff| {
+                        payoff
+                            .interest_by_year
+                            .iter()
+                            .map(|(year, interest)| json!({ "year": year, "interest": amount(*interest) }))
+                            .collect::<Vec<_>>()
+                    }),
+                })
+            })
+            .collect();
+        banks.extend(results.filtered.iter().map(|(name, reason)| {
+            json!({
+                "bank": name,
+                "qualified": true,
+                "filtered_out": reason,
+            })
+        }));
+        banks.extend(results.declined.iter().map(|(name, reason)| {
+            json!({
+                "bank": name,
+                "qualified": false,
+                "reason": reason,
+            })
+        }));
+
+        let recommended = self
+            .rank_offers(
+                results.interest_method,
+                results.loan_type,
+                results.offers,
+                results.amount,
+                results.term_months,
+                self.ranking(),
+            )
+            .into_iter()
+            .next()
+            .map(|best| best.name);
+        let market_rate = self.market_rate(results.loan_type).map(|market_rate| {
+                json!({
+                    "rate": format!("{:.2}", market_rate.rate),
+                    "as_of": market_rate.as_of.map(|date| date.to_string()),
+                    "source": market_rate.source,
+                    "adjustment": market_rate.adjustment.map(|adjustment| adjustment.to_string()),
+                })
+            });
+
+        json!({
+            "loan": {
+                "type": results.loan_type.get_name(),
+                "currency": crate::money::money_format().currency.code,
+                "amount": amount(results.amount),
+                "purchase_price": results.purchase.map(|purchase| amount(purchase.price)),
+                "down_payment": results.purchase.map(|purchase| amount(purchase.down_payment)),
+                "loan_to_value": results.purchase.map(|purchase| purchase.loan_to_value().round_dp(2).to_string()),
+                "property_tax": results.housing.map(|housing| amount(housing.property_tax)),
+                "insurance": results.housing.map(|housing| amount(housing.insurance)),
+                "hoa_dues": results.housing.map(|housing| amount(housing.hoa_dues)),
+                "in_school_months": results.deferment.map(|deferment| deferment.in_school_months),
+                "grace_months": results.deferment.map(|deferment| deferment.grace_months),
+                "term_months": results.term_months,
+                "credit_score": results.credit_score,
+                "interest_method": results.interest_method.get_description(),
+                "payment_frequency": results.frequency.get_description(),
+                "closing_date": results.closing.date.to_string(),
+                "first_payment_date": results.closing.first_payment_date().to_string(),
+            },
+            "banks": banks,
+            "market_rate": market_rate,
+            "recommended": recommended,
+            "warnings": results.warnings,
+            "provenance": results.provenance,
+        })
+    }
+}
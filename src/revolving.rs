@@ -0,0 +1,254 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::{format_money, Error};
+
+// A plan that has not cleared the debts in 50 years never will
+const MAX_MONTHS: u32 = 600;
+
+/// A credit card or other revolving balance, charged its APR monthly.
+#[derive(Debug, Clone)]
+pub struct Debt {
+    pub name: String,
+    pub balance: Decimal,
+    pub apr: Decimal,
+    pub minimum: Decimal,
+}
+
+/// Which debt gets the budget left over after every minimum is paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayoffOrder {
+    // The highest APR first, which costs the least interest
+    Avalanche,
+    // The smallest balance first, which clears whole debts soonest
+    Snowball,
+}
+
+pub const PAYOFF_ORDERS: [PayoffOrder; 2] = [PayoffOrder::Avalanche, PayoffOrder::Snowball];
+
+impl PayoffOrder {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            PayoffOrder::Avalanche => "Avalanche",
+            PayoffOrder::Snowball => "Snowball",
+        }
+    }
+
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            PayoffOrder::Avalanche => "Avalanche (highest APR first)",
+            PayoffOrder::Snowball => "Snowball (smallest balance first)",
+        }
+    }
+
+    // Indexes of `debts` in the order they are paid down
+    fn rank(&self, debts: &[Debt]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..debts.len()).collect();
+        match self {
+            PayoffOrder::Avalanche => order.sort_by(|a, b| {
+                debts[*b]
+                    .apr
+                    .cmp(&debts[*a].apr)
+                    .then(debts[*a].balance.cmp(&debts[*b].balance))
+            }),
+            PayoffOrder::Snowball => order.sort_by(|a, b| {
+                debts[*a]
+                    .balance
+                    .cmp(&debts[*b].balance)
+                    .then(debts[*b].apr.cmp(&debts[*a].apr))
+            }),
+        }
+        order
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DebtPayoff {
+    pub name: String,
+    // The month of the last payment on it, counting from 1
+    pub paid_off_month: u32,
+    pub interest: Decimal,
+}
+
+/// Every debt paid off with one budget, in the order they are cleared.
+#[derive(Debug, Clone)]
+pub struct PayoffPlan {
+    pub order: PayoffOrder,
+    pub debts: Vec<DebtPayoff>,
+}
+
+impl PayoffPlan {
+    pub fn months(&self) -> u32 {
+        self.debts.iter().map(|debt| debt.paid_off_month).max().unwrap_or(0)
+    }
+
+    pub fn total_interest(&self) -> Decimal {
+        self.debts.iter().map(|debt| debt.interest).sum()
+    }
+}
+
+fn check_debts(debts: &[Debt], budget: Decimal) -> Result<(), Error> {
+    if debts.is_empty() {
+        return Err(Error::InvalidLoan("there are no debts to pay off".to_string()));
+    }
+    if let Some(debt) = debts.iter().find(|debt| {
+        debt.balance <= Decimal::ZERO
+            || debt.apr < Decimal::ZERO
+            || debt.apr >= dec!(100)
+            || debt.minimum <= Decimal::ZERO
+    }) {
+        return Err(Error::InvalidLoan(format!(
+            "{} needs a balance and minimum payment above 0 and an APR from 0 to below 100",
+            debt.name
+        )));
+    }
+    if let Some((_, debt)) = debts
+        .iter()
+        .enumerate()
+        .find(|(index, debt)| debts[..*index].iter().any(|earlier| earlier.name == debt.name))
+    {
+        return Err(Error::InvalidLoan(format!(
+            "{} is listed more than once; give each debt its own name",
+            debt.name
+        )));
+    }
+    let minimums: Decimal = debts.iter().map(|debt| debt.minimum).sum();
+    if budget < minimums {
+        return Err(Error::InvalidLoan(format!(
+            "the budget of {} does not cover the minimum payments of {}",
+            format_money(budget),
+            format_money(minimums)
+        )));
+    }
+    Ok(())
+}
+
+// Each month every balance is charged a twelfth of its APR, every minimum
+// is paid, and whatever is left of the budget goes to the first debt in
+// `order` that is still owed, spilling over to the next once it is cleared.
+// A cleared debt's minimum stays in the budget for the others.
+pub fn plan_payoff(debts: &[Debt], budget: Decimal, order: PayoffOrder) -> Result<PayoffPlan, Error> {
+    check_debts(debts, budget)?;
+    let ranked = order.rank(debts);
+    let mut balances: Vec<Decimal> = debts.iter().map(|debt| debt.balance).collect();
+    let mut interest = vec![Decimal::ZERO; debts.len()];
+    let mut paid_off: Vec<Option<u32>> = vec![None; debts.len()];
+    let mut month = 0;
+    while paid_off.iter().any(Option::is_none) {
+        month += 1;
+        if month > MAX_MONTHS {
+            return Err(Error::InvalidLoan(format!(
+                "a budget of {} never pays off these debts; it needs to be more than the interest they are charged",
+                format_money(budget)
+            )));
+        }
+        let mut left = budget;
+        for (index, debt) in debts.iter().enumerate() {
+            if paid_off[index].is_some() {
+                continue;
+            }
+            let charge = (balances[index] * debt.apr / dec!(1200)).round_dp(2);
+            interest[index] += charge;
+            balances[index] += charge;
+            let payment = debt.minimum.min(balances[index]);
+            balances[index] -= payment;
+            left -= payment;
+        }
+        for index in &ranked {
+            if left <= Decimal::ZERO {
+                break;
+            }
+            let payment = left.min(balances[*index]);
+            balances[*index] -= payment;
+            left -= payment;
+        }
+        for (index, balance) in balances.iter().enumerate() {
+            if paid_off[index].is_none() && *balance <= Decimal::ZERO {
+                paid_off[index] = Some(month);
+            }
+        }
+    }
+
+    // Debts cleared the same month stay in the order they were paid down
+    let mut cleared: Vec<usize> = ranked.clone();
+    cleared.sort_by_key(|index| paid_off[*index]);
+    let debts = cleared
+        .into_iter()
+        .map(|index| DebtPayoff {
+            name: debts[index].name.clone(),
+            paid_off_month: paid_off[index].unwrap_or(0),
+            interest: interest[index],
+        })
+        .collect();
+    Ok(PayoffPlan { order, debts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debt(name: &str, balance: Decimal, apr: Decimal, minimum: Decimal) -> Debt {
+        Debt {
+            name: name.to_string(),
+            balance,
+            apr,
+            minimum,
+        }
+    }
+
+    fn cards() -> Vec<Debt> {
+        vec![
+            debt("Visa", dec!(6000), dec!(29.9), dec!(180)),
+            debt("Store", dec!(500), dec!(12), dec!(25)),
+            debt("Car", dec!(3000), dec!(9), dec!(90)),
+        ]
+    }
+
+    #[test]
+    fn interest_free_debt_clears_on_schedule() {
+        let plan = plan_payoff(
+            &[debt("Loan", dec!(1000), dec!(0), dec!(100))],
+            dec!(100),
+            PayoffOrder::Avalanche,
+        )
+        .unwrap();
+        assert_eq!(plan.months(), 10);
+        assert_eq!(plan.total_interest(), dec!(0));
+    }
+
+    #[test]
+    fn orders_pay_down_different_debts_first() {
+        let avalanche = plan_payoff(&cards(), dec!(600), PayoffOrder::Avalanche).unwrap();
+        let snowball = plan_payoff(&cards(), dec!(600), PayoffOrder::Snowball).unwrap();
+        assert_eq!(snowball.debts[0].name, "Store");
+        assert_eq!(avalanche.debts.last().unwrap().name, "Car");
+        // Paying the highest APR first never costs more interest
+        assert!(avalanche.total_interest() <= snowball.total_interest());
+        assert!(
+            snowball.debts[0].paid_off_month
+                < avalanche
+                    .debts
+                    .iter()
+                    .find(|payoff| payoff.name == "Store")
+                    .unwrap()
+                    .paid_off_month
+        );
+    }
+
+    #[test]
+    fn budget_must_cover_minimums_and_interest() {
+        assert!(plan_payoff(&cards(), dec!(200), PayoffOrder::Avalanche).is_err());
+        // $50 a month never catches up with 30% on $10,000
+        let deep = [debt("Card", dec!(10000), dec!(30), dec!(50))];
+        assert!(plan_payoff(&deep, dec!(60), PayoffOrder::Snowball).is_err());
+    }
+
+    #[test]
+    fn debts_need_their_own_names() {
+        let twice = [
+            debt("Card", dec!(100), dec!(10), dec!(5)),
+            debt("Card", dec!(200), dec!(10), dec!(5)),
+        ];
+        assert!(plan_payoff(&twice, dec!(50), PayoffOrder::Avalanche).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::Error;
+
+// The inputs of a comparison, saved under a name so it can be run again.
+// Fields mirror the command-line flags they are replayed through; anything
+// left out is asked for as usual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub saved_at: NaiveDateTime,
+    pub loan_type: String,
+    // Either the amount borrowed or a purchase price and down payment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub down_payment: Option<f64>,
+    pub term_months: u32,
+    pub credit_score: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_frequency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_tax: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insurance: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hoa_dues: Option<f64>,
+    // A car purchase's sales tax, trade-in and dealer fees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sales_tax_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trade_in_value: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trade_in_payoff: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dealer_fees: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_school_months: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grace_months: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gross_monthly_income: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_debts: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prepayments: Vec<ScenarioPrepayment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioPrepayment {
+    pub amount: f64,
+    pub month: u32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScenarioFile {
+    scenarios: Vec<Scenario>,
+}
+
+// Saved scenarios, kept in one JSON file in the user data directory
+pub struct ScenarioStore {
+    path: PathBuf,
+    scenarios: Vec<Scenario>,
+}
+
+impl ScenarioStore {
+    pub fn default_path() -> Option<PathBuf> {
+        config::user_data_dir().map(|dir| dir.join("scenarios.json"))
+    }
+
+    // A store that does not exist yet is empty
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let scenarios = match fs::read_to_string(path) {
+            Ok(contents) => {
+                let file: ScenarioFile = serde_json::from_str(&contents).map_err(|e| Error::Parse {
+                    file: path.display().to_string(),
+                    field: None,
+                    message: e.to_string(),
+                })?;
+                file.scenarios
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(ScenarioStore {
+            path: path.to_path_buf(),
+            scenarios,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn scenarios(&self) -> &[Scenario] {
+        &self.scenarios
+    }
+
+    // Names match regardless of case
+    pub fn get(&self, name: &str) -> Option<&Scenario> {
+        self.scenarios.iter().find(|scenario| scenario.name.eq_ignore_ascii_case(name))
+    }
+
+    // Replaces any scenario of the same name
+    pub fn save(&mut self, scenario: Scenario) -> Result<(), Error> {
+        match self.scenarios.iter_mut().find(|saved| saved.name.eq_ignore_ascii_case(&scenario.name)) {
+            Some(saved) => *saved = scenario,
+            None => self.scenarios.push(scenario),
+        }
+        self.write()
+    }
+
+    // False when there was no scenario by that name
+    pub fn remove(&mut self, name: &str) -> Result<bool, Error> {
+        let count = self.scenarios.len();
+        self.scenarios.retain(|scenario| !scenario.name.eq_ignore_ascii_case(name));
+        if self.scenarios.len() == count {
+            return Ok(false);
+        }
+        self.write()?;
+        Ok(true)
+    }
+
+    fn write(&self) -> Result<(), Error> {
+        let file = ScenarioFile {
+            scenarios: self.scenarios.clone(),
+        };
+        let contents = serde_json::to_string_pretty(&file).map_err(|e| Error::Config(e.to_string()))?;
+        config::write_atomically(&self.path, &contents)?;
+        Ok(())
+    }
+}
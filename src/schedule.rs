@@ -0,0 +1,414 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::conventions::{CentRate, Rounding};
+use crate::money::money_format;
+use crate::{format_money, LoanCalculator};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaymentStrategy {
+    Standard,
+    RoundUp { increment: Decimal },
+    // One extra scheduled payment each year, made with payment `month` of the loan year
+    AnnualExtra { month: u32 },
+    // Savings held in an offset account are netted against the balance before interest
+    Offset { initial_balance: Decimal, monthly_deposit: Decimal },
+    // A one-time prepayment made with payment `month`
+    LumpSum { amount: Decimal, month: u32 },
+    // The same extra principal added to every payment
+    ExtraMonthly { amount: Decimal },
+}
+
+impl PaymentStrategy {
+    pub fn get_description(&self) -> String {
+        match self {
+            PaymentStrategy::Standard => "Standard".to_string(),
+            PaymentStrategy::RoundUp { increment } => {
+                format!("Round up to {}", money_format().with_symbol(&increment.to_string()))
+            }
+            PaymentStrategy::AnnualExtra { month } => {
+                format!("13th payment (month {} of each year)", month)
+            }
+            PaymentStrategy::Offset { initial_balance, monthly_deposit } => format!(
+                "Offset account ({} + {}/mo)",
+                format_money(*initial_balance),
+                format_money(*monthly_deposit)
+            ),
+            PaymentStrategy::LumpSum { amount, month } => {
+                format!("{} prepaid at month {}", format_money(*amount), month)
+            }
+            PaymentStrategy::ExtraMonthly { amount } => format!("{} extra each month", format_money(*amount)),
+        }
+    }
+
+    fn payment_for(&self, number: u32, scheduled_payment: Decimal) -> Decimal {
+        match self {
+            PaymentStrategy::Standard => scheduled_payment,
+            PaymentStrategy::RoundUp { increment } => {
+                (scheduled_payment / increment).ceil() * increment
+            }
+            PaymentStrategy::AnnualExtra { month } if (number - 1) % 12 + 1 == *month => {
+                scheduled_payment * dec!(2)
+            }
+            PaymentStrategy::LumpSum { amount, month } if number == *month => {
+                scheduled_payment + amount
+            }
+            PaymentStrategy::ExtraMonthly { amount } => scheduled_payment + amount,
+            PaymentStrategy::AnnualExtra { .. }
+            | PaymentStrategy::Offset { .. }
+            | PaymentStrategy::LumpSum { .. } => scheduled_payment,
+        }
+    }
+
+    fn initial_offset(&self) -> Decimal {
+        match self {
+            PaymentStrategy::Offset { initial_balance, .. } => *initial_balance,
+            _ => dec!(0),
+        }
+    }
+
+    fn offset_deposit(&self) -> Decimal {
+        match self {
+            PaymentStrategy::Offset { monthly_deposit, .. } => *monthly_deposit,
+            _ => dec!(0),
+        }
+    }
+}
+
+// A one-time payment toward principal, made with payment `month`. Several
+// can be layered onto any strategy with `ScheduleRows::with_prepayments`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Prepayment {
+    pub amount: Decimal,
+    pub month: u32,
+}
+
+impl Prepayment {
+    pub fn get_description(&self) -> String {
+        format!("{} at month {}", format_money(self.amount), self.month)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleRow {
+    pub payment: Decimal,
+    pub interest: Decimal,
+    pub balance: Decimal,
+    pub offset_balance: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub rows: Vec<ScheduleRow>,
+}
+
+impl Schedule {
+    pub fn num_payments(&self) -> u32 {
+        self.rows.len() as u32
+    }
+
+    pub fn total_interest(&self) -> Decimal {
+        self.rows.iter().map(|row| row.interest).sum()
+    }
+
+    // Payment made in the given month, zero before the first and once the
+    // loan is paid off
+    pub fn payment_in(&self, month: u32) -> Decimal {
+        (month as usize)
+            .checked_sub(1)
+            .and_then(|index| self.rows.get(index))
+            .map(|row| row.payment)
+            .unwrap_or(dec!(0))
+    }
+
+    pub fn interest_in(&self, month: u32) -> Decimal {
+        (month as usize)
+            .checked_sub(1)
+            .and_then(|index| self.rows.get(index))
+            .map(|row| row.interest)
+            .unwrap_or(dec!(0))
+    }
+
+    pub fn interest_through(&self, month: u32) -> Decimal {
+        self.rows.iter().take(month as usize).map(|row| row.interest).sum()
+    }
+
+    pub fn balance_after(&self, month: u32) -> Decimal {
+        (month as usize)
+            .checked_sub(1)
+            .and_then(|index| self.rows.get(index))
+            .map(|row| row.balance)
+            .unwrap_or(dec!(0))
+    }
+}
+
+// Produces schedule rows one month at a time, so callers that only stream
+// or summarize rows never hold the whole schedule
+pub struct ScheduleRows {
+    monthly_rate: CentRate,
+    rounding: Rounding,
+    num_payments: u32,
+    scheduled_payment: Decimal,
+    // The payment in cents for strategies that pay the same every month
+    level_payment: Option<i128>,
+    strategy: PaymentStrategy,
+    prepayments: Vec<Prepayment>,
+    number: u32,
+    // In cents, which every row bills in
+    balance: i128,
+    offset_balance: Decimal,
+}
+
+impl ScheduleRows {
+    pub fn with_prepayments(mut self, prepayments: &[Prepayment]) -> Self {
+        self.prepayments.extend_from_slice(prepayments);
+        self
+    }
+}
+
+impl Iterator for ScheduleRows {
+    type Item = ScheduleRow;
+
+    fn next(&mut self) -> Option<ScheduleRow> {
+        if self.number >= self.num_payments || self.balance == 0 {
+            return None;
+        }
+        self.number += 1;
+
+        let interest = match u64::try_from(self.balance) {
+            Ok(balance) if self.offset_balance.is_zero() => self.monthly_rate.interest_cents(balance).map(i128::from),
+            _ => None,
+        }
+        .or_else(|| {
+            let accruing = (self.rounding.amount_of(self.balance) - self.offset_balance).max(dec!(0));
+            self.rounding.cents_of(self.monthly_rate.interest(accruing))
+        })?;
+        let due = self.balance + interest;
+        let payment = if self.number == self.num_payments {
+            due
+        } else {
+            let prepaid = self.prepayments.iter().any(|prepayment| prepayment.month == self.number);
+            let payment = match self.level_payment {
+                Some(payment) if !prepaid => payment,
+                _ => {
+                    let mut payment = self.strategy.payment_for(self.number, self.scheduled_payment);
+                    for prepayment in self.prepayments.iter().filter(|prepayment| prepayment.month == self.number) {
+                        payment += prepayment.amount;
+                    }
+                    self.rounding.cents_of(self.rounding.round_cents(payment))?
+                }
+            };
+            payment.min(due)
+        };
+
+        self.balance = due - payment;
+        let deposit = self.strategy.offset_deposit();
+        if !deposit.is_zero() {
+            self.offset_balance += deposit;
+        }
+        Some(ScheduleRow {
+            payment: self.rounding.amount_of(payment),
+            interest: self.rounding.amount_of(interest),
+            balance: self.rounding.amount_of(self.balance),
+            offset_balance: self.offset_balance,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.balance == 0 {
+            0
+        } else {
+            (self.num_payments - self.number) as usize
+        };
+        (remaining.min(1), Some(remaining))
+    }
+}
+
+impl LoanCalculator {
+    // Walks the loan month by month so payments above the scheduled amount
+    // go to principal and shorten the term. Payments and interest are billed
+    // to the cent, as `exact_amortization` bills them, and the last payment
+    // clears whatever balance is left. An offset account is tracked alongside the loan and
+    // only the balance above it accrues interest.
+    pub fn schedule_rows(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        strategy: &PaymentStrategy,
+    ) -> ScheduleRows {
+        let rounding = self.rounding();
+        let scheduled_payment = rounding.round_cents(self.calculate_monthly_payment(principal, annual_rate, num_payments));
+        let level_payment = match strategy {
+            PaymentStrategy::AnnualExtra { .. } | PaymentStrategy::LumpSum { .. } => None,
+            _ => rounding.cents_of(rounding.round_cents(strategy.payment_for(1, scheduled_payment))),
+        };
+        ScheduleRows {
+            monthly_rate: CentRate::new(rounding, annual_rate / dec!(100) / dec!(12)),
+            rounding,
+            num_payments,
+            scheduled_payment,
+            level_payment,
+            strategy: *strategy,
+            prepayments: Vec::new(),
+            number: 0,
+            balance: rounding.cents_of(rounding.round_cents(principal)).unwrap_or_default(),
+            offset_balance: strategy.initial_offset(),
+        }
+    }
+
+    pub fn generate_schedule(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        strategy: &PaymentStrategy,
+    ) -> Schedule {
+        let mut rows = Vec::with_capacity(num_payments as usize);
+        rows.extend(self.schedule_rows(principal, annual_rate, num_payments, strategy));
+        Schedule { rows }
+    }
+
+    pub fn generate_prepaid_schedule(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        prepayments: &[Prepayment],
+    ) -> Schedule {
+        let rows = self
+            .schedule_rows(principal, annual_rate, num_payments, &PaymentStrategy::Standard)
+            .with_prepayments(prepayments);
+        Schedule { rows: rows.collect() }
+    }
+
+    // Interest-only payments at `io_rate` for `io_months`, then the balance
+    // amortizes over `amortizing_months` at `amortizing_rate`. The interest
+    // is billed to the cent.
+    pub fn generate_interest_only_schedule(
+        &self,
+        principal: Decimal,
+        io_rate: Decimal,
+        io_months: u32,
+        amortizing_rate: Decimal,
+        amortizing_months: u32,
+    ) -> Schedule {
+        let principal = self.rounding().round_cents(principal);
+        let interest = self.rounding().round_product(principal, io_rate / dec!(100) / dec!(12));
+        let mut rows: Vec<ScheduleRow> = (0..io_months)
+            .map(|_| ScheduleRow {
+                payment: interest,
+                interest,
+                balance: principal,
+                offset_balance: dec!(0),
+            })
+            .collect();
+
+        let amortizing = self.generate_schedule(
+            principal,
+            amortizing_rate,
+            amortizing_months,
+            &PaymentStrategy::Standard,
+        );
+        rows.extend(amortizing.rows);
+
+        Schedule { rows }
+    }
+
+    // Payments amortize over `amortization_months`, but whatever balance is
+    // left at `balloon_month` comes due with that payment
+    pub fn generate_balloon_schedule(
+        &self,
+        principal: Decimal,
+        annual_rate: Decimal,
+        amortization_months: u32,
+        balloon_month: u32,
+    ) -> Schedule {
+        let mut schedule = self.generate_schedule(
+            principal,
+            annual_rate,
+            amortization_months,
+            &PaymentStrategy::Standard,
+        );
+
+        if (balloon_month as usize) < schedule.rows.len() {
+            schedule.rows.truncate(balloon_month as usize);
+            if let Some(last_row) = schedule.rows.last_mut() {
+                last_row.payment += last_row.balance;
+                last_row.balance = dec!(0);
+            }
+        }
+
+        schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arm::{ArmScenario, ArmTerms};
+    use crate::config::DEFAULT_CONFIG;
+    use crate::exact_amortization;
+
+    #[test]
+    fn schedule_bills_what_exact_amortization_totals() {
+        let calculator = LoanCalculator::from_config(DEFAULT_CONFIG).unwrap();
+        for (principal, rate, num_payments) in
+            [(dec!(200000), dec!(6), 360), (dec!(25000), dec!(7.25), 60), (dec!(1000), dec!(0), 3)]
+        {
+            let schedule = calculator.generate_schedule(principal, rate, num_payments, &PaymentStrategy::Standard);
+            let exact = exact_amortization(principal, rate, num_payments, calculator.rounding()).unwrap();
+            assert_eq!(schedule.num_payments(), num_payments);
+            assert_eq!(schedule.total_interest(), exact.total_interest);
+            assert_eq!(schedule.rows.iter().map(|row| row.payment).sum::<Decimal>(), exact.total_payment);
+            assert_eq!(schedule.payment_in(num_payments), exact.final_payment);
+            // Months outside the term have nothing in them
+            assert_eq!(schedule.payment_in(0), dec!(0));
+            assert_eq!(schedule.interest_in(0), dec!(0));
+            assert_eq!(schedule.balance_after(num_payments + 1), dec!(0));
+            assert!(schedule.rows.iter().all(|row| row.interest.round_dp(2) == row.interest));
+
+            // Interest-only months bill the same cents before the loan
+            // amortizes exactly as above
+            let interest_only = calculator.generate_interest_only_schedule(principal, rate, 12, rate, num_payments);
+            let io_interest = calculator.rounding().round_cents(principal * rate / dec!(1200));
+            assert!(interest_only.rows[..12].iter().all(|row| row.interest == io_interest && row.payment == io_interest));
+            assert_eq!(interest_only.total_interest(), io_interest * dec!(12) + exact.total_interest);
+
+            // An ARM that never resets within the term is the fixed loan
+            let terms = ArmTerms {
+                initial_rate: rate,
+                fixed_months: num_payments,
+                adjustment_months: 12,
+                margin: dec!(2.75),
+                initial_cap: dec!(2),
+                periodic_cap: dec!(2),
+                lifetime_cap: dec!(5),
+            };
+            let arm = calculator.generate_arm_schedule(principal, num_payments, &terms, &ArmScenario::WorstCase);
+            assert_eq!(arm.total_interest(), exact.total_interest);
+            assert_eq!(arm.payment_in(1), exact.payment);
+            assert_eq!(arm.payment_in(num_payments), exact.final_payment);
+        }
+    }
+
+    #[test]
+    fn resetting_arm_bills_whole_cents() {
+        let calculator = LoanCalculator::from_config(DEFAULT_CONFIG).unwrap();
+        let terms = ArmTerms {
+            initial_rate: dec!(3),
+            fixed_months: 60,
+            adjustment_months: 12,
+            margin: dec!(2.75),
+            initial_cap: dec!(2),
+            periodic_cap: dec!(2),
+            lifetime_cap: dec!(5),
+        };
+        let arm = calculator.generate_arm_schedule(dec!(300000), 360, &terms, &ArmScenario::WorstCase);
+        assert_eq!(arm.balance_after(360), dec!(0));
+        for row in &arm.rows {
+            assert_eq!(row.interest.round_dp(2), row.interest);
+            assert_eq!(row.payment.round_dp(2), row.payment);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::io::Read;
+
+use colored::Colorize;
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use loancalc::dates::{Closing, PaymentDay, StubInterest};
+use loancalc::frequency::PaymentFrequency;
+use loancalc::interest::InterestMethod;
+use loancalc::{results, LoanCalculator, LoanType};
+
+use crate::i18n::{tr, trf};
+
+// Far more than any comparison request needs, so a client cannot make the
+// server buffer whatever it sends
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+// The body of POST /compare, named as in the `--output json` document
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompareRequest {
+    loan_type: String,
+    amount: f64,
+    term_months: u32,
+    credit_score: u16,
+    #[serde(default)]
+    payment_frequency: Option<String>,
+}
+
+// The comparison a scripted run would print: closing today with payments on
+// the 1st, priced by the loan type's interest convention
+fn compare(calculator: &mut LoanCalculator, body: &str) -> Result<Value, String> {
+    let request: CompareRequest = serde_json::from_str(body).map_err(|e| trf("invalid request: {}", &[&e]))?;
+    let loan_type = LoanType::from_name(&request.loan_type)
+        .ok_or_else(|| trf("unknown loan_type '{}' (expected home, car, personal or student)", &[&request.loan_type]))?;
+    crate::check_loan_amount(&loan_type, request.amount).map_err(|e| format!("amount: {}", e))?;
+    let amount = Decimal::from_f64(request.amount).ok_or_else(|| tr("amount must be a number").to_string())?;
+    if request.term_months == 0 || request.term_months > loan_type.get_max_term() {
+        return Err(trf("term_months must be between 1 and {}", &[&loan_type.get_max_term()]));
+    }
+    if !(300..=850).contains(&request.credit_score) {
+        return Err(tr("credit_score must be between 300 and 850").to_string());
+    }
+    let frequency = match &request.payment_frequency {
+        Some(name) => PaymentFrequency::from_name(name).ok_or_else(|| {
+            trf("unknown payment_frequency '{}' (expected weekly, bi-weekly, semi-monthly or monthly)", &[name])
+        })?,
+        None => PaymentFrequency::Monthly,
+    };
+    let (term_months, credit_score) = (request.term_months, request.credit_score);
+    let closing = Closing {
+        date: chrono::Local::now().date_naive(),
+        payment_day: PaymentDay::Day(1),
+        holidays: None,
+        stub_interest: StubInterest::Prepaid,
+    };
+    let interest_method = calculator.default_interest_method(&loan_type, &closing.calendar());
+    if frequency != PaymentFrequency::Monthly && interest_method != InterestMethod::Amortizing {
+        return Err(trf(
+            "payment_frequency: {} loans accrue daily simple interest here, so they are paid monthly",
+            &[&loan_type.get_name()],
+        ));
+    }
+
+    let mut warnings = Vec::new();
+    let mut declined = Vec::new();
+    let offers = crate::quote_banks(
+        calculator,
+        &loan_type,
+        amount,
+        term_months,
+        credit_score,
+        &mut declined,
+        &mut warnings,
+    );
+
+    let mut inputs = vec![
+        ("loan_type", loan_type.get_name().to_string()),
+        ("loan_amount", format!("{:.2}", amount.round_dp(2))),
+        ("term_months", term_months.to_string()),
+        ("credit_score", credit_score.to_string()),
+        ("interest_method", interest_method.get_description()),
+        ("payment_frequency", frequency.get_description().to_string()),
+        ("closing_date", closing.date.to_string()),
+    ];
+    if let Some(market_rate) = calculator.market_rate(&loan_type) {
+        inputs.push(("market_rate", market_rate.get_description()));
+    }
+    let provenance = calculator.provenance(inputs);
+    Ok(calculator.comparison_document(&results::ComparisonResults {
+        loan_type: &loan_type,
+        amount,
+        purchase: None,
+        housing: None,
+        deferment: None,
+        income: None,
+        tax_rate: None,
+        rate_quotes: &[],
+        term_months,
+        credit_score,
+        closing: &closing,
+        interest_method: &interest_method,
+        frequency,
+        offers: &offers,
+        declined: &declined,
+        filtered: &[],
+        warnings: &warnings,
+        provenance: &provenance,
+    }))
+}
+
+fn respond(calculator: &mut LoanCalculator, request: &mut Request) -> (u16, Value) {
+    let path = request.url().split('?').next().unwrap_or_default().to_string();
+    match (request.method(), path.as_str()) {
+        (Method::Post, "/compare") => {
+            let too_large = (413, json!({ "error": trf("the request is over {} bytes", &[&MAX_BODY_BYTES]) }));
+            if request.body_length().is_some_and(|length| length > MAX_BODY_BYTES) {
+                return too_large;
+            }
+            // A chunked body gives no length up front, so the read stops
+            // just past the limit as well
+            let mut body = String::new();
+            let mut reader = request.as_reader().take(MAX_BODY_BYTES as u64 + 1);
+            if let Err(e) = reader.read_to_string(&mut body) {
+                return (400, json!({ "error": trf("could not read the request: {}", &[&e]) }));
+            }
+            if body.len() > MAX_BODY_BYTES {
+                return too_large;
+            }
+            match compare(calculator, &body) {
+                Ok(document) => (200, document),
+                Err(e) => (400, json!({ "error": e })),
+            }
+        }
+        (_, "/compare") => (405, json!({ "error": tr("use POST /compare") })),
+        _ => (404, json!({ "error": trf("no endpoint {} (try POST /compare)", &[&path]) })),
+    }
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("static header")
+}
+
+// Answers one request at a time. Browsers on other origins may call it, so
+// preflight requests are answered and every response allows any origin.
+pub fn run(mut calculator: LoanCalculator, listen: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut warnings = Vec::new();
+    calculator.use_market_rates(false, &mut warnings);
+    for warning in &warnings {
+        eprintln!("{} {}", tr("Warning:").yellow().bold(), warning);
+    }
+
+    let server = Server::http(listen).map_err(|e| trf("could not listen on {}: {}", &[&listen, &e]))?;
+    eprintln!("{}", trf("Serving comparisons at http://{}/compare (Ctrl-C to stop)", &[&listen]));
+    for mut request in server.incoming_requests() {
+        let (status, body) = if *request.method() == Method::Options {
+            (204, Value::Null)
+        } else {
+            respond(&mut calculator, &mut request)
+        };
+        eprintln!("{} {} {}", request.method(), request.url(), status);
+
+        let text = if body.is_null() {
+            String::new()
+        } else {
+            serde_json::to_string_pretty(&body)?
+        };
+        let response = Response::from_string(text)
+            .with_status_code(status)
+            .with_header(header("Content-Type", "application/json"))
+            .with_header(header("Access-Control-Allow-Origin", "*"))
+            .with_header(header("Access-Control-Allow-Methods", "POST, OPTIONS"))
+            .with_header(header("Access-Control-Allow-Headers", "Content-Type"));
+        if let Err(e) = request.respond(response) {
+            eprintln!("{} {}", tr("Warning:").yellow().bold(), trf("could not answer the request: {}", &[&e]));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use rust_decimal::Decimal;
+
+use crate::dates::Calendar;
+use crate::provenance::Provenance;
+use crate::results::ComparisonResults;
+use crate::schedule::Schedule;
+use crate::LoanCalculator;
+
+// Numbers are written plain, without currency symbols or separators, so
+// spreadsheets read them as numbers
+fn number(value: Decimal) -> String {
+    format!("{:.2}", value.round_dp(2))
+}
+
+// The provenance goes above the header as `#` comment lines, which csv readers
+// skip when told to (pandas `comment="#"`, the csv crate's `comment(Some(b'#'))`)
+pub fn write_provenance<W: Write>(output: &mut W, provenance: &Provenance) -> std::io::Result<()> {
+    for (name, value) in provenance.entries() {
+        writeln!(output, "# {}: {}", name, value)?;
+    }
+    Ok(())
+}
+
+fn stamped_writer(path: &str, provenance: &Provenance) -> Result<csv::Writer<File>, Box<dyn std::error::Error>> {
+    let mut file = File::create(path)?;
+    write_provenance(&mut file, provenance)?;
+    Ok(csv::Writer::from_writer(file))
+}
+
+pub fn write_comparison_csv(
+    calculator: &LoanCalculator,
+    results: &ComparisonResults,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = stamped_writer(path, results.provenance)?;
+    writer.write_record([
+        "bank",
+        "rate",
+        "apr",
+        "monthly_payment",
+        "total_interest",
+        "total_payment",
+        "closing_costs",
+    ])?;
+    let method = results.interest_method;
+    for (name, rate) in results.offers {
+        let Some(cost) = calculator.calculate_cost(method, results.amount, *rate, results.term_months) else {
+            continue;
+        };
+        writer.write_record([
+            name.clone(),
+            rate.round_dp(4).to_string(),
+            calculator
+                .calculate_apr(method, name, results.loan_type, results.amount, *rate, results.term_months)
+                .map(|apr| apr.round_dp(4).to_string())
+                .unwrap_or_default(),
+            number(cost.monthly_payment),
+            number(cost.total_interest),
+            number(cost.total_payment),
+            number(calculator.get_closing_costs(name, results.loan_type)),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_schedule_csv(
+    schedule: &Schedule,
+    calendar: &Calendar,
+    provenance: &Provenance,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut writer = stamped_writer(path, provenance)?;
+    writer.write_record(["payment_number", "due_date", "payment", "principal", "interest", "balance"])?;
+    for (index, row) in schedule.rows.iter().enumerate() {
+        let month = index as u32 + 1;
+        writer.write_record([
+            month.to_string(),
+            calendar.payment_date(month).to_string(),
+            number(row.payment),
+            number(row.payment - row.interest),
+            number(row.interest),
+            number(row.balance),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// loans.csv keeps its schedule beside it in loans-schedule.csv
+pub fn schedule_path(comparison_path: &str) -> String {
+    let path = Path::new(comparison_path);
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("loans");
+    path.with_file_name(format!("{}-schedule.csv", stem))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provenance_lines_are_comments() {
+        let calculator = LoanCalculator::from_config(crate::config::DEFAULT_CONFIG).unwrap();
+        let provenance = calculator.provenance(vec![("term_months", "360".to_string())]);
+        let mut output = Vec::new();
+        write_provenance(&mut output, &provenance).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.lines().all(|line| line.starts_with("# ")));
+        assert!(text.contains(&format!("# config_sha256: {}\n", provenance.config_sha256)));
+        assert!(text.ends_with("# term_months: 360\n"));
+    }
+}
@@ -0,0 +1,65 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::interest::InterestMethod;
+use crate::LoanCalculator;
+
+// The usual grace period on federal loans
+pub const DEFAULT_GRACE_MONTHS: u32 = 6;
+
+// Months before repayment starts: the rest of school, then the grace
+// period. Interest still accrues, as simple interest on the amount
+// borrowed, and is capitalized (added to the principal) when repayment
+// begins.
+#[derive(Debug, Clone, Default)]
+pub struct Deferment {
+    pub in_school_months: u32,
+    pub grace_months: u32,
+}
+
+impl Deferment {
+    pub fn months(&self) -> u32 {
+        self.in_school_months + self.grace_months
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StudentRepayment {
+    pub accrued_interest: Decimal,
+    // What repayment amortizes once the accrued interest is capitalized
+    pub capitalized_principal: Decimal,
+    pub monthly_payment: Decimal,
+    // The payment had the accrued interest been paid off before repayment
+    pub uncapitalized_payment: Decimal,
+    // Accrued interest plus the interest paid during repayment
+    pub total_interest: Decimal,
+}
+
+pub fn accrued_interest(principal: Decimal, annual_rate: Decimal, deferment: &Deferment) -> Decimal {
+    (principal * annual_rate / dec!(100) * Decimal::from(deferment.months()) / dec!(12)).round_dp(2)
+}
+
+impl LoanCalculator {
+    // None when the repayment term cannot be quoted
+    pub fn calculate_student_repayment(
+        &self,
+        method: &InterestMethod,
+        principal: Decimal,
+        annual_rate: Decimal,
+        num_payments: u32,
+        deferment: &Deferment,
+    ) -> Option<StudentRepayment> {
+        let accrued_interest = accrued_interest(principal, annual_rate, deferment);
+        let capitalized_principal = principal + accrued_interest;
+        let capitalized = self.calculate_cost(method, capitalized_principal, annual_rate, num_payments)?;
+        let uncapitalized = self.calculate_cost(method, principal, annual_rate, num_payments)?;
+
+        Some(StudentRepayment {
+            accrued_interest,
+            capitalized_principal,
+            monthly_payment: capitalized.monthly_payment,
+            uncapitalized_payment: uncapitalized.monthly_payment,
+            total_interest: accrued_interest + capitalized.total_interest,
+        })
+    }
+}
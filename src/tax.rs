@@ -0,0 +1,82 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::payoff::PayoffProjection;
+
+// Interest is deductible on up to $750,000 of mortgage debt taken on since
+// 2018, and only for borrowers who itemize
+pub const DEDUCTIBLE_DEBT_LIMIT: Decimal = dec!(750000);
+
+/// What deducting a mortgage's interest saves at a marginal tax rate.
+#[derive(Debug, Clone)]
+pub struct TaxSavings {
+    pub deductible_interest: Decimal,
+    pub tax_saved: Decimal,
+    pub after_tax_interest: Decimal,
+    // The note rate less what the deduction gives back, to set beside the
+    // rate of debt whose interest is not deductible
+    pub after_tax_rate: Decimal,
+    // (year, tax saved on the interest due in it)
+    pub by_year: Vec<(i32, Decimal)>,
+}
+
+// Interest on the part of the loan above the limit is left out in
+// proportion, the simplified method the IRS allows
+pub fn tax_savings(
+    principal: Decimal,
+    annual_rate: Decimal,
+    projection: &PayoffProjection,
+    tax_rate: Decimal,
+) -> TaxSavings {
+    let deductible_share = if principal > DEDUCTIBLE_DEBT_LIMIT {
+        DEDUCTIBLE_DEBT_LIMIT / principal
+    } else {
+        dec!(1)
+    };
+    let by_year: Vec<(i32, Decimal)> = projection
+        .interest_by_year
+        .iter()
+        .map(|(year, interest)| (*year, (interest * deductible_share * tax_rate / dec!(100)).round_dp(2)))
+        .collect();
+    let total_interest: Decimal = projection.interest_by_year.iter().map(|(_, interest)| interest).sum();
+    let tax_saved: Decimal = by_year.iter().map(|(_, saved)| saved).sum();
+    TaxSavings {
+        deductible_interest: (total_interest * deductible_share).round_dp(2),
+        tax_saved,
+        after_tax_interest: total_interest - tax_saved,
+        after_tax_rate: annual_rate * (dec!(1) - deductible_share * tax_rate / dec!(100)),
+        by_year,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn projection(interest_by_year: Vec<(i32, Decimal)>) -> PayoffProjection {
+        PayoffProjection {
+            payoff_date: NaiveDate::from_ymd_opt(2056, 1, 1).unwrap(),
+            pmi_drops: None,
+            interest_by_year,
+        }
+    }
+
+    #[test]
+    fn interest_above_the_debt_limit_is_left_out_in_proportion() {
+        let projection = projection(vec![(2027, dec!(40000)), (2028, dec!(39000))]);
+        // 750,000 of 1,000,000 is deductible: 40,000 × 0.75 × 24% = 7,200
+        let savings = tax_savings(dec!(1000000), dec!(6), &projection, dec!(24));
+        assert_eq!(savings.by_year, vec![(2027, dec!(7200)), (2028, dec!(7020))]);
+        assert_eq!(savings.deductible_interest, dec!(59250));
+        assert_eq!(savings.tax_saved, dec!(14220));
+        assert_eq!(savings.after_tax_interest, dec!(64780));
+        // 6% less 0.75 × 24% of it
+        assert_eq!(savings.after_tax_rate, dec!(4.92));
+
+        // Under the limit all of it counts
+        let savings = tax_savings(dec!(500000), dec!(6), &projection, dec!(24));
+        assert_eq!(savings.by_year[0], (2027, dec!(9600)));
+        assert_eq!(savings.deductible_interest, dec!(79000));
+    }
+}
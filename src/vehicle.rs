@@ -0,0 +1,70 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::purchase::Purchase;
+
+/// What a dealer adds to and takes off a car's price before the loan:
+/// sales tax, the trade-in and the paperwork fees.
+#[derive(Debug, Clone, Default)]
+pub struct VehicleDeal {
+    // Percent, charged in most states on the price less the trade-in
+    pub sales_tax_rate: Decimal,
+    pub trade_in_value: Decimal,
+    // Still owed on the trade-in, which the dealer pays off; any of it above
+    // the trade-in's value is rolled into the new loan
+    pub trade_in_payoff: Decimal,
+    // Documentation, title and registration
+    pub dealer_fees: Decimal,
+}
+
+impl VehicleDeal {
+    pub fn sales_tax(&self, price: Decimal) -> Decimal {
+        ((price - self.trade_in_value).max(Decimal::ZERO) * self.sales_tax_rate / dec!(100)).round_dp(2)
+    }
+
+    // Negative when more is owed on the trade-in than it is worth
+    pub fn trade_in_equity(&self) -> Decimal {
+        self.trade_in_value - self.trade_in_payoff
+    }
+
+    // Tax and fees, less the trade-in's equity, on top of the price less the
+    // cash down
+    pub fn added_to_loan(&self, price: Decimal) -> Decimal {
+        self.sales_tax(price) + self.dealer_fees - self.trade_in_equity()
+    }
+
+    pub fn amount_financed(&self, purchase: &Purchase) -> Decimal {
+        purchase.financed_amount() + self.added_to_loan(purchase.price)
+    }
+
+    // Lenders measure a car loan against the car alone, so tax, fees and
+    // negative equity all count against it
+    pub fn loan_to_value(&self, purchase: &Purchase) -> Decimal {
+        self.amount_financed(purchase) / purchase.price * dec!(100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::purchase::DownPayment;
+
+    #[test]
+    fn sales_tax_is_charged_on_the_price_less_the_trade_in() {
+        let deal = VehicleDeal {
+            sales_tax_rate: dec!(7.25),
+            trade_in_value: dec!(8000),
+            trade_in_payoff: dec!(10000),
+            dealer_fees: dec!(500),
+        };
+        // 7.25% of 30,000 − 8,000
+        assert_eq!(deal.sales_tax(dec!(30000)), dec!(1595));
+        // The tax, the fees and the 2,000 still owed past the trade-in's value
+        assert_eq!(deal.added_to_loan(dec!(30000)), dec!(4095));
+        let purchase = Purchase::new(dec!(30000), DownPayment::Amount(dec!(3000))).unwrap();
+        assert_eq!(deal.amount_financed(&purchase), dec!(31095));
+        assert_eq!(deal.loan_to_value(&purchase), dec!(103.65));
+        // A trade-in worth more than the car leaves nothing to tax
+        assert_eq!(deal.sales_tax(dec!(6000)), dec!(0));
+    }
+}
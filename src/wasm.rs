@@ -0,0 +1,88 @@
+// JavaScript bindings, built with
+//   wasm-pack build --target web -- --features wasm
+// Amounts and rates cross as strings so nothing is rounded through a JS
+// number; results are strings to the cent, as in `--output json`.
+
+use rust_decimal::prelude::*;
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+
+use crate::config;
+use crate::schedule::PaymentStrategy;
+use crate::LoanCalculator;
+
+fn decimal(name: &str, value: &str) -> Result<Decimal, JsError> {
+    Decimal::from_str(value.trim()).map_err(|_| JsError::new(&format!("{} '{}' is not a number", name, value)))
+}
+
+/// The engine behind `loancalc`, loaded from a banks.yaml document or the
+/// built-in banks.
+#[wasm_bindgen]
+pub struct Calculator {
+    inner: LoanCalculator,
+}
+
+#[wasm_bindgen]
+impl Calculator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(banks_yaml: Option<String>) -> Result<Calculator, JsError> {
+        let inner = LoanCalculator::from_config(banks_yaml.as_deref().unwrap_or(config::DEFAULT_CONFIG))?;
+        Ok(Calculator { inner })
+    }
+
+    /// The level monthly payment, to the cent.
+    #[wasm_bindgen(js_name = monthlyPayment)]
+    pub fn monthly_payment(&self, principal: &str, annual_rate: &str, num_payments: u32) -> Result<String, JsError> {
+        let payment = crate::monthly_payment(
+            decimal("principal", principal)?,
+            decimal("annual rate", annual_rate)?,
+            num_payments,
+        )?;
+        Ok(format!("{:.2}", payment.round_dp(2)))
+    }
+
+    /// The month-by-month schedule as a JSON array of payment, principal,
+    /// interest and balance.
+    #[wasm_bindgen(js_name = amortizationSchedule)]
+    pub fn amortization_schedule(
+        &self,
+        principal: &str,
+        annual_rate: &str,
+        num_payments: u32,
+    ) -> Result<String, JsError> {
+        let principal = decimal("principal", principal)?;
+        let annual_rate = decimal("annual rate", annual_rate)?;
+        // Rejects the loans the schedule cannot be built for
+        crate::monthly_payment(principal, annual_rate, num_payments)?;
+        let schedule = self
+            .inner
+            .generate_schedule(principal, annual_rate, num_payments, &PaymentStrategy::Standard);
+        let rows: Vec<_> = schedule
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                json!({
+                    "month": index + 1,
+                    "payment": format!("{:.2}", row.payment.round_dp(2)),
+                    "principal": format!("{:.2}", row.payment.round_dp(2) - row.interest.round_dp(2)),
+                    "interest": format!("{:.2}", row.interest.round_dp(2)),
+                    "balance": format!("{:.2}", row.balance.round_dp(2)),
+                })
+            })
+            .collect();
+        Ok(serde_json::to_string(&rows)?)
+    }
+
+    /// A rate moved by the config's credit tiers for `credit_score`.
+    #[wasm_bindgen(js_name = adjustRateForCredit)]
+    pub fn adjust_rate_for_credit(&self, base_rate: &str, credit_score: u16) -> Result<String, JsError> {
+        if !(300..=850).contains(&credit_score) {
+            return Err(JsError::new("credit scores run from 300 to 850"));
+        }
+        Ok(self
+            .inner
+            .adjust_rate_for_credit(decimal("base rate", base_rate)?, credit_score)
+            .to_string())
+    }
+}